@@ -1,18 +1,16 @@
 mod validations;
 use crate::{
     charge,
-    constants::{BASE_FACTOR, FEE},
     contract::{self, Contract},
-    helpers::proportion_of,
     pay,
     token::tokens::USD,
-    Token,
+    Parameters, Token,
 };
 use anyhow::{anyhow, bail, Result};
 use ellipticoin_macros::db_accessors;
 use ellipticoin_types::{
     db::{Backend, Db},
-    Address,
+    Address, Amount,
 };
 use linked_hash_set::LinkedHashSet;
 use std::cmp::max;
@@ -24,15 +22,18 @@ impl Contract for AMM {
 }
 
 db_accessors!(AMM {
-    balance(address: Address, token: Address) -> u64;
-    total_supply(token: Address) -> u64;
-    pool_supply_of_usd(token: Address) -> u64;
-    pool_supply_of_token(token: Address) -> u64;
+    balance(address: Address, token: Address) -> Amount;
+    total_supply(token: Address) -> Amount;
+    pool_supply_of_usd(token: Address) -> Amount;
+    pool_supply_of_token(token: Address) -> Amount;
     liquidity_providers(token: Address) -> LinkedHashSet<Address>;
 });
 
 impl AMM {
-    pub fn get_underlying_pool_supply_of_usd<B: Backend>(db: &mut Db<B>, token: Address) -> u64 {
+    pub fn get_underlying_pool_supply_of_usd<B: Backend>(
+        db: &mut Db<B>,
+        token: Address,
+    ) -> Result<Amount> {
         let pool_supply_of_usd = Self::get_pool_supply_of_usd(db, token);
         Token::amount_to_underlying(db, pool_supply_of_usd, USD)
     }
@@ -40,11 +41,11 @@ impl AMM {
     pub fn create_pool<B: Backend>(
         db: &mut Db<B>,
         sender: Address,
-        amount: u64,
+        amount: Amount,
         token: Address,
-        starting_price: u64,
+        starting_price: Amount,
     ) -> Result<()> {
-        let usd_amount = proportion_of(amount, starting_price, BASE_FACTOR);
+        let usd_amount = amount.mul_fixed(starting_price)?;
         Self::validate_pool_does_not_exist(db, token)?;
         Self::charge(db, sender, token, amount)?;
         Self::charge_usd(db, sender, token, usd_amount)?;
@@ -55,7 +56,7 @@ impl AMM {
     pub fn add_liquidity<B: Backend>(
         db: &mut Db<B>,
         sender: Address,
-        amount: u64,
+        amount: Amount,
         token: Address,
     ) -> Result<()> {
         Self::validate_pool_exists(db, token)?;
@@ -67,18 +68,14 @@ impl AMM {
             db,
             sender,
             token,
-            proportion_of(
-                amount,
-                total_supply_of_liquidity_token,
-                pool_supply_of_token,
-            ),
+            amount.proportion_of(total_supply_of_liquidity_token, pool_supply_of_token)?,
         )?;
         Self::charge(db, sender, token, amount)?;
         Self::charge_usd(
             db,
             sender,
             token,
-            proportion_of(amount, pool_supply_of_usd, pool_supply_of_token),
+            amount.proportion_of(pool_supply_of_usd, pool_supply_of_token)?,
         )?;
 
         Ok(())
@@ -87,35 +84,27 @@ impl AMM {
     pub fn remove_liquidity<B: Backend>(
         db: &mut Db<B>,
         sender: Address,
-        percentage: u64,
+        percentage: Amount,
         token: Address,
     ) -> Result<()> {
         let liquidity_token_balance = Self::get_balance(db, sender, token);
         let total_supply_of_liquidity_token = Self::get_total_supply(db, token);
         let pool_supply_of_token = Self::get_pool_supply_of_token(db, token);
         let pool_supply_of_usd = Self::get_pool_supply_of_usd(db, token);
-        let amount_to_burn = proportion_of(liquidity_token_balance, percentage, BASE_FACTOR);
+        let amount_to_burn = liquidity_token_balance.mul_fixed(percentage)?;
 
         Self::burn_liquidity(db, sender, token, amount_to_burn)?;
         Self::pay_usd(
             db,
             sender,
             token,
-            proportion_of(
-                amount_to_burn,
-                pool_supply_of_usd,
-                total_supply_of_liquidity_token,
-            ),
+            amount_to_burn.proportion_of(pool_supply_of_usd, total_supply_of_liquidity_token)?,
         )?;
         Self::pay(
             db,
             sender,
             token,
-            proportion_of(
-                amount_to_burn,
-                pool_supply_of_token,
-                total_supply_of_liquidity_token,
-            ),
+            amount_to_burn.proportion_of(pool_supply_of_token, total_supply_of_liquidity_token)?,
         )?;
         Ok(())
     }
@@ -123,9 +112,9 @@ impl AMM {
     pub fn sell<B: Backend>(
         db: &mut Db<B>,
         sender: Address,
-        amount: u64,
+        amount: Amount,
         token: Address,
-        minimum_output_amount: u64,
+        minimum_output_amount: Amount,
     ) -> Result<()> {
         charge!(db, sender, token, amount)?;
         Self::validate_token_is_not_usd(token)?;
@@ -133,10 +122,10 @@ impl AMM {
         let output_amount = Self::calculate_output_amount(
             Self::get_pool_supply_of_token(db, token),
             Self::get_pool_supply_of_usd(db, token),
-            amount - Self::fee(amount)?,
-        );
+            amount.checked_sub(Self::fee(db, amount)?)?,
+        )?;
         Self::debit_pool_supply_of_usd(db, token, output_amount)?;
-        Self::credit_pool_supply_of_token(db, token, amount);
+        Self::credit_pool_supply_of_token(db, token, amount)?;
         Self::validate_slippage(minimum_output_amount, output_amount)?;
         pay!(db, sender, USD, output_amount)?;
         Ok(())
@@ -145,9 +134,9 @@ impl AMM {
     pub fn buy<B: Backend>(
         db: &mut Db<B>,
         sender: Address,
-        amount: u64,
+        amount: Amount,
         token: Address,
-        minimum_output_amount: u64,
+        minimum_output_amount: Amount,
     ) -> Result<()> {
         charge!(db, sender, USD, amount)?;
         Self::validate_token_is_not_usd(token)?;
@@ -155,26 +144,29 @@ impl AMM {
         let output_amount = Self::calculate_output_amount(
             Self::get_pool_supply_of_usd(db, token),
             Self::get_pool_supply_of_token(db, token),
-            amount - Self::fee(amount)?,
-        );
+            amount.checked_sub(Self::fee(db, amount)?)?,
+        )?;
         Self::debit_pool_supply_of_token(db, token, output_amount)?;
-        Self::credit_pool_supply_of_usd(db, token, amount);
+        Self::credit_pool_supply_of_usd(db, token, amount)?;
         Self::validate_slippage(minimum_output_amount, output_amount)?;
         pay!(db, sender, token, output_amount)?;
         Ok(())
     }
 
-    fn calculate_output_amount(input_supply: u64, output_supply: u64, input_amount: u64) -> u64 {
-        let new_input_supply = input_supply as u128 + input_amount as u128;
-        let invariant = input_supply as u128 * output_supply as u128;
-        let new_output_supply = (invariant as u128 / new_input_supply as u128) as u64;
-        output_supply - new_output_supply
+    fn calculate_output_amount(
+        input_supply: Amount,
+        output_supply: Amount,
+        input_amount: Amount,
+    ) -> Result<Amount> {
+        let new_input_supply = input_supply.checked_add(input_amount)?;
+        let new_output_supply = input_supply.proportion_of(output_supply, new_input_supply)?;
+        Ok(output_supply.checked_sub(new_output_supply)?)
     }
 
-    fn fee(amount: u64) -> Result<u64> {
+    fn fee<B: Backend>(db: &mut Db<B>, amount: Amount) -> Result<Amount> {
         let fee = max(
-            ((amount as u128 * FEE as u128) / BASE_FACTOR as u128) as u64,
-            1u64,
+            amount.mul_fixed(Amount::from(Parameters::get_fee(db)))?,
+            Amount::from(1),
         );
         if fee < amount {
             Ok(fee)
@@ -187,29 +179,27 @@ impl AMM {
         db: &mut Db<B>,
         address: Address,
         token: Address,
-        amount: u64,
+        amount: Amount,
     ) -> Result<()> {
         charge!(db, address, token, amount)?;
-        Self::credit_pool_supply_of_token(db, token, amount);
-        Ok(())
+        Self::credit_pool_supply_of_token(db, token, amount)
     }
 
     fn charge_usd<B: Backend>(
         db: &mut Db<B>,
         address: Address,
         token: Address,
-        amount: u64,
+        amount: Amount,
     ) -> Result<()> {
         charge!(db, address, USD, amount)?;
-        Self::credit_pool_supply_of_usd(db, token, amount);
-        Ok(())
+        Self::credit_pool_supply_of_usd(db, token, amount)
     }
 
     fn pay<B: Backend>(
         db: &mut Db<B>,
         address: Address,
         token: Address,
-        amount: u64,
+        amount: Amount,
     ) -> Result<()> {
         Self::debit_pool_supply_of_token(db, token, amount)?;
         pay!(db, address, token, amount)?;
@@ -220,31 +210,41 @@ impl AMM {
         db: &mut Db<B>,
         address: Address,
         token: Address,
-        amount: u64,
+        amount: Amount,
     ) -> Result<()> {
         Self::debit_pool_supply_of_usd(db, token, amount)?;
         pay!(db, address, USD, amount)?;
         Ok(())
     }
 
-    fn credit_pool_supply_of_usd<B: Backend>(db: &mut Db<B>, token: Address, amount: u64) {
+    fn credit_pool_supply_of_usd<B: Backend>(
+        db: &mut Db<B>,
+        token: Address,
+        amount: Amount,
+    ) -> Result<()> {
         let usd_supply = Self::get_pool_supply_of_usd(db, token);
-        Self::set_pool_supply_of_usd(db, token, usd_supply + amount);
+        Self::set_pool_supply_of_usd(db, token, usd_supply.checked_add(amount)?);
+        Ok(())
     }
 
-    fn credit_pool_supply_of_token<B: Backend>(db: &mut Db<B>, token: Address, amount: u64) {
+    fn credit_pool_supply_of_token<B: Backend>(
+        db: &mut Db<B>,
+        token: Address,
+        amount: Amount,
+    ) -> Result<()> {
         let token_supply = Self::get_pool_supply_of_token(db, token);
-        Self::set_pool_supply_of_token(db, token, token_supply + amount);
+        Self::set_pool_supply_of_token(db, token, token_supply.checked_add(amount)?);
+        Ok(())
     }
 
     fn debit_pool_supply_of_usd<B: Backend>(
         db: &mut Db<B>,
         token: Address,
-        amount: u64,
+        amount: Amount,
     ) -> Result<()> {
         let usd_supply = Self::get_pool_supply_of_usd(db, token);
         if usd_supply >= amount {
-            Self::set_pool_supply_of_usd(db, token, usd_supply - amount);
+            Self::set_pool_supply_of_usd(db, token, usd_supply.checked_sub(amount)?);
         } else {
             bail!("Insufficient balance")
         };
@@ -254,11 +254,11 @@ impl AMM {
     fn debit_pool_supply_of_token<B: Backend>(
         db: &mut Db<B>,
         token: Address,
-        amount: u64,
+        amount: Amount,
     ) -> Result<()> {
         let token_supply = Self::get_pool_supply_of_token(db, token);
         if token_supply >= amount {
-            Self::set_pool_supply_of_token(db, token, token_supply - amount);
+            Self::set_pool_supply_of_token(db, token, token_supply.checked_sub(amount)?);
         } else {
             bail!("Insufficient balance")
         };
@@ -269,9 +269,9 @@ impl AMM {
         db: &mut Db<B>,
         sender: Address,
         token: Address,
-        amount: u64,
+        amount: Amount,
     ) -> Result<()> {
-        Self::mint(db, amount, token, sender);
+        Self::mint(db, amount, token, sender)?;
         let mut liquidity_providers = Self::get_liquidity_providers(db, token);
         liquidity_providers.insert(sender);
         Self::set_liquidity_providers(db, token, liquidity_providers);
@@ -282,7 +282,7 @@ impl AMM {
         db: &mut Db<B>,
         sender: Address,
         token: Address,
-        amount: u64,
+        amount: Amount,
     ) -> Result<()> {
         Self::burn(db, amount, token, sender)?;
         if Self::get_balance(db, sender, token) == 0 {
@@ -293,21 +293,27 @@ impl AMM {
         Ok(())
     }
 
-    pub fn mint<B: Backend>(db: &mut Db<B>, amount: u64, token: Address, address: Address) {
-        Self::credit(db, amount, token, address);
+    pub fn mint<B: Backend>(
+        db: &mut Db<B>,
+        amount: Amount,
+        token: Address,
+        address: Address,
+    ) -> Result<()> {
+        Self::credit(db, amount, token, address)?;
         let total_supply = Self::get_total_supply(db, token);
-        Self::set_total_supply(db, token, total_supply + amount);
+        Self::set_total_supply(db, token, total_supply.checked_add(amount)?);
+        Ok(())
     }
 
     pub fn burn<B: Backend>(
         db: &mut Db<B>,
-        amount: u64,
+        amount: Amount,
         token: Address,
         address: Address,
     ) -> Result<()> {
         Self::debit(db, amount, token, address)?;
         let total_supply = Self::get_total_supply(db, token);
-        Self::set_total_supply(db, token, total_supply - amount);
+        Self::set_total_supply(db, token, total_supply.checked_sub(amount)?);
         Ok(())
     }
 
@@ -315,29 +321,39 @@ impl AMM {
         db: &mut Db<B>,
         sender: Address,
         recipient: Address,
-        amount: u64,
+        amount: Amount,
         token: Address,
     ) -> Result<()> {
         Self::debit(db, amount, token, sender)?;
-        Self::credit(db, amount, token, recipient);
-        Ok(())
+        Self::credit(db, amount, token, recipient)
     }
 
-    pub fn credit<B: Backend>(db: &mut Db<B>, amount: u64, token: Address, address: Address) {
+    pub fn credit<B: Backend>(
+        db: &mut Db<B>,
+        amount: Amount,
+        token: Address,
+        address: Address,
+    ) -> Result<()> {
         let balance = Self::get_balance(db, address, token);
-        Self::set_balance(db, address, token, balance + amount)
+        Self::set_balance(db, address, token, balance.checked_add(amount)?);
+        Ok(())
     }
 
     fn debit<B: Backend>(
         db: &mut Db<B>,
-        amount: u64,
+        amount: Amount,
         token: Address,
         address: Address,
     ) -> Result<()> {
         let balance = Self::get_balance(db, address, token);
 
         if amount <= balance {
-            Ok(Self::set_balance(db, address, token, balance - amount))
+            Ok(Self::set_balance(
+                db,
+                address,
+                token,
+                balance.checked_sub(amount)?,
+            ))
         } else {
             bail!(
                 "{} has insufficient balance of {} have {} need {}",
@@ -377,7 +393,14 @@ mod tests {
             },
         );
 
-        AMM::create_pool(&mut db, ALICE, 1, APPLES, BASE_FACTOR).unwrap();
+        AMM::create_pool(
+            &mut db,
+            ALICE,
+            Amount::from(1),
+            APPLES,
+            Amount::from(BASE_FACTOR),
+        )
+        .unwrap();
 
         assert_eq!(AMM::get_balance(&mut db, ALICE, APPLES), 1);
         assert_eq!(
@@ -401,12 +424,25 @@ mod tests {
                 ],
             },
         );
-        AMM::create_pool(&mut db, ALICE, 1, APPLES, BASE_FACTOR).unwrap();
+        AMM::create_pool(
+            &mut db,
+            ALICE,
+            Amount::from(1),
+            APPLES,
+            Amount::from(BASE_FACTOR),
+        )
+        .unwrap();
         assert_eq!(
-            AMM::create_pool(&mut db, ALICE, 1, APPLES, BASE_FACTOR)
-                .err()
-                .unwrap()
-                .to_string(),
+            AMM::create_pool(
+                &mut db,
+                ALICE,
+                Amount::from(1),
+                APPLES,
+                Amount::from(BASE_FACTOR)
+            )
+            .err()
+            .unwrap()
+            .to_string(),
             "Pool already exisits: a000000000000000000000000000000000000000"
         );
 
@@ -434,7 +470,7 @@ mod tests {
         );
 
         assert_eq!(
-            AMM::create_pool(&mut db, ALICE, 2, APPLES, BASE_FACTOR / 2)
+            AMM::create_pool(&mut db, ALICE, Amount::from(2), APPLES, Amount::from(BASE_FACTOR / 2))
                 .err()
                 .unwrap()
                 .to_string(),
@@ -467,7 +503,7 @@ mod tests {
         );
 
         assert_eq!(
-            AMM::create_pool(&mut db, ALICE, 2, APPLES, BASE_FACTOR * 2)
+            AMM::create_pool(&mut db, ALICE, Amount::from(2), APPLES, Amount::from(BASE_FACTOR * 2))
                 .err()
                 .unwrap()
                 .to_string(),
@@ -500,8 +536,15 @@ mod tests {
             },
         );
 
-        AMM::create_pool(&mut db, ALICE, 1, APPLES, BASE_FACTOR).unwrap();
-        AMM::add_liquidity(&mut db, ALICE, 1, APPLES).unwrap();
+        AMM::create_pool(
+            &mut db,
+            ALICE,
+            Amount::from(1),
+            APPLES,
+            Amount::from(BASE_FACTOR),
+        )
+        .unwrap();
+        AMM::add_liquidity(&mut db, ALICE, Amount::from(1), APPLES).unwrap();
 
         assert_eq!(AMM::get_balance(&mut db, ALICE, APPLES), 2);
         assert_eq!(
@@ -526,9 +569,16 @@ mod tests {
             },
         );
 
-        AMM::create_pool(&mut db, ALICE, BASE_FACTOR, APPLES, BASE_FACTOR).unwrap();
-        AMM::add_liquidity(&mut db, ALICE, BASE_FACTOR, APPLES).unwrap();
-        AMM::add_liquidity(&mut db, ALICE, BASE_FACTOR, APPLES).unwrap();
+        AMM::create_pool(
+            &mut db,
+            ALICE,
+            Amount::from(BASE_FACTOR),
+            APPLES,
+            Amount::from(BASE_FACTOR),
+        )
+        .unwrap();
+        AMM::add_liquidity(&mut db, ALICE, Amount::from(BASE_FACTOR), APPLES).unwrap();
+        AMM::add_liquidity(&mut db, ALICE, Amount::from(BASE_FACTOR), APPLES).unwrap();
 
         assert_eq!(AMM::get_balance(&mut db, ALICE, APPLES), 3 * BASE_FACTOR);
         assert_eq!(
@@ -553,9 +603,16 @@ mod tests {
             },
         );
 
-        AMM::create_pool(&mut db, ALICE, BASE_FACTOR, APPLES, BASE_FACTOR).unwrap();
-        AMM::add_liquidity(&mut db, ALICE, BASE_FACTOR, APPLES).unwrap();
-        AMM::remove_liquidity(&mut db, ALICE, BASE_FACTOR / 2, APPLES).unwrap();
+        AMM::create_pool(
+            &mut db,
+            ALICE,
+            Amount::from(BASE_FACTOR),
+            APPLES,
+            Amount::from(BASE_FACTOR),
+        )
+        .unwrap();
+        AMM::add_liquidity(&mut db, ALICE, Amount::from(BASE_FACTOR), APPLES).unwrap();
+        AMM::remove_liquidity(&mut db, ALICE, Amount::from(BASE_FACTOR / 2), APPLES).unwrap();
 
         assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 2 * BASE_FACTOR);
         assert_eq!(Token::get_balance(&mut db, ALICE, USD), 2 * BASE_FACTOR);
@@ -585,10 +642,31 @@ mod tests {
                 ],
             },
         );
-        AMM::create_pool(&mut db, ALICE, 100 * BASE_FACTOR, APPLES, BASE_FACTOR).unwrap();
-        AMM::create_pool(&mut db, ALICE, 100 * BASE_FACTOR, BANANAS, BASE_FACTOR).unwrap();
-        AMM::sell(&mut db, BOB, 100 * BASE_FACTOR, BANANAS, 0).unwrap();
-        AMM::buy(&mut db, BOB, 49924888, APPLES, 0).unwrap();
+        AMM::create_pool(
+            &mut db,
+            ALICE,
+            Amount::from(100 * BASE_FACTOR),
+            APPLES,
+            Amount::from(BASE_FACTOR),
+        )
+        .unwrap();
+        AMM::create_pool(
+            &mut db,
+            ALICE,
+            Amount::from(100 * BASE_FACTOR),
+            BANANAS,
+            Amount::from(BASE_FACTOR),
+        )
+        .unwrap();
+        AMM::sell(
+            &mut db,
+            BOB,
+            Amount::from(100 * BASE_FACTOR),
+            BANANAS,
+            Amount::ZERO,
+        )
+        .unwrap();
+        AMM::buy(&mut db, BOB, Amount::from(49924888), APPLES, Amount::ZERO).unwrap();
         assert_eq!(Token::get_balance(&mut db, BOB, APPLES), 33233234);
     }
 
@@ -608,9 +686,16 @@ mod tests {
                 ],
             },
         );
-        AMM::create_pool(&mut db, ALICE, 100 * BASE_FACTOR, APPLES, BASE_FACTOR).unwrap();
+        AMM::create_pool(
+            &mut db,
+            ALICE,
+            Amount::from(100 * BASE_FACTOR),
+            APPLES,
+            Amount::from(BASE_FACTOR),
+        )
+        .unwrap();
         assert_eq!(
-            AMM::buy(&mut db, BOB, 1, APPLES, 0)
+            AMM::buy(&mut db, BOB, Amount::from(1), APPLES, Amount::ZERO)
                 .err()
                 .unwrap()
                 .to_string(),
@@ -637,13 +722,20 @@ mod tests {
         AMM::create_pool(
             &mut db,
             ALICE,
-            100 * BASE_FACTOR,
+            Amount::from(100 * BASE_FACTOR),
             APPLES.clone(),
-            BASE_FACTOR,
+            Amount::from(BASE_FACTOR),
         )
         .unwrap();
 
-        AMM::buy(&mut db, BOB, 100 * BASE_FACTOR, APPLES.clone(), 0).unwrap();
+        AMM::buy(
+            &mut db,
+            BOB,
+            Amount::from(100 * BASE_FACTOR),
+            APPLES.clone(),
+            Amount::ZERO,
+        )
+        .unwrap();
         assert_eq!(
             Token::get_balance(&mut db, BOB, APPLES.clone(),),
             49_924_888
@@ -668,12 +760,19 @@ mod tests {
         AMM::create_pool(
             &mut db,
             ALICE,
-            100 * BASE_FACTOR,
+            Amount::from(100 * BASE_FACTOR),
             APPLES.clone(),
-            BASE_FACTOR,
+            Amount::from(BASE_FACTOR),
+        )
+        .unwrap();
+        AMM::sell(
+            &mut db,
+            BOB,
+            Amount::from(100 * BASE_FACTOR),
+            APPLES.clone(),
+            Amount::ZERO,
         )
         .unwrap();
-        AMM::sell(&mut db, BOB, 100 * BASE_FACTOR, APPLES.clone(), 0).unwrap();
         assert_eq!(Token::get_balance(&mut db, BOB, USD.clone()), 49_924_888);
     }
 
@@ -696,17 +795,17 @@ mod tests {
         AMM::create_pool(
             &mut db,
             ALICE,
-            100 * BASE_FACTOR,
+            Amount::from(100 * BASE_FACTOR),
             APPLES.clone(),
-            BASE_FACTOR,
+            Amount::from(BASE_FACTOR),
         )
         .unwrap();
         AMM::create_pool(
             &mut db,
             ALICE,
-            100 * BASE_FACTOR,
+            Amount::from(100 * BASE_FACTOR),
             BANANAS.clone(),
-            BASE_FACTOR,
+            Amount::from(BASE_FACTOR),
         )
         .unwrap();
 
@@ -745,25 +844,42 @@ mod tests {
         AMM::create_pool(
             &mut db,
             ALICE,
-            100_000 * BASE_FACTOR,
+            Amount::from(100_000 * BASE_FACTOR),
             APPLES.clone(),
-            BASE_FACTOR / 100,
+            Amount::from(BASE_FACTOR / 100),
         )
         .unwrap();
 
-        AMM::sell(&mut db, BOB, 100 * BASE_FACTOR, APPLES.clone(), 0).unwrap();
+        AMM::sell(
+            &mut db,
+            BOB,
+            Amount::from(100 * BASE_FACTOR),
+            APPLES.clone(),
+            Amount::ZERO,
+        )
+        .unwrap();
         assert_eq!(Token::get_balance(&mut db, BOB, USD.clone(),), 996_007);
-        AMM::buy(&mut db, BOB, 996_007, APPLES.clone(), 0).unwrap();
+        AMM::buy(
+            &mut db,
+            BOB,
+            Amount::from(996_007),
+            APPLES.clone(),
+            Amount::ZERO,
+        )
+        .unwrap();
         assert_eq!(
             Token::get_balance(&mut db, BOB, APPLES.clone(),),
             99_999_401_499
         );
         assert_eq!(Token::get_balance(&mut db, BOB, USD.clone(),), 0);
 
-        AMM::remove_liquidity(&mut db, ALICE, BASE_FACTOR, APPLES).unwrap();
+        AMM::remove_liquidity(&mut db, ALICE, Amount::from(BASE_FACTOR), APPLES).unwrap();
         let alices_apples = Token::get_balance(&mut db, ALICE, APPLES);
         let bobs_apples = Token::get_balance(&mut db, BOB, APPLES);
-        assert_eq!(alices_apples + bobs_apples, 200_000 * BASE_FACTOR);
+        assert_eq!(
+            alices_apples.checked_add(bobs_apples).unwrap(),
+            200_000 * BASE_FACTOR
+        );
     }
 
     #[test]
@@ -782,18 +898,42 @@ mod tests {
                 ],
             },
         );
-        AMM::create_pool(&mut db, ALICE, 100 * BASE_FACTOR, APPLES, BASE_FACTOR).unwrap();
-        AMM::create_pool(&mut db, ALICE, 100 * BASE_FACTOR, BANANAS, BASE_FACTOR).unwrap();
-        AMM::sell(&mut db, BOB, 100 * BASE_FACTOR, BANANAS, 0).unwrap();
-        AMM::buy(&mut db, BOB, 49924888, APPLES, 0).unwrap();
+        AMM::create_pool(
+            &mut db,
+            ALICE,
+            Amount::from(100 * BASE_FACTOR),
+            APPLES,
+            Amount::from(BASE_FACTOR),
+        )
+        .unwrap();
+        AMM::create_pool(
+            &mut db,
+            ALICE,
+            Amount::from(100 * BASE_FACTOR),
+            BANANAS,
+            Amount::from(BASE_FACTOR),
+        )
+        .unwrap();
+        AMM::sell(
+            &mut db,
+            BOB,
+            Amount::from(100 * BASE_FACTOR),
+            BANANAS,
+            Amount::ZERO,
+        )
+        .unwrap();
+        AMM::buy(&mut db, BOB, Amount::from(49924888), APPLES, Amount::ZERO).unwrap();
 
-        AMM::remove_liquidity(&mut db, ALICE, BASE_FACTOR, APPLES).unwrap();
+        AMM::remove_liquidity(&mut db, ALICE, Amount::from(BASE_FACTOR), APPLES).unwrap();
         let alices_apples = Token::get_balance(&mut db, ALICE, APPLES);
         let alices_usds = Token::get_balance(&mut db, ALICE, USD);
         assert_eq!(alices_apples, 66766766);
         assert_eq!(alices_usds, 149924888);
         let bobs_apples = Token::get_balance(&mut db, BOB, APPLES);
         assert_eq!(bobs_apples, 33233234);
-        assert_eq!(alices_apples + bobs_apples, 100 * BASE_FACTOR);
+        assert_eq!(
+            alices_apples.checked_add(bobs_apples).unwrap(),
+            100 * BASE_FACTOR
+        );
     }
 }
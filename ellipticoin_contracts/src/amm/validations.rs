@@ -3,7 +3,7 @@ use crate::token::tokens::USD;
 use anyhow::{bail, Result};
 use ellipticoin_types::{
     db::{Backend, Db},
-    Address,
+    Address, Amount,
 };
 impl AMM {
     pub fn validate_pool_does_not_exist<B: Backend>(db: &mut Db<B>, token: Address) -> Result<()> {
@@ -15,7 +15,7 @@ impl AMM {
     }
 
     pub fn validate_pool_exists<B: Backend>(db: &mut Db<B>, token: Address) -> Result<()> {
-        if Self::get_pool_supply_of_token(db, token.clone()) > 0 {
+        if Self::get_pool_supply_of_token(db, token.clone()) > Amount::ZERO {
             Ok(())
         } else {
             bail!("Pool does not exisit: {}", hex::encode(token))
@@ -23,8 +23,8 @@ impl AMM {
     }
 
     pub fn validate_slippage(
-        minimum_output_token_amount: u64,
-        output_token_amount: u64,
+        minimum_output_token_amount: Amount,
+        output_token_amount: Amount,
     ) -> Result<()> {
         if output_token_amount >= minimum_output_token_amount {
             Ok(())
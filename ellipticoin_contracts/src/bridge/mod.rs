@@ -3,11 +3,11 @@ use crate::{
     contract::{self, Contract},
     token::Token,
 };
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use ellipticoin_macros::db_accessors;
 use ellipticoin_types::{
     db::{Backend, Db},
-    Address,
+    Address, Amount, U256,
 };
 use num_bigint::BigUint;
 use serde::{Deserialize, Serialize};
@@ -15,7 +15,7 @@ use std::convert::TryInto;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum PolygonMessage {
-    Deposit(u64, Address, Address),
+    Deposit(U256, Address, Address),
     ProcessWithdrawl(u64, [u8; 32]),
 }
 
@@ -29,14 +29,14 @@ pub struct PendingWithdrawl {
     pub id: u64,
     pub to: Address,
     pub token: Address,
-    pub amount: u64,
+    pub amount: Amount,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct CompletedWithdrawl {
     pub to: Address,
     pub token: Address,
-    pub amount: u64,
+    pub amount: Amount,
     pub transaction_hash: [u8; 32],
 }
 
@@ -62,8 +62,9 @@ impl Bridge {
     ) -> Result<()> {
         for message in messages {
             match message {
-                PolygonMessage::Deposit(amount, token, address) => {
-                    Token::mint(db, amount, token, address)
+                PolygonMessage::Deposit(native_amount, token, address) => {
+                    let amount = Token::native_to_amount(db, native_amount, token)?;
+                    Token::mint(db, amount, token, address)?
                 }
                 PolygonMessage::ProcessWithdrawl(withdrawl_id, transaction_hash) => {
                     let mut pending_withdrawls = Self::get_pending_withdrawls(db);
@@ -110,9 +111,16 @@ impl Bridge {
     pub fn create_withdrawl_request<B: Backend>(
         db: &mut Db<B>,
         to: Address,
-        amount: u64,
+        amount: Amount,
         token: Address,
     ) -> Result<()> {
+        // Withdrawls are paid out in the token's native precision so any
+        // dust below that precision stays in the sender's balance.
+        let native_amount = Token::amount_to_native(db, amount, token)?;
+        let amount = Token::native_to_amount(db, native_amount, token)?;
+        if amount == Amount::ZERO {
+            bail!("Withdrawl amount is less than the smallest unit of the token")
+        }
         charge!(db, to, token, amount)?;
         let mut pending_withdrawls = Self::get_pending_withdrawls(db);
         pending_withdrawls.push(PendingWithdrawl {
@@ -137,22 +145,23 @@ impl Bridge {
 #[cfg(test)]
 mod tests {
     use super::{Bridge, PolygonMessage};
-    use crate::{constants::BASE_FACTOR, Token};
-    use ellipticoin_test_framework::{
-        constants::{actors::ALICE, tokens::APPLES},
-        new_db,
-    };
+    use crate::{token::tokens::WBTC, Token};
+    use ellipticoin_test_framework::{constants::actors::ALICE, new_db};
+    use ellipticoin_types::{Amount, U256};
 
     #[test]
     fn test_deposit() {
         let mut db = new_db();
         Bridge::process_polygon_messages(
             &mut db,
-            vec![PolygonMessage::Deposit(1 * BASE_FACTOR, APPLES, ALICE)],
+            vec![PolygonMessage::Deposit(U256::exp10(8), WBTC, ALICE)],
             1,
         )
         .unwrap();
-        assert_eq!(Token::get_balance(&mut db, ALICE, APPLES,), 1 * BASE_FACTOR);
+        assert_eq!(
+            Token::get_balance(&mut db, ALICE, WBTC),
+            Amount(U256::exp10(18))
+        );
     }
 
     #[test]
@@ -160,17 +169,43 @@ mod tests {
         let mut db = new_db();
         Bridge::process_polygon_messages(
             &mut db,
-            vec![PolygonMessage::Deposit(1 * BASE_FACTOR, APPLES, ALICE)],
+            vec![PolygonMessage::Deposit(U256::exp10(8), WBTC, ALICE)],
             1,
         )
         .unwrap();
-        Bridge::create_withdrawl_request(&mut db, ALICE, 1 * BASE_FACTOR, APPLES).unwrap();
+        Bridge::create_withdrawl_request(&mut db, ALICE, Amount(U256::exp10(18)), WBTC).unwrap();
         Bridge::process_polygon_messages(
             &mut db,
             vec![PolygonMessage::ProcessWithdrawl(0, [0; 32])],
             1,
         )
         .unwrap();
-        assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 0);
+        assert_eq!(Token::get_balance(&mut db, ALICE, WBTC), 0);
+    }
+
+    #[test]
+    fn test_withdrawl_leaves_dust() {
+        let mut db = new_db();
+        Token::set_balance(
+            &mut db,
+            ALICE,
+            WBTC,
+            Amount(U256::exp10(10).checked_add(U256::from(1)).unwrap()),
+        );
+        Bridge::create_withdrawl_request(
+            &mut db,
+            ALICE,
+            Amount(U256::exp10(10).checked_add(U256::from(1)).unwrap()),
+            WBTC,
+        )
+        .unwrap();
+        assert_eq!(Token::get_balance(&mut db, ALICE, WBTC), 1);
+        assert_eq!(
+            Bridge::create_withdrawl_request(&mut db, ALICE, Amount::from(1), WBTC)
+                .err()
+                .unwrap()
+                .to_string(),
+            "Withdrawl amount is less than the smallest unit of the token"
+        );
     }
 }
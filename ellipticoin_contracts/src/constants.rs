@@ -1,17 +1,31 @@
-use ellipticoin_types::Address;
+use crate::token::tokens::USD;
+use ellipticoin_types::{amount::DECIMALS, Address, Amount, U256};
 
 use std::time::Duration;
 
+pub use ellipticoin_types::amount::BASE_FACTOR;
+
 pub const BASE_TOKEN_MANTISSA: usize = 6;
+pub const BLOCK_TIME_IN_SECONDS: u64 = 3600;
+pub const BLOCKS_PER_DAY: u64 = 24 * 60 * 60 / BLOCK_TIME_IN_SECONDS;
 pub const EXCHANGE_RATE_MANTISSA: usize = 10;
-pub const BASE_FACTOR: u64 = 1_000_000;
 pub const FEE: u64 = 3_000;
-pub const MINER_ALLOW_LIST: [Address; 2] = [
-    Address(hex!("0113713f91dd6a7c179a038e66e5919a9a0a9d1d")),
-    Address(hex!("418b993b7d17b45937ef4f69a06a3433cd30b5ce")),
-];
+pub const FEE_TOKEN: Address = USD;
+pub const HASH_ONION_ROTATION_LAYERS: u64 = 100_000;
+pub const MINER_BOND_GRACE_PERIOD: u64 = 7 * BLOCKS_PER_DAY;
+pub const MINER_TIMEOUT: u64 = 3;
+pub const MINER_UNBONDING_PERIOD: u64 = 7 * BLOCKS_PER_DAY;
+pub const MINIMUM_PROPOSAL_THRESHOLD: u64 = 50_000;
 pub const RATIFICATION_THRESHOLD: u64 = 20;
+pub const EXECUTION_DELAY: u64 = BLOCKS_PER_DAY;
+pub const GUARDIAN: Address = Address(hex!("0113713f91dd6a7c179a038e66e5919a9a0a9d1d"));
 
 lazy_static! {
-    pub static ref BLOCK_TIME: Duration = Duration::from_secs(4);
+    pub static ref BLOCK_TIME: Duration = Duration::from_secs(BLOCK_TIME_IN_SECONDS);
+    pub static ref GAS_PRICE: Amount = Amount(U256::exp10(9));
+    pub static ref MINER_BOND: Amount = Amount(
+        U256::exp10(DECIMALS)
+            .checked_mul(U256::from(10_000))
+            .unwrap()
+    );
 }
@@ -38,4 +38,6 @@ pub enum Name {
     OrderBook,
     System,
     Token,
+    Parameters,
+    Multisig,
 }
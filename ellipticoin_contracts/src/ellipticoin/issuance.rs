@@ -1,7 +1,7 @@
-use super::Ellipticoin;
-use crate::constants::BASE_FACTOR;
 use crate::token::tokens::{ETH, WBTC};
-use ellipticoin_types::Address;
+use ellipticoin_types::{amount::DECIMALS, Address, Amount, U256};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
 
 lazy_static! {
     pub static ref INCENTIVISED_POOLS: Vec<Address> = vec![WBTC.clone(), ETH.clone()];
@@ -11,63 +11,107 @@ const MIGRATION_TO_POLYGON_AT: u64 = 6_359_375;
 const BLOCKS_PER_ERA: u64 = 8_000_000;
 const NUMBER_OF_ERAS: u64 = 8;
 
-impl Ellipticoin {
-    pub fn block_reward_at(block: u64) -> u64 {
-        if block > MIGRATION_TO_POLYGON_AT {
-            return 0;
-        }
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct IssuanceSchedule {
+    pub initial_block_reward: Amount,
+    pub blocks_per_era: u64,
+    pub number_of_eras: u64,
+    pub last_issuance_block: u64,
+}
+
+pub const ISSUANCE_SCHEDULE: IssuanceSchedule = IssuanceSchedule {
+    initial_block_reward: Amount(U256::from_u64(128 * 10u64.pow(DECIMALS - 2))),
+    blocks_per_era: BLOCKS_PER_ERA,
+    number_of_eras: NUMBER_OF_ERAS,
+    last_issuance_block: MIGRATION_TO_POLYGON_AT,
+};
 
-        if block > BLOCKS_PER_ERA * NUMBER_OF_ERAS {
-            return 0;
+impl IssuanceSchedule {
+    // Schedules which can't be evaluated without overflowing don't issue
+    // any rewards rather than halting the chain.
+    pub fn block_reward_at(&self, block: u64) -> Amount {
+        if block > self.last_issuance_block {
+            return Amount::ZERO;
         }
-        let era = block / BLOCKS_PER_ERA;
-        BASE_FACTOR * 128 * 10u64.pow(6) / 2u64.pow(era as u32) / 10u64.pow(8)
+        match self.blocks_per_era.checked_mul(self.number_of_eras) {
+            Some(last_block) if block <= last_block => (),
+            _ => return Amount::ZERO,
+        }
+        block
+            .checked_div(self.blocks_per_era)
+            .and_then(|era| u32::try_from(era).ok())
+            .and_then(|era| 2u64.checked_pow(era))
+            .and_then(|halvings| self.initial_block_reward.checked_div(halvings).ok())
+            .unwrap_or(Amount::ZERO)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::constants::BLOCK_TIME;
     use std::time::Duration;
+    const BLOCK_TIME: Duration = Duration::from_secs(4);
     const NUMBER_OF_ERAS: u64 = 8;
     const SECONDS_IN_A_YEAR: u64 = 31556952;
 
     #[test]
     fn test_migration_to_polygon() {
         assert_eq!(
-            Ellipticoin::block_reward_at(MIGRATION_TO_POLYGON_AT),
-            1280000
+            ISSUANCE_SCHEDULE.block_reward_at(MIGRATION_TO_POLYGON_AT),
+            1_280_000_000_000_000_000
+        );
+        assert_eq!(
+            ISSUANCE_SCHEDULE.block_reward_at(MIGRATION_TO_POLYGON_AT + 1),
+            Amount::ZERO
         );
-        assert_eq!(Ellipticoin::block_reward_at(MIGRATION_TO_POLYGON_AT + 1), 0);
     }
 
     #[test]
     #[ignore]
     fn test_total_supply() {
-        let mut total_issuance = 0;
+        let mut total_issuance = Amount::ZERO;
         let mut total_time: Duration = Default::default();
         for era in 0..=NUMBER_OF_ERAS - 1 {
-            let reward = Ellipticoin::block_reward_at(era * BLOCKS_PER_ERA);
-            total_issuance += reward * BLOCKS_PER_ERA as u64;
-            total_time += (BLOCKS_PER_ERA as u32) * BLOCK_TIME.clone();
+            let reward = ISSUANCE_SCHEDULE.block_reward_at(era * BLOCKS_PER_ERA);
+            total_issuance = total_issuance
+                .checked_add(reward.checked_mul(BLOCKS_PER_ERA).unwrap())
+                .unwrap();
+            total_time += (BLOCKS_PER_ERA as u32) * BLOCK_TIME;
         }
         assert_eq!(
-            Ellipticoin::block_reward_at((NUMBER_OF_ERAS * BLOCKS_PER_ERA) + 1),
-            0
+            ISSUANCE_SCHEDULE.block_reward_at((NUMBER_OF_ERAS * BLOCKS_PER_ERA) + 1),
+            Amount::ZERO
+        );
+        assert_eq!(
+            total_issuance,
+            Amount(
+                U256::exp10(DECIMALS)
+                    .checked_mul(U256::from(20400000))
+                    .unwrap()
+            )
         );
-        assert_eq!(total_issuance, 20400000 * BASE_FACTOR);
         assert_eq!(total_time.as_secs() / SECONDS_IN_A_YEAR, 8);
     }
 
+    #[test]
+    fn test_invalid_schedules() {
+        let mut issuance_schedule = ISSUANCE_SCHEDULE;
+        issuance_schedule.blocks_per_era = 0;
+        assert_eq!(issuance_schedule.block_reward_at(1), Amount::ZERO);
+        let mut issuance_schedule = ISSUANCE_SCHEDULE;
+        issuance_schedule.blocks_per_era = 1;
+        issuance_schedule.number_of_eras = 100;
+        assert_eq!(issuance_schedule.block_reward_at(64), Amount::ZERO);
+    }
+
     #[test]
     fn test_halvenings() {
-        let mut last_block_reward: u64 = Ellipticoin::block_reward_at(BLOCKS_PER_ERA - 1);
+        let mut last_block_reward = ISSUANCE_SCHEDULE.block_reward_at(BLOCKS_PER_ERA - 1);
         let mut block: u64 = BLOCKS_PER_ERA + 1;
 
         for _era in 1..=NUMBER_OF_ERAS - 1 {
-            let new_reward: u64 = Ellipticoin::block_reward_at(block);
-            assert_eq!(new_reward, last_block_reward / 2);
+            let new_reward = ISSUANCE_SCHEDULE.block_reward_at(block);
+            assert_eq!(new_reward, last_block_reward.checked_div(2).unwrap());
             block += BLOCKS_PER_ERA;
             last_block_reward = new_reward;
         }
@@ -1,18 +1,21 @@
 mod issuance;
 
+pub use issuance::{IssuanceSchedule, ISSUANCE_SCHEDULE};
+
 use crate::{
-    constants::MINER_ALLOW_LIST,
+    charge,
+    constants::{HASH_ONION_ROTATION_LAYERS, MINER_BOND_GRACE_PERIOD},
     contract::{self, Contract},
     crypto::sha256,
     pay,
-    token::tokens::INCENTIVISED_POOLS,
-    System, Token, AMM,
+    token::tokens::{INCENTIVISED_POOLS, MSX, USD},
+    Parameters, System, Token, AMM,
 };
 use anyhow::{anyhow, bail, Result};
 use ellipticoin_macros::db_accessors;
 use ellipticoin_types::{
     db::{Backend, Db},
-    Address,
+    Address, Amount,
 };
 use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use serde::{Deserialize, Serialize};
@@ -25,8 +28,11 @@ impl Contract for Ellipticoin {
 }
 
 db_accessors!(Ellipticoin {
-    issuance_rewards(address: Address) -> u64;
+    issuance_rewards(address: Address) -> Amount;
     miners() -> Vec<Miner>;
+    last_sealed_at() -> Option<u64>;
+    unbonding(address: Address) -> Option<Unbonding>;
+    miner_bond_deadline() -> Option<u64>;
 });
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct Miner {
@@ -34,9 +40,30 @@ pub struct Miner {
     pub address: Address,
     pub hash_onion_skin: [u8; 32],
     pub hash_onion_layers_left: u64,
+    #[serde(default)]
+    pub bond: Amount,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Unbonding {
+    pub amount: Amount,
+    pub release_block_number: u64,
 }
 
 impl Ellipticoin {
+    // Miners which started mining before bonds were required have no bond.
+    // They're given a grace period to post one.
+    pub fn migrate<B: Backend>(db: &mut Db<B>) {
+        if Self::get_miner_bond_deadline(db).is_none()
+            && Self::get_miners(db)
+                .iter()
+                .any(|miner| miner.bond == Amount::ZERO)
+        {
+            let deadline = System::get_block_number(db) + MINER_BOND_GRACE_PERIOD;
+            Self::set_miner_bond_deadline(db, Some(deadline));
+        }
+    }
+
     pub fn start_mining<B: Backend>(
         db: &mut Db<B>,
         sender: Address,
@@ -45,85 +72,254 @@ impl Ellipticoin {
         layer_count: u64,
     ) -> Result<()> {
         let mut miners = Self::get_miners(db);
-        if !MINER_ALLOW_LIST.contains(&sender) {
-            bail!("Miner {} is not on the allow list", hex::encode(sender));
+        if miners.iter().any(|miner| miner.address == sender) {
+            bail!("Miner {} is already mining", hex::encode(sender));
         }
+        let bond = Parameters::get_miner_bond(db);
+        charge!(db, sender, MSX, bond)?;
         miners.push(Miner {
             address: sender,
             host,
             hash_onion_skin,
             hash_onion_layers_left: layer_count,
+            bond,
         });
         Self::set_miners(db, miners);
         Ok(())
     }
 
-    pub fn seal<B: Backend>(
+    // Onions can only be rotated once they're running low so that a miner
+    // can't swap in a new onion after learning the next block's seed.
+    pub fn rotate_hash_onion<B: Backend>(
         db: &mut Db<B>,
         sender: Address,
         hash_onion_skin: [u8; 32],
+        layer_count: u64,
     ) -> Result<()> {
         let mut miners = Self::get_miners(db);
-        if sender
-            != miners
-                .first()
-                .ok_or_else(|| anyhow!("No miners running"))?
-                .address
-        {
+        let miner = miners
+            .iter_mut()
+            .find(|miner| miner.address == sender)
+            .ok_or_else(|| anyhow!("Miner {} is not mining", hex::encode(sender)))?;
+        if miner.hash_onion_layers_left > HASH_ONION_ROTATION_LAYERS {
             bail!(
-                "Winning miner was {} but sender was {}",
-                hex::encode(miners.first().unwrap().address),
-                hex::encode(sender)
+                "Hash onion can't be rotated until {} layers are left",
+                HASH_ONION_ROTATION_LAYERS
             )
+        }
+        miner.hash_onion_skin = hash_onion_skin;
+        miner.hash_onion_layers_left = layer_count;
+        Self::set_miners(db, miners);
+        Ok(())
+    }
+
+    pub fn stop_mining<B: Backend>(db: &mut Db<B>, sender: Address) -> Result<()> {
+        let mut miners = Self::get_miners(db);
+        let index = miners
+            .iter()
+            .position(|miner| miner.address == sender)
+            .ok_or_else(|| anyhow!("Miner {} is not mining", hex::encode(sender)))?;
+        if miners.len() == 1 {
+            bail!("The last miner cannot stop mining")
+        }
+        let miner = miners.remove(index);
+        Self::set_miners(db, miners);
+
+        let amount = match Self::get_unbonding(db, sender) {
+            Some(unbonding) => unbonding.amount.checked_add(miner.bond)?,
+            None => miner.bond,
         };
-        if !miners
-            .first()
-            .unwrap()
+        let release_block_number =
+            System::get_block_number(db) + Parameters::get_miner_unbonding_period(db);
+        Self::set_unbonding(
+            db,
+            sender,
+            Some(Unbonding {
+                amount,
+                release_block_number,
+            }),
+        );
+        Ok(())
+    }
+
+    pub fn withdraw_bond<B: Backend>(db: &mut Db<B>, sender: Address) -> Result<()> {
+        let unbonding = Self::get_unbonding(db, sender)
+            .ok_or_else(|| anyhow!("{} has no bond to withdraw", hex::encode(sender)))?;
+        if System::get_block_number(db) < unbonding.release_block_number {
+            bail!(
+                "Bond can't be withdrawn until block {}",
+                unbonding.release_block_number
+            )
+        }
+        Self::set_unbonding(db, sender, None);
+        pay!(db, sender, MSX, unbonding.amount)?;
+        Ok(())
+    }
+
+    pub fn seal<B: Backend>(
+        db: &mut Db<B>,
+        sender: Address,
+        hash_onion_skin: [u8; 32],
+        timestamp: Option<u64>,
+    ) -> Result<()> {
+        let mut miners = Self::get_miners(db);
+        let index = Self::get_sealer_index(db, &miners, sender, timestamp)?;
+        Self::post_missing_bond(db, &mut miners[index])?;
+        if !miners[index]
             .hash_onion_skin
             .to_vec()
             .eq(&sha256(hash_onion_skin.to_vec()))
         {
             bail!(
                 "Invalid onion skin: expected {} but got {}",
-                base64::encode(&miners.first().unwrap().hash_onion_skin),
+                base64::encode(&miners[index].hash_onion_skin),
                 base64::encode(&sha256(hash_onion_skin.to_vec()))
             );
         }
-        miners.first_mut().unwrap().hash_onion_skin = hash_onion_skin.clone();
-        miners.first_mut().unwrap().hash_onion_layers_left -= 1;
-        Self::settle_block_rewards(db)?;
+        miners[index].hash_onion_skin = hash_onion_skin.clone();
+        miners[index].hash_onion_layers_left -= 1;
+        let skipped_miners = Self::penalize_skipped_miners(db, &mut miners[..index])?;
+        Self::settle_block_rewards(db, sender)?;
+        System::pay_block_fees(db, sender)?;
         Self::shuffle_miners(db, &mut miners, hash_onion_skin);
         Self::issue_block_rewards(db)?;
-        System::seal_block(db);
+        Self::set_last_sealed_at(db, timestamp);
+        System::seal_block(db, sender, skipped_miners, timestamp);
 
         Ok(())
     }
 
-    pub fn harvest<B: Backend>(db: &mut Db<B>, sender: Address) -> Result<()> {
+    pub fn can_seal<B: Backend>(db: &mut Db<B>, sender: Address, timestamp: Option<u64>) -> bool {
+        let miners = Self::get_miners(db);
+        Self::get_sealer_index(db, &miners, sender, timestamp).is_ok()
+    }
+
+    // The winning miner can always seal. If it hasn't sealed within
+    // `miner_timeout` block times the next miner in line may seal instead and
+    // so on down the list.
+    fn get_sealer_index<B: Backend>(
+        db: &mut Db<B>,
+        miners: &[Miner],
+        sender: Address,
+        timestamp: Option<u64>,
+    ) -> Result<usize> {
+        let winner = miners.first().ok_or_else(|| anyhow!("No miners running"))?;
+        let last_sealed_at = Self::get_last_sealed_at(db);
+        if let (Some(timestamp), Some(last_sealed_at)) = (timestamp, last_sealed_at) {
+            if timestamp < last_sealed_at {
+                bail!(
+                    "Block timestamp {} is before the previous block timestamp {}",
+                    timestamp,
+                    last_sealed_at
+                )
+            }
+        }
+        if sender == winner.address {
+            return Ok(0);
+        }
+        if let (Some(timestamp), Some(last_sealed_at)) = (timestamp, last_sealed_at) {
+            let timeout = Parameters::get_block_time(db) * Parameters::get_miner_timeout(db);
+            if let Some(index) = miners.iter().position(|miner| miner.address == sender) {
+                if timestamp >= last_sealed_at + timeout * index as u64 {
+                    return Ok(index);
+                }
+            }
+        }
+        bail!(
+            "Winning miner was {} but sender was {}",
+            hex::encode(winner.address),
+            hex::encode(sender)
+        )
+    }
+
+    // Unbonded miners post the bond when they next seal. Once the grace period
+    // is over they can't seal without one.
+    fn post_missing_bond<B: Backend>(db: &mut Db<B>, miner: &mut Miner) -> Result<()> {
+        let deadline = match Self::get_miner_bond_deadline(db) {
+            Some(deadline) if miner.bond == Amount::ZERO => deadline,
+            _ => return Ok(()),
+        };
+        let bond = Parameters::get_miner_bond(db);
+        match charge!(db, miner.address, MSX, bond) {
+            Ok(()) => {
+                miner.bond = bond;
+                Ok(())
+            }
+            Err(err) if System::get_block_number(db) >= deadline => Err(err.context(format!(
+                "Miner {} must post a bond of {}",
+                hex::encode(miner.address),
+                bond
+            ))),
+            Err(_) => Ok(()),
+        }
+    }
+
+    // Penalties are burned from the bonds held by the contract.
+    fn penalize_skipped_miners<B: Backend>(
+        db: &mut Db<B>,
+        skipped_miners: &mut [Miner],
+    ) -> Result<Vec<Address>> {
+        let missed_block_penalty = Parameters::get_missed_block_penalty(db);
+        for miner in skipped_miners.iter_mut() {
+            let penalty = std::cmp::min(missed_block_penalty, miner.bond);
+            miner.bond = miner.bond.checked_sub(penalty)?;
+            Token::burn(db, penalty, MSX, Self::address())?;
+        }
+        Ok(skipped_miners.iter().map(|miner| miner.address).collect())
+    }
+
+    pub fn harvest<B: Backend>(db: &mut Db<B>, sender: Address) -> Result<Amount> {
         let issuance_rewards = Self::get_issuance_rewards(db, sender);
-        Self::debit_issuance_rewards(db, sender, issuance_rewards);
+        if issuance_rewards == Amount::ZERO {
+            bail!("{} has no issuance rewards to harvest", hex::encode(sender))
+        }
+        Self::debit_issuance_rewards(db, sender, issuance_rewards)?;
         pay!(db, sender, Self::address(), issuance_rewards)?;
-        Ok(())
+        Ok(issuance_rewards)
+    }
+
+    // Rewards compounded into another pool are swapped for that pool's token
+    // first. The sender provides the USD side of the liquidity. Only the
+    // amount of the pool's token is bounded since that's what the sender ends
+    // up with.
+    pub fn compound<B: Backend>(
+        db: &mut Db<B>,
+        sender: Address,
+        token: Address,
+        minimum_output_amount: Amount,
+    ) -> Result<()> {
+        let issuance_rewards = Self::harvest(db, sender)?;
+        let amount = if token == Self::address() {
+            issuance_rewards
+        } else {
+            let usd_balance = Token::get_balance(db, sender, USD);
+            AMM::sell(db, sender, issuance_rewards, Self::address(), Amount::ZERO)?;
+            let usd_amount = Token::get_balance(db, sender, USD).checked_sub(usd_balance)?;
+            let token_balance = Token::get_balance(db, sender, token);
+            AMM::buy(db, sender, usd_amount, token, minimum_output_amount)?;
+            Token::get_balance(db, sender, token).checked_sub(token_balance)?
+        };
+        AMM::add_liquidity(db, sender, amount, token)
     }
 
     fn issue_block_rewards<B: Backend>(db: &mut Db<B>) -> Result<()> {
         let block_number = System::get_block_number(db);
-        let block_reward = Self::block_reward_at(block_number);
-        Self::mint(db, block_reward);
-        let reward_per_pool = block_reward / INCENTIVISED_POOLS.len() as u64;
+        let block_reward = Parameters::get_issuance_schedule(db).block_reward_at(block_number);
+        Self::mint(db, block_reward)?;
+        let reward_per_pool = block_reward.checked_div(INCENTIVISED_POOLS.len() as u64)?;
         for token in INCENTIVISED_POOLS.iter() {
             let liquidity_providers = AMM::get_liquidity_providers(db, token.clone());
-            let (addresses, balances): (Vec<Address>, Vec<u64>) = liquidity_providers
+            let (addresses, balances): (Vec<Address>, Vec<Amount>) = liquidity_providers
                 .iter()
                 .map(|address| (address, AMM::get_balance(db, *address, *token)))
                 .unzip();
 
-            addresses
+            for (address, issuance) in addresses
                 .iter()
-                .zip(distribute(reward_per_pool, balances).iter())
-                .for_each(|(address, issuance)| {
-                    Self::credit_issuance_rewards(db, address.clone(), *issuance);
-                });
+                .zip(distribute(reward_per_pool, balances)?.iter())
+            {
+                Self::credit_issuance_rewards(db, address.clone(), *issuance)?;
+            }
         }
         Ok(())
     }
@@ -140,11 +336,10 @@ impl Ellipticoin {
         Self::set_miners(db, shuffled_miners);
     }
 
-    fn settle_block_rewards<B: Backend>(db: &mut Db<B>) -> Result<()> {
+    fn settle_block_rewards<B: Backend>(db: &mut Db<B>, winner: Address) -> Result<()> {
         let miners = Self::get_miners(db);
-        let winner = miners.first().as_ref().unwrap().clone();
         for miner in &miners {
-            Self::transfer(db, miner.address.clone(), winner.address.clone(), 0)?;
+            Self::transfer(db, miner.address.clone(), winner, Amount::ZERO)?;
         }
         Ok(())
     }
@@ -153,51 +348,64 @@ impl Ellipticoin {
         db: &mut Db<B>,
         sender: Address,
         recipient: Address,
-        amount: u64,
+        amount: Amount,
     ) -> Result<()> {
         Token::transfer(db, sender, recipient, amount, Self::address())
     }
 
-    fn mint<B: Backend>(db: &mut Db<B>, amount: u64) {
+    fn mint<B: Backend>(db: &mut Db<B>, amount: Amount) -> Result<()> {
         Token::credit(db, amount, Self::address(), Self::address())
     }
 
-    fn credit_issuance_rewards<B: Backend>(db: &mut Db<B>, address: Address, amount: u64) {
+    fn credit_issuance_rewards<B: Backend>(
+        db: &mut Db<B>,
+        address: Address,
+        amount: Amount,
+    ) -> Result<()> {
         let issuance_rewards = Self::get_issuance_rewards(db, address.clone());
-        Self::set_issuance_rewards(db, address, issuance_rewards + amount);
+        Self::set_issuance_rewards(db, address, issuance_rewards.checked_add(amount)?);
+        Ok(())
     }
 
-    fn debit_issuance_rewards<B: Backend>(db: &mut Db<B>, address: Address, amount: u64) {
+    fn debit_issuance_rewards<B: Backend>(
+        db: &mut Db<B>,
+        address: Address,
+        amount: Amount,
+    ) -> Result<()> {
         let issuance_rewards = Self::get_issuance_rewards(db, address.clone());
-        Self::set_issuance_rewards(db, address, issuance_rewards - amount);
+        Self::set_issuance_rewards(db, address, issuance_rewards.checked_sub(amount)?);
+        Ok(())
     }
 }
 
-fn distribute(mut amount: u64, mut values: Vec<u64>) -> Vec<u64> {
+fn distribute(mut amount: Amount, mut values: Vec<Amount>) -> Result<Vec<Amount>> {
     let mut rest = values.clone();
-    let mut distributions: Vec<u64> = Default::default();
+    let mut distributions: Vec<Amount> = Default::default();
     values.reverse();
     for balance in values.clone() {
-        let denominator = rest.iter().sum::<u64>();
+        let denominator = Amount::sum(rest.iter().cloned())?;
         let distribution = if denominator == 0 {
-            0
+            Amount::ZERO
         } else {
-            (amount * balance) / denominator
+            amount.proportion_of(balance, denominator)?
         };
-        amount -= distribution;
+        amount = amount.checked_sub(distribution)?;
         distributions.push(distribution);
         rest.pop();
     }
     distributions.reverse();
-    distributions
+    Ok(distributions)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::hash_onion;
+    use crate::{constants::BASE_FACTOR, hash_onion, Action};
     use ellipticoin_test_framework::{
-        constants::actors::{ALICE, ALICES_PRIVATE_KEY, BOB, BOBS_PRIVATE_KEY},
+        constants::{
+            actors::{ALICE, ALICES_PRIVATE_KEY, BOB, BOBS_PRIVATE_KEY},
+            tokens::APPLES,
+        },
         new_db, setup,
     };
 
@@ -222,23 +430,432 @@ mod tests {
                     host: "host1".to_string(),
                     hash_onion_skin: *alices_onion.last().unwrap(),
                     hash_onion_layers_left: alices_onion.len() as u64,
+                    bond: Amount::ZERO,
                 },
                 Miner {
                     address: BOB,
                     host: "host2".to_string(),
                     hash_onion_skin: *bobs_onion.last().unwrap(),
                     hash_onion_layers_left: bobs_onion.len() as u64,
+                    bond: Amount::ZERO,
                 },
             ],
         );
         alices_onion.pop();
-        assert!(Ellipticoin::seal(&mut db, ALICE, *alices_onion.last().unwrap()).is_ok());
+        assert!(Ellipticoin::seal(&mut db, ALICE, *alices_onion.last().unwrap(), None).is_ok());
         bobs_onion.pop();
-        assert!(Ellipticoin::seal(&mut db, BOB, *bobs_onion.last().unwrap()).is_ok());
+        assert!(Ellipticoin::seal(&mut db, BOB, *bobs_onion.last().unwrap(), None).is_ok());
 
         alices_onion.pop();
-        assert!(Ellipticoin::seal(&mut db, ALICE, *alices_onion.last().unwrap()).is_ok());
+        assert!(Ellipticoin::seal(&mut db, ALICE, *alices_onion.last().unwrap(), None).is_ok());
         assert_eq!(Token::get_balance(&mut db, ALICE, elc), 5);
         assert_eq!(System::get_block_number(&mut db), 3);
     }
+
+    #[test]
+    fn test_seal_pays_block_fees() {
+        let mut db = new_db();
+        let mut alices_onion = hash_onion::generate(2, ALICES_PRIVATE_KEY);
+        Ellipticoin::set_miners(
+            &mut db,
+            vec![Miner {
+                address: ALICE,
+                host: "host1".to_string(),
+                hash_onion_skin: *alices_onion.last().unwrap(),
+                hash_onion_layers_left: alices_onion.len() as u64,
+                bond: Amount::ZERO,
+            }],
+        );
+        let fee = System::fee(&mut db, BOB, &Action::Null).unwrap();
+        Token::set_balance(&mut db, BOB, USD, fee);
+        System::charge_fee(&mut db, BOB, fee).unwrap();
+        assert_eq!(Token::get_balance(&mut db, BOB, USD), 0);
+        alices_onion.pop();
+        Ellipticoin::seal(&mut db, ALICE, *alices_onion.last().unwrap(), None).unwrap();
+        assert_eq!(Token::get_balance(&mut db, ALICE, USD), fee);
+        assert!(System::get_block_fees(&mut db).is_empty());
+    }
+
+    #[test]
+    fn test_start_and_stop_mining() {
+        let mut db = new_db();
+        let bond = Parameters::get_miner_bond(&mut db);
+        Token::mint(&mut db, bond.checked_mul(2).unwrap(), MSX, ALICE).unwrap();
+        Token::mint(&mut db, bond, MSX, BOB).unwrap();
+        let alices_onion = hash_onion::generate(3, ALICES_PRIVATE_KEY);
+        let bobs_onion = hash_onion::generate(3, BOBS_PRIVATE_KEY);
+        Ellipticoin::start_mining(
+            &mut db,
+            ALICE,
+            "host1".to_string(),
+            *alices_onion.last().unwrap(),
+            3,
+        )
+        .unwrap();
+        assert_eq!(
+            Ellipticoin::start_mining(
+                &mut db,
+                ALICE,
+                "host1".to_string(),
+                *alices_onion.last().unwrap(),
+                3,
+            )
+            .err()
+            .unwrap()
+            .to_string(),
+            "Miner aaa1b967f4e3d67c4946ec6816b05f0207aad9cd is already mining"
+        );
+        assert_eq!(Token::get_balance(&mut db, ALICE, MSX), bond);
+        assert_eq!(
+            Ellipticoin::stop_mining(&mut db, ALICE)
+                .err()
+                .unwrap()
+                .to_string(),
+            "The last miner cannot stop mining"
+        );
+        Ellipticoin::start_mining(
+            &mut db,
+            BOB,
+            "host2".to_string(),
+            *bobs_onion.last().unwrap(),
+            3,
+        )
+        .unwrap();
+        Ellipticoin::stop_mining(&mut db, ALICE).unwrap();
+        assert_eq!(Ellipticoin::get_miners(&mut db).len(), 1);
+        assert_eq!(
+            Ellipticoin::withdraw_bond(&mut db, ALICE)
+                .err()
+                .unwrap()
+                .to_string(),
+            format!(
+                "Bond can't be withdrawn until block {}",
+                Parameters::get_miner_unbonding_period(&mut db)
+            )
+        );
+        let unbonding_period = Parameters::get_miner_unbonding_period(&mut db);
+        System::set_block_number(&mut db, unbonding_period);
+        Ellipticoin::withdraw_bond(&mut db, ALICE).unwrap();
+        assert_eq!(
+            Token::get_balance(&mut db, ALICE, MSX),
+            bond.checked_mul(2).unwrap()
+        );
+        assert_eq!(Ellipticoin::get_unbonding(&mut db, ALICE), None);
+    }
+
+    #[test]
+    fn test_start_mining_without_bond() {
+        let mut db = new_db();
+        let onion = hash_onion::generate(3, ALICES_PRIVATE_KEY);
+        assert!(Ellipticoin::start_mining(
+            &mut db,
+            ALICE,
+            "host1".to_string(),
+            *onion.last().unwrap(),
+            3,
+        )
+        .is_err());
+        assert!(Ellipticoin::get_miners(&mut db).is_empty());
+    }
+
+    #[test]
+    fn test_unbonded_miners_post_bond_when_sealing() {
+        let mut db = new_db();
+        let bond = Parameters::get_miner_bond(&mut db);
+        let mut alices_onion = hash_onion::generate(3, ALICES_PRIVATE_KEY);
+        Ellipticoin::set_miners(
+            &mut db,
+            vec![Miner {
+                address: ALICE,
+                host: "host1".to_string(),
+                hash_onion_skin: *alices_onion.last().unwrap(),
+                hash_onion_layers_left: alices_onion.len() as u64,
+                bond: Amount::ZERO,
+            }],
+        );
+        Ellipticoin::migrate(&mut db);
+        assert_eq!(
+            Ellipticoin::get_miner_bond_deadline(&mut db),
+            Some(MINER_BOND_GRACE_PERIOD)
+        );
+        alices_onion.pop();
+        Ellipticoin::seal(&mut db, ALICE, *alices_onion.last().unwrap(), None).unwrap();
+        assert_eq!(Ellipticoin::get_miners(&mut db)[0].bond, Amount::ZERO);
+
+        Token::mint(&mut db, bond, MSX, ALICE).unwrap();
+        alices_onion.pop();
+        Ellipticoin::seal(&mut db, ALICE, *alices_onion.last().unwrap(), None).unwrap();
+        assert_eq!(Ellipticoin::get_miners(&mut db)[0].bond, bond);
+        assert_eq!(Token::get_balance(&mut db, ALICE, MSX), 0);
+    }
+
+    #[test]
+    fn test_unbonded_miners_cant_seal_after_deadline() {
+        let mut db = new_db();
+        let mut alices_onion = hash_onion::generate(2, ALICES_PRIVATE_KEY);
+        Ellipticoin::set_miners(
+            &mut db,
+            vec![Miner {
+                address: ALICE,
+                host: "host1".to_string(),
+                hash_onion_skin: *alices_onion.last().unwrap(),
+                hash_onion_layers_left: alices_onion.len() as u64,
+                bond: Amount::ZERO,
+            }],
+        );
+        Ellipticoin::migrate(&mut db);
+        System::set_block_number(&mut db, MINER_BOND_GRACE_PERIOD);
+        alices_onion.pop();
+        assert_eq!(
+            Ellipticoin::seal(&mut db, ALICE, *alices_onion.last().unwrap(), None)
+                .err()
+                .unwrap()
+                .to_string(),
+            format!(
+                "Miner aaa1b967f4e3d67c4946ec6816b05f0207aad9cd must post a bond of {}",
+                Parameters::get_miner_bond(&mut db)
+            )
+        );
+    }
+
+    #[test]
+    fn test_seal_after_timeout() {
+        let mut db = new_db();
+        let bond = Parameters::get_miner_bond(&mut db);
+        Token::mint(
+            &mut db,
+            bond.checked_mul(2).unwrap(),
+            MSX,
+            Ellipticoin::address(),
+        )
+        .unwrap();
+        Parameters::set_missed_block_penalty(&mut db, Amount::from(1));
+        let alices_onion = hash_onion::generate(3, ALICES_PRIVATE_KEY);
+        let mut bobs_onion = hash_onion::generate(3, BOBS_PRIVATE_KEY);
+        Ellipticoin::set_miners(
+            &mut db,
+            vec![
+                Miner {
+                    address: ALICE,
+                    host: "host1".to_string(),
+                    hash_onion_skin: *alices_onion.last().unwrap(),
+                    hash_onion_layers_left: alices_onion.len() as u64,
+                    bond,
+                },
+                Miner {
+                    address: BOB,
+                    host: "host2".to_string(),
+                    hash_onion_skin: *bobs_onion.last().unwrap(),
+                    hash_onion_layers_left: bobs_onion.len() as u64,
+                    bond,
+                },
+            ],
+        );
+        Ellipticoin::set_last_sealed_at(&mut db, Some(100));
+        let timeout = Parameters::get_block_time(&mut db) * Parameters::get_miner_timeout(&mut db);
+        bobs_onion.pop();
+        assert_eq!(
+            Ellipticoin::seal(
+                &mut db,
+                BOB,
+                *bobs_onion.last().unwrap(),
+                Some(100 + timeout - 1)
+            )
+            .err()
+            .unwrap()
+            .to_string(),
+            "Winning miner was aaa1b967f4e3d67c4946ec6816b05f0207aad9cd but sender was b0b9416041676df4342b6c85fb69351e5ba69f4f"
+        );
+        Ellipticoin::seal(
+            &mut db,
+            BOB,
+            *bobs_onion.last().unwrap(),
+            Some(100 + timeout),
+        )
+        .unwrap();
+        let block = System::get_blocks(&mut db).last().unwrap().clone();
+        assert_eq!(block.sealed_by, BOB);
+        assert_eq!(block.skipped_miners, vec![ALICE]);
+        assert_eq!(block.timestamp, Some(100 + timeout));
+        let alice = Ellipticoin::get_miners(&mut db)
+            .into_iter()
+            .find(|miner| miner.address == ALICE)
+            .unwrap();
+        assert_eq!(alice.bond, bond.checked_sub(Amount::from(1)).unwrap());
+        assert_eq!(
+            Token::get_total_supply(&mut db, MSX),
+            bond.checked_mul(2)
+                .unwrap()
+                .checked_sub(Amount::from(1))
+                .unwrap()
+        );
+        assert_eq!(
+            Ellipticoin::seal(&mut db, BOB, *bobs_onion.last().unwrap(), Some(99))
+                .err()
+                .unwrap()
+                .to_string(),
+            format!(
+                "Block timestamp 99 is before the previous block timestamp {}",
+                100 + timeout
+            )
+        );
+    }
+
+    #[test]
+    fn test_rotate_hash_onion() {
+        let mut db = new_db();
+        let mut old_onion = hash_onion::generate(3, ALICES_PRIVATE_KEY);
+        let mut new_onion = hash_onion::generate(5, BOBS_PRIVATE_KEY);
+        Ellipticoin::set_miners(
+            &mut db,
+            vec![Miner {
+                address: ALICE,
+                host: "host1".to_string(),
+                hash_onion_skin: old_onion.pop().unwrap(),
+                hash_onion_layers_left: old_onion.len() as u64,
+                bond: Amount::ZERO,
+            }],
+        );
+        assert_eq!(
+            Ellipticoin::rotate_hash_onion(&mut db, BOB, *new_onion.last().unwrap(), 4)
+                .err()
+                .unwrap()
+                .to_string(),
+            "Miner b0b9416041676df4342b6c85fb69351e5ba69f4f is not mining"
+        );
+        Ellipticoin::rotate_hash_onion(&mut db, ALICE, new_onion.pop().unwrap(), 4).unwrap();
+        assert!(Ellipticoin::seal(&mut db, ALICE, old_onion.pop().unwrap(), None).is_err());
+        Ellipticoin::seal(&mut db, ALICE, new_onion.pop().unwrap(), None).unwrap();
+        assert_eq!(
+            Ellipticoin::get_miners(&mut db)[0].hash_onion_layers_left,
+            3
+        );
+    }
+
+    #[test]
+    fn test_rotate_hash_onion_with_layers_left() {
+        let mut db = new_db();
+        let onion = hash_onion::generate(3, ALICES_PRIVATE_KEY);
+        Ellipticoin::set_miners(
+            &mut db,
+            vec![Miner {
+                address: ALICE,
+                host: "host1".to_string(),
+                hash_onion_skin: *onion.last().unwrap(),
+                hash_onion_layers_left: HASH_ONION_ROTATION_LAYERS + 1,
+                bond: Amount::ZERO,
+            }],
+        );
+        assert_eq!(
+            Ellipticoin::rotate_hash_onion(&mut db, ALICE, onion[0], 3)
+                .err()
+                .unwrap()
+                .to_string(),
+            format!(
+                "Hash onion can't be rotated until {} layers are left",
+                HASH_ONION_ROTATION_LAYERS
+            )
+        );
+    }
+
+    #[test]
+    fn test_harvest() {
+        let elc: Address = Ellipticoin::address();
+        let mut db = new_db();
+        Ellipticoin::mint(&mut db, Amount::from(5)).unwrap();
+        Ellipticoin::credit_issuance_rewards(&mut db, ALICE, Amount::from(5)).unwrap();
+        Ellipticoin::harvest(&mut db, ALICE).unwrap();
+        assert_eq!(Token::get_balance(&mut db, ALICE, elc), 5);
+        assert_eq!(Ellipticoin::get_issuance_rewards(&mut db, ALICE), 0);
+        assert_eq!(
+            Ellipticoin::harvest(&mut db, ALICE)
+                .err()
+                .unwrap()
+                .to_string(),
+            "aaa1b967f4e3d67c4946ec6816b05f0207aad9cd has no issuance rewards to harvest"
+        );
+    }
+
+    #[test]
+    fn test_compound() {
+        let elc: Address = Ellipticoin::address();
+        let mut db = new_db();
+        setup(
+            &mut db,
+            hashmap! {
+                ALICE => vec![(1, elc), (2, USD)],
+            },
+        );
+        AMM::create_pool(
+            &mut db,
+            ALICE,
+            Amount::from(1),
+            elc,
+            Amount::from(BASE_FACTOR),
+        )
+        .unwrap();
+        Ellipticoin::mint(&mut db, Amount::from(1)).unwrap();
+        Ellipticoin::credit_issuance_rewards(&mut db, ALICE, Amount::from(1)).unwrap();
+        Ellipticoin::compound(&mut db, ALICE, elc, Amount::ZERO).unwrap();
+        assert_eq!(Token::get_balance(&mut db, ALICE, elc), 0);
+        assert_eq!(AMM::get_balance(&mut db, ALICE, elc), 2);
+    }
+
+    #[test]
+    fn test_compound_into_another_pool() {
+        let elc: Address = Ellipticoin::address();
+        let mut db = new_db();
+        setup(
+            &mut db,
+            hashmap! {
+                ALICE => vec![(1000 * BASE_FACTOR, elc), (1000 * BASE_FACTOR, APPLES), (3000 * BASE_FACTOR, USD)],
+            },
+        );
+        for token in [elc, APPLES].iter() {
+            AMM::create_pool(
+                &mut db,
+                ALICE,
+                Amount::from(1000 * BASE_FACTOR),
+                *token,
+                Amount::from(BASE_FACTOR),
+            )
+            .unwrap();
+        }
+        Ellipticoin::mint(&mut db, Amount::from(100 * BASE_FACTOR)).unwrap();
+        Ellipticoin::credit_issuance_rewards(&mut db, ALICE, Amount::from(100 * BASE_FACTOR))
+            .unwrap();
+        Ellipticoin::compound(&mut db, ALICE, APPLES, Amount::from(80 * BASE_FACTOR)).unwrap();
+        assert_eq!(Token::get_balance(&mut db, ALICE, elc), 0);
+        assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 0);
+        assert!(AMM::get_balance(&mut db, ALICE, APPLES) > Amount::from(1000 * BASE_FACTOR));
+        assert_eq!(AMM::get_balance(&mut db, ALICE, elc), 1000 * BASE_FACTOR);
+    }
+
+    #[test]
+    fn test_compound_below_minimum_output_amount() {
+        let elc: Address = Ellipticoin::address();
+        let mut db = new_db();
+        setup(
+            &mut db,
+            hashmap! {
+                ALICE => vec![(1000 * BASE_FACTOR, elc), (1000 * BASE_FACTOR, APPLES), (3000 * BASE_FACTOR, USD)],
+            },
+        );
+        for token in [elc, APPLES].iter() {
+            AMM::create_pool(
+                &mut db,
+                ALICE,
+                Amount::from(1000 * BASE_FACTOR),
+                *token,
+                Amount::from(BASE_FACTOR),
+            )
+            .unwrap();
+        }
+        Ellipticoin::mint(&mut db, Amount::from(100 * BASE_FACTOR)).unwrap();
+        Ellipticoin::credit_issuance_rewards(&mut db, ALICE, Amount::from(100 * BASE_FACTOR))
+            .unwrap();
+        assert!(
+            Ellipticoin::compound(&mut db, ALICE, APPLES, Amount::from(100 * BASE_FACTOR)).is_err()
+        );
+    }
 }
@@ -2,26 +2,28 @@ mod validations;
 
 use crate::{
     charge,
-    constants::RATIFICATION_THRESHOLD,
     contract::{self, Contract},
     pay,
     token::tokens::MSX,
     token::Token,
-    Action, Ellipticoin,
+    Action, Parameters, System,
 };
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use ellipticoin_macros::db_accessors;
 use ellipticoin_types::{
     db::{Backend, Db},
-    Address,
+    Address, Amount,
 };
+use linked_hash_set::LinkedHashSet;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Vote {
     pub voter: Address,
     pub choice: Choice,
-    pub weight: u64,
+    pub weight: Amount,
+    #[serde(default)]
+    pub delegators: Vec<Address>,
 }
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum Choice {
@@ -29,6 +31,13 @@ pub enum Choice {
     Against,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Execution {
+    Queued(u64),
+    Cancelled,
+    Executed(Vec<Result<(), String>>),
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Proposal {
     pub id: usize,
@@ -39,6 +48,8 @@ pub struct Proposal {
     pub actions: Vec<Action>,
     pub result: Option<Choice>,
     pub votes: Vec<Vote>,
+    #[serde(default)]
+    pub execution: Option<Execution>,
 }
 pub struct Governance;
 
@@ -49,6 +60,8 @@ impl Contract for Governance {
 db_accessors!(Governance {
     proposals() -> Vec<Proposal>;
     proposal_id_counter() -> usize;
+    delegate(delegator: Address) -> Option<Address>;
+    delegators(delegate: Address) -> LinkedHashSet<Address>;
 });
 
 impl Governance {
@@ -75,8 +88,10 @@ impl Governance {
                 choice: Choice::For,
                 voter: sender,
                 weight: balance,
+                delegators: vec![],
             }],
             result: None,
+            execution: None,
         };
         proposals.push(proposal);
         Self::increment_proposal_id_counter(db);
@@ -90,51 +105,185 @@ impl Governance {
         proposal_id: usize,
         choice: Choice,
     ) -> Result<()> {
-        Self::validate_balance(db, sender)?;
-        let balance = Token::get_balance(db, sender, MSX);
-        charge!(db, sender, MSX, balance)?;
         let mut proposals = Self::get_proposals(db);
-        Self::validate_proposal_is_open(&proposals[proposal_id])?;
-        proposals[proposal_id].votes.push(Vote {
+        let proposal = proposals
+            .get_mut(proposal_id)
+            .ok_or_else(|| anyhow!("Proposal {} not found", proposal_id))?;
+        Self::validate_proposal_is_open(proposal)?;
+        Self::remove_vote(db, proposal, sender);
+        Self::remove_delegator(proposal, sender);
+        let vote = Self::cast_vote(db, proposal, sender, choice)?;
+        proposal.votes.push(vote);
+        let votes_for = Self::tally(db, &proposal.votes, Choice::For)?;
+        let votes_against = Self::tally(db, &proposal.votes, Choice::Against)?;
+        let total_supply = Token::get_total_supply(db, MSX);
+        let ratification_threshold = Parameters::get_ratification_threshold(db);
+        if votes_for.percentage_of(total_supply)? > ratification_threshold {
+            proposal.result = Some(Choice::For);
+            proposal.execution = Some(Execution::Queued(
+                System::get_block_number(db) + Parameters::get_execution_delay(db),
+            ));
+            Self::return_balances(db, &proposal.votes);
+        } else if votes_against.percentage_of(total_supply)? > ratification_threshold {
+            proposal.result = Some(Choice::Against);
+            Self::return_balances(db, &proposal.votes);
+        }
+        Self::set_proposals(db, proposals);
+        Ok(())
+    }
+
+    pub fn withdraw_vote<B: Backend>(
+        db: &mut Db<B>,
+        sender: Address,
+        proposal_id: usize,
+    ) -> Result<()> {
+        let mut proposals = Self::get_proposals(db);
+        let proposal = proposals
+            .get_mut(proposal_id)
+            .ok_or_else(|| anyhow!("Proposal {} not found", proposal_id))?;
+        Self::validate_proposal_is_open(proposal)?;
+        if Self::remove_vote(db, proposal, sender).is_none() {
+            bail!("No vote found on proposal {}", proposal_id)
+        }
+        Self::set_proposals(db, proposals);
+        Ok(())
+    }
+
+    pub fn delegate<B: Backend>(db: &mut Db<B>, sender: Address, delegate: Address) -> Result<()> {
+        if sender == delegate {
+            bail!("Cannot delegate to self")
+        }
+        Self::undelegate(db, sender)?;
+        let mut delegators = Self::get_delegators(db, delegate);
+        delegators.insert(sender);
+        Self::set_delegators(db, delegate, delegators);
+        Self::set_delegate(db, sender, Some(delegate));
+        Ok(())
+    }
+
+    pub fn undelegate<B: Backend>(db: &mut Db<B>, sender: Address) -> Result<()> {
+        if let Some(delegate) = Self::get_delegate(db, sender) {
+            let mut delegators = Self::get_delegators(db, delegate);
+            delegators.remove(&sender);
+            Self::set_delegators(db, delegate, delegators);
+            Self::set_delegate(db, sender, None);
+        }
+        let mut proposals = Self::get_proposals(db);
+        for proposal in proposals
+            .iter_mut()
+            .filter(|proposal| proposal.result.is_none())
+        {
+            Self::remove_delegator(proposal, sender);
+        }
+        Self::set_proposals(db, proposals);
+        Ok(())
+    }
+
+    // Delegators' MSX isn't locked. Their current balances are counted
+    // towards their delegate's vote until they vote themselves or undelegate.
+    fn cast_vote<B: Backend>(
+        db: &mut Db<B>,
+        proposal: &Proposal,
+        voter: Address,
+        choice: Choice,
+    ) -> Result<Vote> {
+        let balance = Token::get_balance(db, voter, MSX);
+        let delegators: Vec<Address> = Self::get_delegators(db, voter)
+            .into_iter()
+            .filter(|delegator| !proposal.votes.iter().any(|vote| vote.voter == *delegator))
+            .collect();
+        let vote = Vote {
             choice,
-            voter: sender,
+            voter,
             weight: balance,
-        });
-        let votes_for = Self::tally(&proposals[proposal_id].votes, Choice::For);
-        let votes_against = Self::tally(&proposals[proposal_id].votes, Choice::Against);
-        if votes_for * 100 / Token::get_total_supply(db, MSX) > RATIFICATION_THRESHOLD {
-            for action in &proposals[proposal_id].actions {
-                action.run(db, Self::address())?;
-            }
-            proposals[proposal_id].result = Some(Choice::For);
-            Self::return_balances(db, &proposals[proposal_id].votes);
-        } else if votes_against * 100 / Token::get_total_supply(db, Ellipticoin::address())
-            > RATIFICATION_THRESHOLD
-        {
-            proposals[proposal_id].result = Some(Choice::Against);
-            Self::return_balances(db, &proposals[proposal_id].votes);
+            delegators,
+        };
+        Self::validate_voting_weight(Self::get_vote_weight(db, &vote)?)?;
+        charge!(db, voter, MSX, balance)?;
+        Ok(vote)
+    }
+
+    fn remove_vote<B: Backend>(
+        db: &mut Db<B>,
+        proposal: &mut Proposal,
+        voter: Address,
+    ) -> Option<Vote> {
+        let index = proposal.votes.iter().position(|vote| vote.voter == voter)?;
+        let vote = proposal.votes.remove(index);
+        Self::return_balances(db, &[vote.clone()]);
+        Some(vote)
+    }
+
+    fn remove_delegator(proposal: &mut Proposal, delegator: Address) {
+        for vote in proposal.votes.iter_mut() {
+            vote.delegators.retain(|address| *address != delegator);
+        }
+    }
+
+    pub fn get_vote_weight<B: Backend>(db: &mut Db<B>, vote: &Vote) -> Result<Amount> {
+        let delegated_weight = Amount::sum(
+            vote.delegators
+                .iter()
+                .map(|delegator| Token::get_balance(db, *delegator, MSX)),
+        )?;
+        Ok(vote.weight.checked_add(delegated_weight)?)
+    }
+
+    pub fn execute<B: Backend>(db: &mut Db<B>, proposal_id: usize) -> Result<()> {
+        let proposals = Self::get_proposals(db);
+        let proposal = proposals
+            .get(proposal_id)
+            .ok_or_else(|| anyhow!("Proposal {} not found", proposal_id))?;
+        Self::validate_proposal_is_executable(db, proposal)?;
+        let action_results = proposal
+            .actions
+            .iter()
+            .map(|action| {
+                action
+                    .run_in_savepoint(db, Self::address())
+                    .map_err(|err| err.to_string())
+            })
+            .collect();
+        let mut proposals = Self::get_proposals(db);
+        proposals[proposal_id].execution = Some(Execution::Executed(action_results));
+        Self::set_proposals(db, proposals);
+        Ok(())
+    }
+
+    pub fn cancel<B: Backend>(db: &mut Db<B>, sender: Address, proposal_id: usize) -> Result<()> {
+        if sender != Parameters::get_guardian(db) {
+            bail!("Permission denied")
         }
+        let mut proposals = Self::get_proposals(db);
+        let proposal = proposals
+            .get_mut(proposal_id)
+            .ok_or_else(|| anyhow!("Proposal {} not found", proposal_id))?;
+        Self::validate_proposal_is_cancellable(db, proposal)?;
+        proposal.execution = Some(Execution::Cancelled);
         Self::set_proposals(db, proposals);
         Ok(())
     }
 
+    pub fn get_execution_queue<B: Backend>(db: &mut Db<B>) -> Vec<Proposal> {
+        Self::get_proposals(db)
+            .into_iter()
+            .filter(|proposal| matches!(proposal.execution, Some(Execution::Queued(_))))
+            .collect()
+    }
+
     pub fn return_balances<B: Backend>(db: &mut Db<B>, votes: &[Vote]) {
         for vote in votes {
             pay!(db, vote.voter, MSX, vote.weight).unwrap();
         }
     }
 
-    pub fn tally(votes: &[Vote], choice: Choice) -> u64 {
+    pub fn tally<B: Backend>(db: &mut Db<B>, votes: &[Vote], choice: Choice) -> Result<Amount> {
         votes
             .iter()
-            .map(|vote| {
-                if vote.choice == choice {
-                    vote.weight
-                } else {
-                    0
-                }
+            .filter(|vote| vote.choice == choice)
+            .try_fold(Amount::ZERO, |total, vote| {
+                Ok(total.checked_add(Self::get_vote_weight(db, vote)?)?)
             })
-            .sum()
     }
 
     fn increment_proposal_id_counter<B: Backend>(db: &mut Db<B>) -> usize {
@@ -146,9 +295,10 @@ impl Governance {
 
 #[cfg(test)]
 mod tests {
-    use super::{Choice, Governance, Proposal, Vote};
+    use super::{Choice, Execution, Governance, Proposal, Vote};
+    use crate::constants::{EXECUTION_DELAY, GUARDIAN};
     use crate::token::tokens::MSX;
-    use crate::{contract::Contract, Action, Token};
+    use crate::{contract::Contract, Action, System, Token};
     use ellipticoin_test_framework::{
         constants::{
             actors::{ALICE, BOB, CAROL},
@@ -156,17 +306,16 @@ mod tests {
         },
         new_db,
     };
-    use ellipticoin_types::Uint;
+    use ellipticoin_types::{Amount, U256};
     use std::collections::HashMap;
-    use std::convert::TryFrom;
 
     #[test]
     fn create_proposal() {
         let mut db = new_db();
-        let actions = vec![Action::Pay(ALICE, Uint::try_from(1u64).unwrap(), APPLES)];
-        Token::mint(&mut db, 1, MSX, ALICE);
-        Token::mint(&mut db, 1, MSX, BOB);
-        Token::mint(&mut db, 1, MSX, CAROL);
+        let actions = vec![Action::Pay(ALICE, U256::from(1), APPLES)];
+        Token::mint(&mut db, Amount::from(1), MSX, ALICE).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, BOB).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, CAROL).unwrap();
 
         Governance::create_proposal(
             &mut db,
@@ -189,9 +338,11 @@ mod tests {
                 votes: vec![Vote {
                     choice: Choice::For,
                     voter: ALICE,
-                    weight: 1,
+                    weight: Amount::from(1),
+                    delegators: vec![],
                 }],
                 result: None,
+                execution: None,
             }
         );
     }
@@ -202,8 +353,8 @@ mod tests {
         let actions = vec![];
         let mut votes = HashMap::new();
         votes.insert(ALICE, Choice::For);
-        Token::mint(&mut db, 1, MSX, ALICE);
-        Token::mint(&mut db, 100, MSX, BOB);
+        Token::mint(&mut db, Amount::from(1), MSX, ALICE).unwrap();
+        Token::mint(&mut db, Amount::from(100), MSX, BOB).unwrap();
 
         assert_eq!(
             Governance::create_proposal(
@@ -224,9 +375,9 @@ mod tests {
     #[test]
     fn vote_without_moonshine() {
         let mut db = new_db();
-        let actions = vec![Action::Pay(ALICE, Uint::try_from(1u64).unwrap(), APPLES)];
-        Token::mint(&mut db, 1, APPLES, Governance::address());
-        Token::mint(&mut db, 1, MSX, ALICE);
+        let actions = vec![Action::Pay(ALICE, U256::from(1), APPLES)];
+        Token::mint(&mut db, Amount::from(1), APPLES, Governance::address()).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, ALICE).unwrap();
 
         Governance::create_proposal(
             &mut db,
@@ -249,11 +400,11 @@ mod tests {
     #[test]
     fn vote_after_poll_closed() {
         let mut db = new_db();
-        let actions = vec![Action::Pay(ALICE, Uint::try_from(1u64).unwrap(), APPLES)];
-        Token::mint(&mut db, 1, APPLES, Governance::address());
-        Token::mint(&mut db, 1, MSX, ALICE);
-        Token::mint(&mut db, 1, MSX, BOB);
-        Token::mint(&mut db, 1, MSX, CAROL);
+        let actions = vec![Action::Pay(ALICE, U256::from(1), APPLES)];
+        Token::mint(&mut db, Amount::from(1), APPLES, Governance::address()).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, ALICE).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, BOB).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, CAROL).unwrap();
 
         Governance::create_proposal(
             &mut db,
@@ -277,11 +428,79 @@ mod tests {
     #[test]
     fn vote() {
         let mut db = new_db();
-        let actions = vec![Action::Pay(ALICE, Uint::try_from(1u64).unwrap(), APPLES)];
-        Token::mint(&mut db, 1, APPLES, Governance::address());
-        Token::mint(&mut db, 1, MSX, ALICE);
-        Token::mint(&mut db, 1, MSX, BOB);
-        Token::mint(&mut db, 1, MSX, CAROL);
+        let actions = vec![Action::Pay(ALICE, U256::from(1), APPLES)];
+        Token::mint(&mut db, Amount::from(1), APPLES, Governance::address()).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, ALICE).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, BOB).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, CAROL).unwrap();
+
+        Governance::create_proposal(
+            &mut db,
+            ALICE,
+            "Pay Alice".to_string(),
+            "Test Subtitle".to_string(),
+            "Test Content".to_string(),
+            actions.clone(),
+        )
+        .unwrap();
+        Governance::vote(&mut db, BOB, 0, Choice::For).unwrap();
+        assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 0);
+        assert_eq!(
+            Governance::get_proposals(&mut db)[0].execution,
+            Some(Execution::Queued(EXECUTION_DELAY))
+        );
+    }
+
+    #[test]
+    fn execute() {
+        let mut db = new_db();
+        let actions = vec![Action::Pay(ALICE, U256::from(1), APPLES)];
+        Token::mint(&mut db, Amount::from(1), APPLES, Governance::address()).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, ALICE).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, BOB).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, CAROL).unwrap();
+
+        Governance::create_proposal(
+            &mut db,
+            ALICE,
+            "Pay Alice".to_string(),
+            "Test Subtitle".to_string(),
+            "Test Content".to_string(),
+            actions.clone(),
+        )
+        .unwrap();
+        Governance::vote(&mut db, BOB, 0, Choice::For).unwrap();
+        assert_eq!(
+            Governance::execute(&mut db, 0).err().unwrap().to_string(),
+            format!(
+                "Proposal 0 can not be executed until block {}",
+                EXECUTION_DELAY
+            )
+        );
+        System::set_block_number(&mut db, EXECUTION_DELAY);
+        Governance::execute(&mut db, 0).unwrap();
+        assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 1);
+        assert_eq!(
+            Governance::get_proposals(&mut db)[0].execution,
+            Some(Execution::Executed(vec![Ok(())]))
+        );
+        assert_eq!(
+            Governance::execute(&mut db, 0).err().unwrap().to_string(),
+            "Proposal 0 is not queued for execution"
+        );
+    }
+
+    #[test]
+    fn execute_with_failing_action() {
+        let mut db = new_db();
+        let actions = vec![
+            Action::Pay(ALICE, U256::from(1), APPLES),
+            Action::WithdrawBond,
+        ];
+        Token::mint(&mut db, Amount::from(1), APPLES, Governance::address()).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, ALICE).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, BOB).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, CAROL).unwrap();
 
         Governance::create_proposal(
             &mut db,
@@ -293,6 +512,227 @@ mod tests {
         )
         .unwrap();
         Governance::vote(&mut db, BOB, 0, Choice::For).unwrap();
+        System::set_block_number(&mut db, EXECUTION_DELAY);
+        Governance::execute(&mut db, 0).unwrap();
         assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 1);
+        assert_eq!(Token::get_balance(&mut db, CAROL, MSX), 1);
+        assert_eq!(
+            Governance::get_proposals(&mut db)[0].execution,
+            Some(Execution::Executed(vec![
+                Ok(()),
+                Err("0000000000000000000000000000000000000003 has no bond to withdraw".to_string())
+            ]))
+        );
+    }
+
+    #[test]
+    fn cancel() {
+        let mut db = new_db();
+        let actions = vec![Action::Pay(ALICE, U256::from(1), APPLES)];
+        Token::mint(&mut db, Amount::from(1), APPLES, Governance::address()).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, ALICE).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, BOB).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, CAROL).unwrap();
+
+        Governance::create_proposal(
+            &mut db,
+            ALICE,
+            "Pay Alice".to_string(),
+            "Test Subtitle".to_string(),
+            "Test Content".to_string(),
+            actions.clone(),
+        )
+        .unwrap();
+        Governance::vote(&mut db, BOB, 0, Choice::For).unwrap();
+        assert_eq!(
+            Governance::cancel(&mut db, ALICE, 0)
+                .err()
+                .unwrap()
+                .to_string(),
+            "Permission denied"
+        );
+        Governance::cancel(&mut db, GUARDIAN, 0).unwrap();
+        System::set_block_number(&mut db, EXECUTION_DELAY);
+        assert_eq!(
+            Governance::execute(&mut db, 0).err().unwrap().to_string(),
+            "Proposal 0 is not queued for execution"
+        );
+        assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 0);
+    }
+
+    #[test]
+    fn change_vote() {
+        let mut db = new_db();
+        let actions = vec![Action::Pay(ALICE, U256::from(1), APPLES)];
+        Token::mint(&mut db, Amount::from(1), MSX, ALICE).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, BOB).unwrap();
+        Token::mint(&mut db, Amount::from(8), MSX, CAROL).unwrap();
+
+        Governance::create_proposal(
+            &mut db,
+            ALICE,
+            "Pay Alice".to_string(),
+            "Test Subtitle".to_string(),
+            "Test Content".to_string(),
+            actions.clone(),
+        )
+        .unwrap();
+        Governance::vote(&mut db, BOB, 0, Choice::Against).unwrap();
+        Governance::vote(&mut db, BOB, 0, Choice::For).unwrap();
+        assert_eq!(
+            Governance::get_proposals(&mut db)[0].votes,
+            vec![
+                Vote {
+                    choice: Choice::For,
+                    voter: ALICE,
+                    weight: Amount::from(1),
+                    delegators: vec![],
+                },
+                Vote {
+                    choice: Choice::For,
+                    voter: BOB,
+                    weight: Amount::from(1),
+                    delegators: vec![],
+                }
+            ]
+        );
+        assert_eq!(Token::get_balance(&mut db, BOB, MSX), 0);
+        assert_eq!(Governance::get_proposals(&mut db)[0].result, None);
+    }
+
+    #[test]
+    fn withdraw_vote() {
+        let mut db = new_db();
+        let actions = vec![Action::Pay(ALICE, U256::from(1), APPLES)];
+        Token::mint(&mut db, Amount::from(1), MSX, ALICE).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, BOB).unwrap();
+        Token::mint(&mut db, Amount::from(8), MSX, CAROL).unwrap();
+
+        Governance::create_proposal(
+            &mut db,
+            ALICE,
+            "Pay Alice".to_string(),
+            "Test Subtitle".to_string(),
+            "Test Content".to_string(),
+            actions.clone(),
+        )
+        .unwrap();
+        Governance::vote(&mut db, BOB, 0, Choice::Against).unwrap();
+        Governance::withdraw_vote(&mut db, BOB, 0).unwrap();
+        assert_eq!(Governance::get_proposals(&mut db)[0].votes.len(), 1);
+        assert_eq!(Token::get_balance(&mut db, BOB, MSX), 1);
+        assert_eq!(
+            Governance::withdraw_vote(&mut db, BOB, 0)
+                .err()
+                .unwrap()
+                .to_string(),
+            "No vote found on proposal 0"
+        );
+    }
+
+    #[test]
+    fn vote_with_delegated_weight() {
+        let mut db = new_db();
+        let actions = vec![Action::Pay(ALICE, U256::from(1), APPLES)];
+        Token::mint(&mut db, Amount::from(1), APPLES, Governance::address()).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, ALICE).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, CAROL).unwrap();
+        Token::mint(&mut db, Amount::from(8), MSX, Governance::address()).unwrap();
+
+        Governance::create_proposal(
+            &mut db,
+            ALICE,
+            "Pay Alice".to_string(),
+            "Test Subtitle".to_string(),
+            "Test Content".to_string(),
+            actions.clone(),
+        )
+        .unwrap();
+        Governance::delegate(&mut db, CAROL, BOB).unwrap();
+        Governance::vote(&mut db, BOB, 0, Choice::Against).unwrap();
+        assert_eq!(
+            Governance::get_proposals(&mut db)[0].votes[1],
+            Vote {
+                choice: Choice::Against,
+                voter: BOB,
+                weight: Amount::from(0),
+                delegators: vec![CAROL],
+            }
+        );
+        assert_eq!(Token::get_balance(&mut db, CAROL, MSX), 1);
+        let votes = Governance::get_proposals(&mut db)[0].votes.clone();
+        assert_eq!(
+            Governance::tally(&mut db, &votes, Choice::Against).unwrap(),
+            Amount::from(1)
+        );
+
+        Governance::vote(&mut db, CAROL, 0, Choice::For).unwrap();
+        assert_eq!(
+            Governance::get_proposals(&mut db)[0].votes[1],
+            Vote {
+                choice: Choice::Against,
+                voter: BOB,
+                weight: Amount::from(0),
+                delegators: vec![],
+            }
+        );
+        assert_eq!(Governance::get_proposals(&mut db)[0].votes[2].weight, 1);
+    }
+
+    #[test]
+    fn delegated_weight_ratifies_proposal() {
+        let mut db = new_db();
+        let actions = vec![Action::Pay(ALICE, U256::from(1), APPLES)];
+        Token::mint(&mut db, Amount::from(1), APPLES, Governance::address()).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, ALICE).unwrap();
+        Token::mint(&mut db, Amount::from(2), MSX, CAROL).unwrap();
+        Token::mint(&mut db, Amount::from(8), MSX, Governance::address()).unwrap();
+
+        Governance::create_proposal(
+            &mut db,
+            ALICE,
+            "Pay Alice".to_string(),
+            "Test Subtitle".to_string(),
+            "Test Content".to_string(),
+            actions.clone(),
+        )
+        .unwrap();
+        Governance::delegate(&mut db, CAROL, BOB).unwrap();
+        Governance::vote(&mut db, BOB, 0, Choice::For).unwrap();
+        assert_eq!(
+            Governance::get_proposals(&mut db)[0].result,
+            Some(Choice::For)
+        );
+        assert_eq!(Token::get_balance(&mut db, ALICE, MSX), 1);
+        assert_eq!(Token::get_balance(&mut db, BOB, MSX), 0);
+        assert_eq!(Token::get_balance(&mut db, CAROL, MSX), 2);
+    }
+
+    #[test]
+    fn undelegate() {
+        let mut db = new_db();
+        let actions = vec![Action::Pay(ALICE, U256::from(1), APPLES)];
+        Token::mint(&mut db, Amount::from(1), MSX, ALICE).unwrap();
+        Token::mint(&mut db, Amount::from(1), MSX, CAROL).unwrap();
+        Token::mint(&mut db, Amount::from(8), MSX, Governance::address()).unwrap();
+
+        Governance::create_proposal(
+            &mut db,
+            ALICE,
+            "Pay Alice".to_string(),
+            "Test Subtitle".to_string(),
+            "Test Content".to_string(),
+            actions.clone(),
+        )
+        .unwrap();
+        Governance::delegate(&mut db, CAROL, BOB).unwrap();
+        Governance::vote(&mut db, BOB, 0, Choice::Against).unwrap();
+        Governance::undelegate(&mut db, CAROL).unwrap();
+        assert_eq!(Governance::get_delegate(&mut db, CAROL), None);
+        assert!(Governance::get_delegators(&mut db, BOB).is_empty());
+        assert!(Governance::get_proposals(&mut db)[0].votes[1]
+            .delegators
+            .is_empty());
+        assert_eq!(Token::get_balance(&mut db, CAROL, MSX), 1);
     }
 }
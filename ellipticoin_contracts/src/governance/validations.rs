@@ -1,13 +1,14 @@
-use crate::{constants::BASE_FACTOR, governance::Proposal, token::tokens::MSX, Governance, Token};
+use crate::{
+    governance::{Execution, Proposal},
+    token::tokens::MSX,
+    Governance, Parameters, System, Token,
+};
 use anyhow::{bail, Result};
-use ellipticoin_types::{db::Backend, Address, Db};
-
-const MINIMUM_PROPOSAL_THRESHOLD: u64 = 50000;
+use ellipticoin_types::{db::Backend, Address, Amount, Db};
 
 impl Governance {
-    pub fn validate_balance<B: Backend>(db: &mut Db<B>, sender: Address) -> Result<()> {
-        let balance = Token::get_balance(db, sender, MSX);
-        if balance > 0 {
+    pub fn validate_voting_weight(weight: Amount) -> Result<()> {
+        if weight > Amount::ZERO {
             Ok(())
         } else {
             bail!("Moonshine balance greater that zero required for voting")
@@ -22,13 +23,51 @@ impl Governance {
         }
     }
 
+    pub fn validate_proposal_is_executable<B: Backend>(
+        db: &mut Db<B>,
+        proposal: &Proposal,
+    ) -> Result<()> {
+        match proposal.execution {
+            Some(Execution::Queued(executable_at))
+                if System::get_block_number(db) >= executable_at =>
+            {
+                Ok(())
+            }
+            Some(Execution::Queued(executable_at)) => bail!(
+                "Proposal {} can not be executed until block {}",
+                proposal.id,
+                executable_at
+            ),
+            _ => bail!("Proposal {} is not queued for execution", proposal.id),
+        }
+    }
+
+    pub fn validate_proposal_is_cancellable<B: Backend>(
+        db: &mut Db<B>,
+        proposal: &Proposal,
+    ) -> Result<()> {
+        match proposal.execution {
+            Some(Execution::Queued(executable_at))
+                if System::get_block_number(db) < executable_at =>
+            {
+                Ok(())
+            }
+            Some(Execution::Queued(_)) => {
+                bail!("Timelock for proposal {} has already ended", proposal.id)
+            }
+            _ => bail!("Proposal {} is not queued for execution", proposal.id),
+        }
+    }
+
     pub fn validate_minimum_proposal_theshold<B: Backend>(
         db: &mut Db<B>,
         sender: Address,
     ) -> Result<()> {
         let balance = Token::get_balance(db, sender, MSX);
         let total_supply = Token::get_total_supply(db, MSX);
-        if balance > total_supply * MINIMUM_PROPOSAL_THRESHOLD / BASE_FACTOR {
+        let minimum_proposal_threshold =
+            total_supply.mul_fixed(Amount::from(Parameters::get_minimum_proposal_threshold(db)))?;
+        if balance > minimum_proposal_threshold {
             Ok(())
         } else {
             bail!("5 % of total tokens in circulation required to create proposals")
@@ -8,16 +8,18 @@ extern crate hex_literal;
 #[cfg(test)]
 extern crate ellipticoin_test_framework;
 
-mod amm;
+pub mod amm;
 pub mod bridge;
 pub mod constants;
 pub mod contract;
-mod crypto;
+pub mod crypto;
 mod ellipticoin;
 pub mod governance;
 pub mod hash_onion;
 mod helpers;
+pub mod multisig;
 pub mod order_book;
+pub mod parameters;
 pub mod system;
 pub mod token;
 mod types;
@@ -27,7 +29,9 @@ pub use bridge::Bridge;
 pub use ellipticoin::{Ellipticoin, Miner};
 pub use governance::Governance;
 pub use hash_onion::*;
+pub use multisig::Multisig;
 pub use order_book::OrderBook;
+pub use parameters::Parameters;
 pub use system::{Action, System, Transaction};
 pub use token::Token;
 pub use types::*;
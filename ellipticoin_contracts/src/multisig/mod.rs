@@ -0,0 +1,276 @@
+use crate::{
+    contract::{self, Contract},
+    crypto::sha256,
+    Action,
+};
+use anyhow::{anyhow, bail, Result};
+use ellipticoin_macros::db_accessors;
+use ellipticoin_types::{
+    db::{Backend, Db},
+    Address,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+
+pub struct Multisig;
+
+impl Contract for Multisig {
+    const NAME: contract::Name = contract::Name::Multisig;
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Account {
+    pub address: Address,
+    pub owners: Vec<Address>,
+    pub threshold: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Submission {
+    pub id: usize,
+    pub submitter: Address,
+    pub action: Action,
+    pub approvals: Vec<Address>,
+    pub execution: Option<Result<(), String>>,
+}
+
+db_accessors!(Multisig {
+    account(address: Address) -> Option<Account>;
+    account_counter() -> u64;
+    owned_accounts(owner: Address) -> Vec<Address>;
+    submissions(account: Address) -> Vec<Submission>;
+});
+
+impl Multisig {
+    pub fn create<B: Backend>(
+        db: &mut Db<B>,
+        owners: Vec<Address>,
+        threshold: u64,
+    ) -> Result<Address> {
+        if threshold == 0 || threshold > owners.len() as u64 {
+            bail!("Threshold must be between 1 and {}", owners.len())
+        }
+        if owners
+            .iter()
+            .enumerate()
+            .any(|(index, owner)| owners[..index].contains(owner))
+        {
+            bail!("Owners must be unique")
+        }
+        let account_counter = Self::get_account_counter(db);
+        let address = Address(
+            sha256(
+                [
+                    Self::address().0.to_vec(),
+                    account_counter.to_be_bytes().to_vec(),
+                ]
+                .concat(),
+            )[..20]
+                .try_into()
+                .unwrap(),
+        );
+        Self::set_account_counter(db, account_counter + 1);
+        for owner in &owners {
+            let mut owned_accounts = Self::get_owned_accounts(db, *owner);
+            owned_accounts.push(address);
+            Self::set_owned_accounts(db, *owner, owned_accounts);
+        }
+        Self::set_account(
+            db,
+            address,
+            Some(Account {
+                address,
+                owners,
+                threshold,
+            }),
+        );
+        Ok(address)
+    }
+
+    // Submitting an action counts as the submitter's approval.
+    pub fn submit<B: Backend>(
+        db: &mut Db<B>,
+        sender: Address,
+        account: Address,
+        action: Action,
+    ) -> Result<()> {
+        Self::get_owned_account(db, sender, account)?;
+        let mut submissions = Self::get_submissions(db, account);
+        let id = submissions.len();
+        submissions.push(Submission {
+            id,
+            submitter: sender,
+            action,
+            approvals: vec![],
+            execution: None,
+        });
+        Self::set_submissions(db, account, submissions);
+        Self::approve(db, sender, account, id)
+    }
+
+    pub fn approve<B: Backend>(
+        db: &mut Db<B>,
+        sender: Address,
+        account: Address,
+        id: usize,
+    ) -> Result<()> {
+        let threshold = Self::get_owned_account(db, sender, account)?.threshold;
+        let mut submissions = Self::get_submissions(db, account);
+        let submission = submissions
+            .get_mut(id)
+            .ok_or_else(|| anyhow!("Submission {} not found", id))?;
+        if submission.execution.is_some() {
+            bail!("Submission {} has already been executed", id)
+        }
+        if submission.approvals.contains(&sender) {
+            bail!(
+                "{} has already approved submission {}",
+                hex::encode(sender),
+                id
+            )
+        }
+        submission.approvals.push(sender);
+        let action = submission.action.clone();
+        let approved = submission.approvals.len() as u64 >= threshold;
+        Self::set_submissions(db, account, submissions);
+        if approved {
+            let execution = action
+                .run_in_savepoint(db, account)
+                .map_err(|err| err.to_string());
+            let mut submissions = Self::get_submissions(db, account);
+            submissions[id].execution = Some(execution);
+            Self::set_submissions(db, account, submissions);
+        }
+        Ok(())
+    }
+
+    fn get_owned_account<B: Backend>(
+        db: &mut Db<B>,
+        sender: Address,
+        account: Address,
+    ) -> Result<Account> {
+        let account = Self::get_account(db, account)
+            .ok_or_else(|| anyhow!("Multisig account {} not found", hex::encode(account)))?;
+        if !account.owners.contains(&sender) {
+            bail!(
+                "{} is not an owner of {}",
+                hex::encode(sender),
+                hex::encode(account.address)
+            )
+        }
+        Ok(account)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Token;
+    use ellipticoin_test_framework::{
+        constants::{
+            actors::{ALICE, BOB, CAROL},
+            tokens::APPLES,
+        },
+        new_db,
+    };
+    use ellipticoin_types::{Amount, U256};
+
+    #[test]
+    fn test_create() {
+        let mut db = new_db();
+        let account = Multisig::create(&mut db, vec![ALICE, BOB, CAROL], 2).unwrap();
+        assert_eq!(
+            Multisig::get_account(&mut db, account),
+            Some(Account {
+                address: account,
+                owners: vec![ALICE, BOB, CAROL],
+                threshold: 2,
+            })
+        );
+        assert_eq!(Multisig::get_owned_accounts(&mut db, BOB), vec![account]);
+        assert_ne!(
+            Multisig::create(&mut db, vec![ALICE, BOB, CAROL], 2).unwrap(),
+            account
+        );
+    }
+
+    #[test]
+    fn test_create_with_invalid_threshold() {
+        let mut db = new_db();
+        assert_eq!(
+            Multisig::create(&mut db, vec![ALICE, BOB], 3)
+                .err()
+                .unwrap()
+                .to_string(),
+            "Threshold must be between 1 and 2"
+        );
+    }
+
+    #[test]
+    fn test_submit_and_approve() {
+        let mut db = new_db();
+        let account = Multisig::create(&mut db, vec![ALICE, BOB, CAROL], 2).unwrap();
+        Token::set_balance(&mut db, account, APPLES, Amount::from(100));
+        Multisig::submit(
+            &mut db,
+            ALICE,
+            account,
+            Action::Pay(CAROL, U256::from(20), APPLES),
+        )
+        .unwrap();
+        assert_eq!(Token::get_balance(&mut db, account, APPLES), 100);
+        assert_eq!(
+            Multisig::approve(&mut db, ALICE, account, 0)
+                .err()
+                .unwrap()
+                .to_string(),
+            "aaa1b967f4e3d67c4946ec6816b05f0207aad9cd has already approved submission 0"
+        );
+        Multisig::approve(&mut db, BOB, account, 0).unwrap();
+        assert_eq!(Token::get_balance(&mut db, account, APPLES), 80);
+        assert_eq!(Token::get_balance(&mut db, CAROL, APPLES), 20);
+        assert_eq!(
+            Multisig::get_submissions(&mut db, account)[0].execution,
+            Some(Ok(()))
+        );
+        assert_eq!(
+            Multisig::approve(&mut db, CAROL, account, 0)
+                .err()
+                .unwrap()
+                .to_string(),
+            "Submission 0 has already been executed"
+        );
+    }
+
+    #[test]
+    fn test_failed_execution() {
+        let mut db = new_db();
+        let account = Multisig::create(&mut db, vec![ALICE], 1).unwrap();
+        Multisig::submit(&mut db, ALICE, account, Action::WithdrawBond).unwrap();
+        assert_eq!(
+            Multisig::get_submissions(&mut db, account)[0].execution,
+            Some(Err(format!(
+                "{} has no bond to withdraw",
+                hex::encode(account)
+            )))
+        );
+    }
+
+    #[test]
+    fn test_approve_without_permission() {
+        let mut db = new_db();
+        let account = Multisig::create(&mut db, vec![ALICE, BOB], 2).unwrap();
+        Multisig::submit(&mut db, ALICE, account, Action::Null).unwrap();
+        assert_eq!(
+            Multisig::approve(&mut db, CAROL, account, 0)
+                .err()
+                .unwrap()
+                .to_string(),
+            format!(
+                "{} is not an owner of {}",
+                hex::encode(CAROL),
+                hex::encode(account)
+            )
+        );
+    }
+}
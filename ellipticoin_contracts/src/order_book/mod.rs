@@ -1,6 +1,5 @@
 use crate::{
     charge,
-    constants::BASE_FACTOR,
     contract::{self, Contract},
     pay,
     token::tokens::USD,
@@ -9,8 +8,9 @@ use crate::{
 use anyhow::{anyhow, bail, Result};
 use ellipticoin_macros::db_accessors;
 use ellipticoin_types::{
+    amount,
     db::{Backend, Db},
-    Address,
+    Address, Amount,
 };
 use serde::{Deserialize, Serialize};
 
@@ -25,16 +25,17 @@ pub struct Order {
     pub order_type: OrderType,
     pub sender: Address,
     pub token: Address,
-    pub amount: u64,
-    pub price: u64,
+    pub amount: Amount,
+    #[serde(deserialize_with = "amount::deserialize_fixed")]
+    pub price: Amount,
 }
 
 impl Order {
-    pub fn get_underlying_price<B: Backend>(&self, db: &mut Db<B>) -> u64 {
+    pub fn get_underlying_price<B: Backend>(&self, db: &mut Db<B>) -> Result<Amount> {
         Token::underlying_to_amount(db, self.price, self.token)
     }
 
-    pub fn get_underlying_amount<B: Backend>(&self, db: &mut Db<B>) -> u64 {
+    pub fn get_underlying_amount<B: Backend>(&self, db: &mut Db<B>) -> Result<Amount> {
         Token::amount_to_underlying(db, self.amount, self.token)
     }
 }
@@ -55,9 +56,9 @@ impl OrderBook {
         db: &mut Db<B>,
         sender: Address,
         order_type: OrderType,
-        amount: u64,
+        amount: Amount,
         token: Address,
-        price: u64,
+        price: Amount,
     ) -> Result<()> {
         let mut orders = Self::get_orders(db);
         let order = Order {
@@ -70,7 +71,7 @@ impl OrderBook {
         };
         match order.order_type {
             OrderType::Buy => {
-                charge!(db, sender, USD, amount * price / BASE_FACTOR)?;
+                charge!(db, sender, USD, amount.mul_fixed(price)?)?;
             }
             OrderType::Sell => {
                 charge!(db, sender, token, amount)?;
@@ -116,14 +117,14 @@ impl OrderBook {
         match order.order_type {
             OrderType::Buy => {
                 Token::transfer(db, sender, order.sender, order.amount, order.token)?;
-                pay!(db, sender, USD, order.amount * order.price / BASE_FACTOR)?;
+                pay!(db, sender, USD, order.amount.mul_fixed(order.price)?)?;
             }
             OrderType::Sell => {
                 Token::transfer(
                     db,
                     sender,
                     order.sender,
-                    order.amount * order.price / BASE_FACTOR,
+                    order.amount.mul_fixed(order.price)?,
                     USD,
                 )?;
                 pay!(db, sender, order.token, order.amount)?;
@@ -153,21 +154,31 @@ mod tests {
         },
         new_db,
     };
+    use ellipticoin_types::{Address, Amount};
+    use serde::Serialize;
 
     #[test]
     fn test_create_order() {
         let mut db = new_db();
-        Token::set_balance(&mut db, ALICE, APPLES, 1);
-        OrderBook::create_order(&mut db, ALICE, OrderType::Sell, 1, APPLES, 1).unwrap();
+        Token::set_balance(&mut db, ALICE, APPLES, Amount::from(1));
+        OrderBook::create_order(
+            &mut db,
+            ALICE,
+            OrderType::Sell,
+            Amount::from(1),
+            APPLES,
+            Amount::from(1),
+        )
+        .unwrap();
         assert_eq!(
             OrderBook::get_orders(&mut db)[0],
             Order {
                 id: 0,
                 order_type: OrderType::Sell,
                 token: APPLES,
-                amount: 1,
+                amount: Amount::from(1),
                 sender: ALICE,
-                price: 1
+                price: Amount::from(1)
             }
         );
     }
@@ -175,8 +186,16 @@ mod tests {
     #[test]
     fn test_cancel() {
         let mut db = new_db();
-        Token::set_balance(&mut db, ALICE, APPLES, 1);
-        OrderBook::create_order(&mut db, ALICE, OrderType::Sell, 1, APPLES, 1).unwrap();
+        Token::set_balance(&mut db, ALICE, APPLES, Amount::from(1));
+        OrderBook::create_order(
+            &mut db,
+            ALICE,
+            OrderType::Sell,
+            Amount::from(1),
+            APPLES,
+            Amount::from(1),
+        )
+        .unwrap();
         OrderBook::cancel(&mut db, ALICE, 0).unwrap();
         assert_eq!(OrderBook::get_orders(&mut db), vec![]);
         assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 1);
@@ -185,9 +204,17 @@ mod tests {
     #[test]
     fn test_fill_sell() {
         let mut db = new_db();
-        Token::set_balance(&mut db, ALICE, APPLES, 1);
-        Token::set_balance(&mut db, BOB, USD, 1);
-        OrderBook::create_order(&mut db, ALICE, OrderType::Sell, 1, APPLES, BASE_FACTOR).unwrap();
+        Token::set_balance(&mut db, ALICE, APPLES, Amount::from(1));
+        Token::set_balance(&mut db, BOB, USD, Amount::from(1));
+        OrderBook::create_order(
+            &mut db,
+            ALICE,
+            OrderType::Sell,
+            Amount::from(1),
+            APPLES,
+            Amount::from(BASE_FACTOR),
+        )
+        .unwrap();
         OrderBook::fill(&mut db, BOB, 0).unwrap();
         assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 0);
         assert_eq!(Token::get_balance(&mut db, ALICE, USD), 1);
@@ -198,13 +225,50 @@ mod tests {
     #[test]
     fn test_fill_buy() {
         let mut db = new_db();
-        Token::set_balance(&mut db, ALICE, USD, 1);
-        Token::set_balance(&mut db, BOB, APPLES, 1);
-        OrderBook::create_order(&mut db, ALICE, OrderType::Buy, 1, APPLES, BASE_FACTOR).unwrap();
+        Token::set_balance(&mut db, ALICE, USD, Amount::from(1));
+        Token::set_balance(&mut db, BOB, APPLES, Amount::from(1));
+        OrderBook::create_order(
+            &mut db,
+            ALICE,
+            OrderType::Buy,
+            Amount::from(1),
+            APPLES,
+            Amount::from(BASE_FACTOR),
+        )
+        .unwrap();
         OrderBook::fill(&mut db, BOB, 0).unwrap();
         assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 1);
         assert_eq!(Token::get_balance(&mut db, ALICE, USD), 0);
         assert_eq!(Token::get_balance(&mut db, BOB, APPLES), 0);
         assert_eq!(Token::get_balance(&mut db, BOB, USD), 1);
     }
+
+    #[test]
+    fn test_load_legacy_order() {
+        #[derive(Serialize)]
+        struct LegacyOrder {
+            id: u64,
+            order_type: OrderType,
+            sender: Address,
+            token: Address,
+            amount: u64,
+            price: u64,
+        }
+        let legacy_order = serde_cbor::to_vec(&LegacyOrder {
+            id: 0,
+            order_type: OrderType::Buy,
+            sender: ALICE,
+            token: APPLES,
+            amount: 2_000_000,
+            price: 1_500_000,
+        })
+        .unwrap();
+        let order: Order = serde_cbor::from_slice(&legacy_order).unwrap();
+        assert_eq!(order.amount, Amount::from_legacy(2_000_000));
+        assert_eq!(order.price, Amount::from(3 * BASE_FACTOR / 2));
+        assert_eq!(
+            order.amount.mul_fixed(order.price).unwrap(),
+            Amount::from_legacy(3_000_000)
+        );
+    }
 }
@@ -0,0 +1,291 @@
+mod validations;
+
+use crate::{
+    constants::{
+        BLOCK_TIME, EXECUTION_DELAY, FEE, FEE_TOKEN, GAS_PRICE, GUARDIAN, MINER_BOND,
+        MINER_TIMEOUT, MINER_UNBONDING_PERIOD, MINIMUM_PROPOSAL_THRESHOLD, RATIFICATION_THRESHOLD,
+    },
+    contract::{self, Contract},
+    ellipticoin::{IssuanceSchedule, ISSUANCE_SCHEDULE},
+    Governance,
+};
+use anyhow::{bail, Result};
+use ellipticoin_macros::db_accessors;
+use ellipticoin_types::{
+    db::{Backend, Db},
+    Address, Amount,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+pub struct Parameters;
+
+impl Contract for Parameters {
+    const NAME: contract::Name = contract::Name::Parameters;
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Parameter {
+    BlockTime(u64),
+    ExecutionDelay(u64),
+    Fee(u64),
+    FeeToken(Address),
+    GasPrice(Amount),
+    Guardian(Address),
+    IssuanceSchedule(IssuanceSchedule),
+    MinerBond(Amount),
+    MinerTimeout(u64),
+    MinerUnbondingPeriod(u64),
+    MinimumProposalThreshold(u64),
+    MissedBlockPenalty(Amount),
+    RatificationThreshold(u64),
+}
+
+db_accessors!(Parameters {
+    block_time() -> u64;
+    execution_delay() -> u64;
+    fee() -> u64;
+    fee_token() -> Address;
+    gas_price() -> Amount;
+    guardian() -> Address;
+    issuance_schedule() -> IssuanceSchedule;
+    miner_bond() -> Amount;
+    miner_timeout() -> u64;
+    miner_unbonding_period() -> u64;
+    minimum_proposal_threshold() -> u64;
+    missed_block_penalty() -> Amount;
+    ratification_threshold() -> u64;
+});
+
+impl Parameters {
+    // Each parameter is seeded on its own so that parameters added later get
+    // their defaults while values set by governance are kept.
+    pub fn migrate<B: Backend>(db: &mut Db<B>) {
+        Self::seed::<u64, _>(db, Namespace::BlockTime, |db| {
+            Self::set_block_time(db, BLOCK_TIME.as_secs())
+        });
+        Self::seed::<u64, _>(db, Namespace::ExecutionDelay, |db| {
+            Self::set_execution_delay(db, EXECUTION_DELAY)
+        });
+        Self::seed::<u64, _>(db, Namespace::Fee, |db| Self::set_fee(db, FEE));
+        Self::seed::<Address, _>(db, Namespace::FeeToken, |db| {
+            Self::set_fee_token(db, FEE_TOKEN)
+        });
+        Self::seed::<Amount, _>(db, Namespace::GasPrice, |db| {
+            Self::set_gas_price(db, *GAS_PRICE)
+        });
+        Self::seed::<Address, _>(db, Namespace::Guardian, |db| {
+            Self::set_guardian(db, GUARDIAN)
+        });
+        Self::seed::<IssuanceSchedule, _>(db, Namespace::IssuanceSchedule, |db| {
+            Self::set_issuance_schedule(db, ISSUANCE_SCHEDULE)
+        });
+        Self::seed::<Amount, _>(db, Namespace::MinerBond, |db| {
+            Self::set_miner_bond(db, *MINER_BOND)
+        });
+        Self::seed::<u64, _>(db, Namespace::MinerTimeout, |db| {
+            Self::set_miner_timeout(db, MINER_TIMEOUT)
+        });
+        Self::seed::<u64, _>(db, Namespace::MinerUnbondingPeriod, |db| {
+            Self::set_miner_unbonding_period(db, MINER_UNBONDING_PERIOD)
+        });
+        Self::seed::<u64, _>(db, Namespace::MinimumProposalThreshold, |db| {
+            Self::set_minimum_proposal_threshold(db, MINIMUM_PROPOSAL_THRESHOLD)
+        });
+        Self::seed::<Amount, _>(db, Namespace::MissedBlockPenalty, |db| {
+            Self::set_missed_block_penalty(db, Amount::ZERO)
+        });
+        Self::seed::<u64, _>(db, Namespace::RatificationThreshold, |db| {
+            Self::set_ratification_threshold(db, RATIFICATION_THRESHOLD)
+        });
+    }
+
+    fn seed<T: DeserializeOwned, B: Backend>(
+        db: &mut Db<B>,
+        namespace: Namespace,
+        set: impl FnOnce(&mut Db<B>),
+    ) {
+        let value: Option<T> = Self::get(db, u16::to_le_bytes(namespace as u16).to_vec());
+        if value.is_none() {
+            set(db)
+        }
+    }
+
+    pub fn set_parameter<B: Backend>(
+        db: &mut Db<B>,
+        sender: Address,
+        parameter: Parameter,
+    ) -> Result<()> {
+        if sender != Governance::address() {
+            bail!("Parameters can only be set by governance")
+        }
+        Self::validate_parameter(db, &parameter)?;
+        match parameter {
+            Parameter::BlockTime(block_time) => Self::set_block_time(db, block_time),
+            Parameter::ExecutionDelay(execution_delay) => {
+                Self::set_execution_delay(db, execution_delay)
+            }
+            Parameter::Fee(fee) => Self::set_fee(db, fee),
+            Parameter::FeeToken(fee_token) => Self::set_fee_token(db, fee_token),
+            Parameter::GasPrice(gas_price) => Self::set_gas_price(db, gas_price),
+            Parameter::Guardian(guardian) => Self::set_guardian(db, guardian),
+            Parameter::IssuanceSchedule(issuance_schedule) => {
+                Self::set_issuance_schedule(db, issuance_schedule)
+            }
+            Parameter::MinerBond(miner_bond) => Self::set_miner_bond(db, miner_bond),
+            Parameter::MinerTimeout(miner_timeout) => Self::set_miner_timeout(db, miner_timeout),
+            Parameter::MinerUnbondingPeriod(miner_unbonding_period) => {
+                Self::set_miner_unbonding_period(db, miner_unbonding_period)
+            }
+            Parameter::MinimumProposalThreshold(minimum_proposal_threshold) => {
+                Self::set_minimum_proposal_threshold(db, minimum_proposal_threshold)
+            }
+            Parameter::MissedBlockPenalty(missed_block_penalty) => {
+                Self::set_missed_block_penalty(db, missed_block_penalty)
+            }
+            Parameter::RatificationThreshold(ratification_threshold) => {
+                Self::set_ratification_threshold(db, ratification_threshold)
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Namespace, Parameter, Parameters};
+    use crate::{
+        constants::{BASE_FACTOR, FEE, MINER_TIMEOUT},
+        contract::Contract,
+        ellipticoin::ISSUANCE_SCHEDULE,
+        Governance,
+    };
+    use ellipticoin_test_framework::{constants::actors::ALICE, new_db};
+    use ellipticoin_types::Db;
+
+    #[test]
+    fn set_parameter() {
+        let mut db = new_db();
+        assert_eq!(Parameters::get_fee(&mut db), FEE);
+        Parameters::set_parameter(&mut db, Governance::address(), Parameter::Fee(1_000)).unwrap();
+        assert_eq!(Parameters::get_fee(&mut db), 1_000);
+    }
+
+    #[test]
+    fn set_parameter_without_permission() {
+        let mut db = new_db();
+        assert_eq!(
+            Parameters::set_parameter(&mut db, ALICE, Parameter::Fee(1_000))
+                .err()
+                .unwrap()
+                .to_string(),
+            "Parameters can only be set by governance"
+        );
+        assert_eq!(Parameters::get_fee(&mut db), FEE);
+    }
+
+    #[test]
+    fn migrate_keeps_existing_parameters() {
+        let mut db = new_db();
+        Parameters::set_parameter(&mut db, Governance::address(), Parameter::Fee(0)).unwrap();
+        Parameters::migrate(&mut db);
+        assert_eq!(Parameters::get_fee(&mut db), 0);
+    }
+
+    #[test]
+    fn migrate_seeds_missing_parameters() {
+        let mut db = new_db();
+        Parameters::set_parameter(&mut db, Governance::address(), Parameter::Fee(1_000)).unwrap();
+        db.commit();
+        let key = [
+            (Parameters::NAME as u16).to_le_bytes(),
+            (Namespace::MinerTimeout as u16).to_le_bytes(),
+        ]
+        .concat();
+        db.insert_raw(&key, &[]);
+        assert_eq!(Parameters::get_miner_timeout(&mut db), 0);
+        Parameters::migrate(&mut db);
+        assert_eq!(Parameters::get_miner_timeout(&mut db), MINER_TIMEOUT);
+        assert_eq!(Parameters::get_fee(&mut db), 1_000);
+    }
+
+    fn assert_rejected<B: ellipticoin_types::db::Backend>(
+        db: &mut Db<B>,
+        parameter: Parameter,
+        error: &str,
+    ) {
+        assert_eq!(
+            Parameters::set_parameter(db, Governance::address(), parameter)
+                .err()
+                .unwrap()
+                .to_string(),
+            error
+        );
+    }
+
+    #[test]
+    fn set_invalid_parameters() {
+        let mut db = new_db();
+        assert_rejected(
+            &mut db,
+            Parameter::BlockTime(0),
+            "Block time must be greater than zero",
+        );
+        assert_rejected(
+            &mut db,
+            Parameter::MinerTimeout(0),
+            "Miner timeout must be greater than zero",
+        );
+        assert_rejected(
+            &mut db,
+            Parameter::BlockTime(u64::MAX),
+            "Block time times the miner timeout overflows",
+        );
+        assert_rejected(
+            &mut db,
+            Parameter::Fee(BASE_FACTOR + 1),
+            "Fee must be at most 1000000",
+        );
+        assert_rejected(
+            &mut db,
+            Parameter::MinimumProposalThreshold(BASE_FACTOR + 1),
+            "Minimum proposal threshold must be at most 1000000",
+        );
+        assert_rejected(
+            &mut db,
+            Parameter::RatificationThreshold(100),
+            "Ratification threshold must be less than 100",
+        );
+        assert_rejected(
+            &mut db,
+            Parameter::FeeToken(ALICE),
+            "Fee token aaa1b967f4e3d67c4946ec6816b05f0207aad9cd isn't registered",
+        );
+        assert_eq!(Parameters::get_fee(&mut db), FEE);
+    }
+
+    #[test]
+    fn set_invalid_issuance_schedule() {
+        let mut db = new_db();
+        let mut issuance_schedule = ISSUANCE_SCHEDULE;
+        issuance_schedule.blocks_per_era = 0;
+        assert_rejected(
+            &mut db,
+            Parameter::IssuanceSchedule(issuance_schedule),
+            "Blocks per era must be greater than zero",
+        );
+        let mut issuance_schedule = ISSUANCE_SCHEDULE;
+        issuance_schedule.number_of_eras = 64;
+        assert_rejected(
+            &mut db,
+            Parameter::IssuanceSchedule(issuance_schedule),
+            "Number of eras must be less than 64",
+        );
+        let mut issuance_schedule = ISSUANCE_SCHEDULE;
+        issuance_schedule.blocks_per_era = u64::MAX;
+        assert_rejected(
+            &mut db,
+            Parameter::IssuanceSchedule(issuance_schedule),
+            "Blocks per era times the number of eras overflows",
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use super::Parameter;
+use crate::{constants::BASE_FACTOR, Parameters, Token};
+use anyhow::{bail, Result};
+use ellipticoin_types::db::{Backend, Db};
+
+impl Parameters {
+    pub fn validate_parameter<B: Backend>(db: &mut Db<B>, parameter: &Parameter) -> Result<()> {
+        match parameter {
+            Parameter::BlockTime(block_time) => {
+                if *block_time == 0 {
+                    bail!("Block time must be greater than zero")
+                }
+                if block_time
+                    .checked_mul(Self::get_miner_timeout(db))
+                    .is_none()
+                {
+                    bail!("Block time times the miner timeout overflows")
+                }
+            }
+            Parameter::MinerTimeout(miner_timeout) => {
+                if *miner_timeout == 0 {
+                    bail!("Miner timeout must be greater than zero")
+                }
+                if miner_timeout
+                    .checked_mul(Self::get_block_time(db))
+                    .is_none()
+                {
+                    bail!("Block time times the miner timeout overflows")
+                }
+            }
+            Parameter::Fee(fee) if *fee > BASE_FACTOR => {
+                bail!("Fee must be at most {}", BASE_FACTOR)
+            }
+            Parameter::FeeToken(fee_token)
+                if Token::get_token_metadata(db, *fee_token).is_none() =>
+            {
+                bail!("Fee token {} isn't registered", fee_token)
+            }
+            Parameter::IssuanceSchedule(issuance_schedule) => {
+                if issuance_schedule.blocks_per_era == 0 {
+                    bail!("Blocks per era must be greater than zero")
+                }
+                if issuance_schedule.number_of_eras >= 64 {
+                    bail!("Number of eras must be less than 64")
+                }
+                if issuance_schedule
+                    .blocks_per_era
+                    .checked_mul(issuance_schedule.number_of_eras)
+                    .is_none()
+                {
+                    bail!("Blocks per era times the number of eras overflows")
+                }
+            }
+            Parameter::MinimumProposalThreshold(minimum_proposal_threshold)
+                if *minimum_proposal_threshold > BASE_FACTOR =>
+            {
+                bail!("Minimum proposal threshold must be at most {}", BASE_FACTOR)
+            }
+            Parameter::RatificationThreshold(ratification_threshold)
+                if *ratification_threshold >= 100 =>
+            {
+                bail!("Ratification threshold must be less than 100")
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+}
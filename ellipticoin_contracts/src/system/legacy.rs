@@ -0,0 +1,197 @@
+use crate::{
+    bridge::{EthereumMessage, PolygonMessage},
+    Action, System, Token,
+};
+use anyhow::Result;
+use ellipticoin_types::{
+    db::{Backend, Db},
+    Address, Amount, Uint, U256,
+};
+use serde::{Deserialize, Serialize};
+
+// Transactions logged before amounts became 256-bit stored them as u64s with 6
+// decimals of precision. They're upgraded to the current actions when they're
+// replayed.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyTransaction {
+    pub transaction_number: u64,
+    pub action: LegacyAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum LegacyAction {
+    AddLiquidity(Uint, Address),
+    Buy(Uint, Address, Uint),
+    CreatePool(Uint, Address, Uint),
+    CreateWithdrawlRequest(Uint, Address),
+    ProcessEthereumMessages(Vec<EthereumMessage>, Uint),
+    ProcessPolygonMessages(Vec<LegacyPolygonMessage>, Uint),
+    Null,
+    Pay(Address, Uint, Address),
+    Sell(Uint, Address, Uint),
+    RemoveLiquidity(Uint, Address),
+    Seal([u8; 32]),
+    StartMining(String, [u8; 32], Uint),
+}
+
+impl Default for LegacyAction {
+    fn default() -> Self {
+        LegacyAction::Null
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum LegacyPolygonMessage {
+    Deposit(u64, Address, Address),
+    ProcessWithdrawl(u64, [u8; 32]),
+}
+
+impl LegacyTransaction {
+    // Legacy transactions were free and their transaction numbers weren't
+    // checked. They were only incremented when the action succeeded.
+    pub fn run<B: Backend>(&self, db: &mut Db<B>, sender: Address) -> Result<u64> {
+        let transaction_id = self.action.upgrade(db)?.run(db, sender)?;
+        System::increment_transaction_number(db, sender);
+        db.commit();
+        Ok(transaction_id)
+    }
+}
+
+impl LegacyAction {
+    pub fn upgrade<B: Backend>(&self, db: &mut Db<B>) -> Result<Action> {
+        Ok(match self {
+            LegacyAction::AddLiquidity(amount, token) => {
+                Action::AddLiquidity(upgrade_amount(*amount), *token)
+            }
+            LegacyAction::Buy(input_amount, token, minimum_output_amount) => Action::Buy(
+                upgrade_amount(*input_amount),
+                *token,
+                upgrade_amount(*minimum_output_amount),
+            ),
+            LegacyAction::CreatePool(amount, token, starting_price) => Action::CreatePool(
+                upgrade_amount(*amount),
+                *token,
+                U256::from(u64::from(*starting_price)),
+            ),
+            LegacyAction::CreateWithdrawlRequest(amount, token) => {
+                Action::CreateWithdrawlRequest(upgrade_amount(*amount), *token)
+            }
+            LegacyAction::ProcessEthereumMessages(messages, block_number) => {
+                Action::ProcessEthereumMessages(messages.clone(), *block_number)
+            }
+            LegacyAction::ProcessPolygonMessages(messages, block_number) => {
+                Action::ProcessPolygonMessages(
+                    messages
+                        .iter()
+                        .map(|message| message.upgrade(db))
+                        .collect::<Result<_>>()?,
+                    *block_number,
+                )
+            }
+            LegacyAction::Null => Action::Null,
+            LegacyAction::Pay(recipient, amount, token) => {
+                Action::Pay(*recipient, upgrade_amount(*amount), *token)
+            }
+            LegacyAction::Sell(input_amount, token, minimum_output_amount) => Action::Sell(
+                upgrade_amount(*input_amount),
+                *token,
+                upgrade_amount(*minimum_output_amount),
+            ),
+            LegacyAction::RemoveLiquidity(percentage, token) => {
+                Action::RemoveLiquidity(*percentage, *token)
+            }
+            LegacyAction::Seal(onion_skin) => Action::Seal(*onion_skin),
+            LegacyAction::StartMining(host, onion_skin, layer_count) => {
+                Action::StartMining(host.clone(), *onion_skin, *layer_count)
+            }
+        })
+    }
+}
+
+impl LegacyPolygonMessage {
+    // Deposits used to be credited in internal units. They're now passed in
+    // the token's native units.
+    fn upgrade<B: Backend>(&self, db: &mut Db<B>) -> Result<PolygonMessage> {
+        Ok(match self {
+            LegacyPolygonMessage::Deposit(amount, token, address) => PolygonMessage::Deposit(
+                Token::amount_to_native(db, Amount::from_legacy(*amount), *token)?,
+                *token,
+                *address,
+            ),
+            LegacyPolygonMessage::ProcessWithdrawl(withdrawl_id, transaction_hash) => {
+                PolygonMessage::ProcessWithdrawl(*withdrawl_id, *transaction_hash)
+            }
+        })
+    }
+}
+
+fn upgrade_amount(amount: Uint) -> U256 {
+    Amount::from_legacy(u64::from(amount)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::tokens::WBTC;
+    use ellipticoin_test_framework::{
+        constants::{
+            actors::{ALICE, BOB},
+            tokens::APPLES,
+        },
+        new_db,
+    };
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_replay_scales_amounts() {
+        let mut db = new_db();
+        Token::set_balance(&mut db, ALICE, APPLES, Amount::from_legacy(100));
+        let bytes = serde_cbor::to_vec(&LegacyTransaction {
+            transaction_number: 1,
+            action: LegacyAction::Pay(BOB, Uint::try_from(20u64).unwrap(), APPLES),
+        })
+        .unwrap();
+        let transaction: LegacyTransaction = serde_cbor::from_slice(&bytes).unwrap();
+        transaction.run(&mut db, ALICE).unwrap();
+        assert_eq!(
+            Token::get_balance(&mut db, ALICE, APPLES),
+            Amount::from_legacy(80)
+        );
+        assert_eq!(
+            Token::get_balance(&mut db, BOB, APPLES),
+            Amount::from_legacy(20)
+        );
+        assert_eq!(System::get_transaction_number(&mut db, ALICE), 1);
+    }
+
+    #[test]
+    fn test_upgrade_keeps_starting_price() {
+        let mut db = new_db();
+        let action = LegacyAction::CreatePool(
+            Uint::try_from(1_000_000u64).unwrap(),
+            APPLES,
+            Uint::try_from(1_500_000u64).unwrap(),
+        );
+        assert_eq!(
+            action.upgrade(&mut db).unwrap(),
+            Action::CreatePool(U256::exp10(18), APPLES, U256::from(1_500_000u64))
+        );
+    }
+
+    #[test]
+    fn test_upgrade_deposit() {
+        let mut db = new_db();
+        let action = LegacyAction::ProcessPolygonMessages(
+            vec![LegacyPolygonMessage::Deposit(1_000_000, WBTC, ALICE)],
+            Uint::try_from(1u64).unwrap(),
+        );
+        assert_eq!(
+            action.upgrade(&mut db).unwrap(),
+            Action::ProcessPolygonMessages(
+                vec![PolygonMessage::Deposit(U256::exp10(8), WBTC, ALICE)],
+                Uint::try_from(1u64).unwrap(),
+            )
+        );
+    }
+}
@@ -1,19 +1,27 @@
+mod legacy;
+
 use crate::{
     bridge::{EthereumMessage, PolygonMessage},
+    charge,
     contract::{self, Contract},
     crypto::ed25519_verify,
-    token::tokens::{TOKENS, USD},
-    Bridge, Ellipticoin, Token, AMM,
+    governance::Choice,
+    parameters::Parameter,
+    pay,
+    token::tokens::{TokenMetadata, TOKENS, USD},
+    Bridge, Ellipticoin, Governance, Multisig, Parameters, Token, AMM,
 };
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use ellipticoin_macros::db_accessors;
 use ellipticoin_types::{
     db::{Backend, Db},
-    Address, Uint,
+    Address, Amount, Uint, U256,
 };
 use serde::{Deserialize, Serialize};
 
-use std::convert::TryInto;
+use std::{cmp::max, collections::BTreeMap, convert::TryInto};
+
+pub use legacy::{LegacyAction, LegacyPolygonMessage, LegacyTransaction};
 
 pub struct System;
 
@@ -26,6 +34,12 @@ impl Contract for System {
 pub struct Block {
     pub start_transaction_id: u64,
     pub transaction_count: u64,
+    #[serde(default)]
+    pub sealed_by: Address,
+    #[serde(default)]
+    pub skipped_miners: Vec<Address>,
+    #[serde(default)]
+    pub timestamp: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -37,24 +51,170 @@ pub struct Transaction {
 
 impl Transaction {
     pub fn run<B: Backend>(&self, db: &mut Db<B>, sender: Address) -> Result<u64> {
+        self.use_transaction_number(db, sender, sender)?;
         self.action.run(db, sender)
     }
+
+    // Relayed transactions are submitted by the relayer who pays the fee.
+    pub fn run_relayed<B: Backend>(
+        &self,
+        db: &mut Db<B>,
+        sender: Address,
+        relayer: Address,
+    ) -> Result<u64> {
+        self.use_transaction_number(db, sender, relayer)?;
+        self.action.run(db, sender)
+    }
+
+    // The sender's transaction number is checked to prevent transactions from
+    // being replayed. It's incremented and the fee is committed before the
+    // action runs so that both are kept even if the action fails.
+    fn use_transaction_number<B: Backend>(
+        &self,
+        db: &mut Db<B>,
+        sender: Address,
+        payer: Address,
+    ) -> Result<()> {
+        let next_transaction_number = System::get_next_transaction_number(db, sender);
+        if self.transaction_number != next_transaction_number {
+            bail!(
+                "Expected transaction number {} but got {}",
+                next_transaction_number,
+                self.transaction_number
+            )
+        }
+        let fee = System::fee(db, sender, &self.action)?;
+        System::charge_fee(db, payer, fee)?;
+        System::increment_transaction_number(db, sender);
+        db.commit();
+        Ok(())
+    }
 }
 
 impl Action {
     pub fn run<B: Backend>(&self, db: &mut Db<B>, sender: Address) -> Result<u64> {
-        let result = match &self {
+        let result = self.apply(db, sender);
+        if result.is_ok() {
+            let transaction_id = System::increment_transaction_id(db);
+            db.commit();
+            Ok(transaction_id)
+        } else {
+            db.revert();
+            Err(result.err().unwrap())
+        }
+    }
+
+    // Runs the action as part of an outer transaction. If it fails only its own
+    // writes are undone.
+    pub fn run_in_savepoint<B: Backend>(&self, db: &mut Db<B>, sender: Address) -> Result<()> {
+        let savepoint = db.checkpoint();
+        let result = self.apply(db, sender);
+        if result.is_ok() {
+            db.release(savepoint);
+        } else {
+            db.rollback_to(savepoint);
+        }
+        result
+    }
+
+    // Submitting or approving a multisig action may run it so its gas is
+    // included.
+    pub fn gas<B: Backend>(&self, db: &mut Db<B>) -> u64 {
+        match self {
+            Action::Batch(actions) => {
+                max(actions.iter().map(|action| action.gas(db)).sum(), 21_000)
+            }
+            Action::Approve(..) | Action::Null | Action::Pay(..) | Action::TransferFrom(..) => {
+                21_000
+            }
+            Action::ApproveMultisigAction(account, id) => {
+                50_000
+                    + Multisig::get_submissions(db, *account)
+                        .get(u64::from(*id) as usize)
+                        .map_or(0, |submission| submission.action.gas(db))
+            }
+            Action::SubmitMultisigAction(_account, action) => 50_000 + action.gas(db),
+            Action::CancelProposal(..)
+            | Action::Delegate(..)
+            | Action::Harvest
+            | Action::RotateHashOnion(..)
+            | Action::Seal(..)
+            | Action::SealAt(..)
+            | Action::SetParameter(..)
+            | Action::StopMining
+            | Action::Undelegate
+            | Action::Vote(..)
+            | Action::WithdrawBond
+            | Action::WithdrawVote(..) => 50_000,
+            Action::AddLiquidity(..)
+            | Action::AddToken(..)
+            | Action::Buy(..)
+            | Action::CreateMultisig(..)
+            | Action::CreateWithdrawlRequest(..)
+            | Action::ExecuteProposal(..)
+            | Action::ProcessEthereumMessages(..)
+            | Action::ProcessPolygonMessages(..)
+            | Action::RemoveLiquidity(..)
+            | Action::Sell(..)
+            | Action::StartMining(..) => 100_000,
+            Action::Compound(..) | Action::CreatePool(..) => 150_000,
+        }
+    }
+
+    // Only timestamped seals say when they were sent.
+    fn timestamp(&self) -> Option<u64> {
+        match self {
+            Action::SealAt(_onion_skin, timestamp) => Some(u64::from(*timestamp)),
+            _ => None,
+        }
+    }
+
+    fn keeps_chain_running(&self) -> bool {
+        matches!(
+            self,
+            Action::ProcessEthereumMessages(..)
+                | Action::ProcessPolygonMessages(..)
+                | Action::RotateHashOnion(..)
+                | Action::Seal(..)
+                | Action::SealAt(..)
+        )
+    }
+
+    fn apply<B: Backend>(&self, db: &mut Db<B>, sender: Address) -> Result<()> {
+        match &self {
             Action::AddLiquidity(amount, token) => {
-                AMM::add_liquidity(db, sender, (*amount).try_into()?, *token)
+                AMM::add_liquidity(db, sender, Amount(*amount), *token)
+            }
+            Action::AddToken(token_metadata) => {
+                Token::add_token(db, sender, token_metadata.clone())
+            }
+            Action::Approve(spender, underlying_amount, token) => {
+                let amount = if *underlying_amount == U256::MAX {
+                    Amount::MAX
+                } else {
+                    Token::underlying_to_amount(db, Amount(*underlying_amount), *token)?
+                };
+                Token::approve(db, sender, *spender, amount, *token)
+            }
+            Action::ApproveMultisigAction(account, id) => {
+                Multisig::approve(db, sender, *account, u64::from(*id) as usize)
+            }
+            Action::Batch(actions) => {
+                for (index, action) in actions.iter().enumerate() {
+                    action
+                        .apply(db, sender)
+                        .map_err(|err| anyhow!("Step {} failed: {}", index, err))?;
+                }
+                Ok(())
             }
             Action::Buy(underlying_input_amount, input_token, minimum_underlying_output_amount) => {
                 let input_amount =
-                    Token::underlying_to_amount(db, (*underlying_input_amount).try_into()?, USD);
+                    Token::underlying_to_amount(db, Amount(*underlying_input_amount), USD)?;
                 let minimum_output_amount = Token::underlying_to_amount(
                     db,
-                    (*minimum_underlying_output_amount).try_into()?,
+                    Amount(*minimum_underlying_output_amount),
                     *input_token,
-                );
+                )?;
 
                 AMM::buy(
                     db,
@@ -65,26 +225,52 @@ impl Action {
                 )
             }
 
+            Action::CancelProposal(proposal_id) => {
+                Governance::cancel(db, sender, u64::from(*proposal_id) as usize)
+            }
+            Action::Compound(token, minimum_underlying_output_amount) => {
+                let minimum_output_amount = Token::underlying_to_amount(
+                    db,
+                    Amount(*minimum_underlying_output_amount),
+                    *token,
+                )?;
+                Ellipticoin::compound(db, sender, *token, minimum_output_amount)
+            }
+            Action::CreateMultisig(owners, threshold) => {
+                Multisig::create(db, owners.clone(), u64::from(*threshold)).map(|_| ())
+            }
             Action::CreatePool(amount, token, underlying_starting_price) => {
                 let starting_price =
-                    Token::underlying_to_amount(db, (*underlying_starting_price).try_into()?, USD);
-                AMM::create_pool(db, sender, (*amount).try_into()?, *token, starting_price)
+                    Token::underlying_to_amount(db, Amount(*underlying_starting_price), USD)?;
+                AMM::create_pool(db, sender, Amount(*amount), *token, starting_price)
             }
             Action::CreateWithdrawlRequest(underlying_amount, token) => {
-                let amount =
-                    Token::underlying_to_amount(db, (*underlying_amount).try_into()?, *token);
-                Bridge::create_withdrawl_request(db, sender, (amount).try_into()?, *token)
+                let amount = Token::underlying_to_amount(db, Amount(*underlying_amount), *token)?;
+                Bridge::create_withdrawl_request(db, sender, amount, *token)
             }
+            Action::Delegate(delegate) => Governance::delegate(db, sender, *delegate),
+            Action::ExecuteProposal(proposal_id) => {
+                Governance::execute(db, u64::from(*proposal_id) as usize)
+            }
+            Action::Harvest => Ellipticoin::harvest(db, sender).map(|_| ()),
             Action::Null => Ok(()),
             Action::Pay(recipient, underlying_amount, token) => {
-                let amount =
-                    Token::underlying_to_amount(db, (*underlying_amount).try_into()?, *token);
+                let amount = Token::underlying_to_amount(db, Amount(*underlying_amount), *token)?;
                 Token::transfer(db, sender, *recipient, amount, *token)
             }
             Action::RemoveLiquidity(percentage, token) => {
-                AMM::remove_liquidity(db, sender, (*percentage).try_into()?, *token)
+                AMM::remove_liquidity(db, sender, Amount::from(u64::from(*percentage)), *token)
+            }
+            Action::RotateHashOnion(onion_skin, layer_count) => {
+                Ellipticoin::rotate_hash_onion(db, sender, *onion_skin, (*layer_count).try_into()?)
+            }
+            Action::Seal(onion_skin) => Ellipticoin::seal(db, sender, *onion_skin, None),
+            Action::SealAt(onion_skin, timestamp) => {
+                Ellipticoin::seal(db, sender, *onion_skin, Some((*timestamp).try_into()?))
+            }
+            Action::SetParameter(parameter) => {
+                Parameters::set_parameter(db, sender, parameter.clone())
             }
-            Action::Seal(onion_skin) => Ellipticoin::seal(db, sender, *onion_skin),
             Action::Sell(
                 underlying_input_amount,
                 input_token,
@@ -92,14 +278,14 @@ impl Action {
             ) => {
                 let input_amount = Token::underlying_to_amount(
                     db,
-                    (*underlying_input_amount).try_into()?,
+                    Amount(*underlying_input_amount),
                     *input_token,
-                );
+                )?;
                 let minimum_output_amount = Token::underlying_to_amount(
                     db,
-                    (*minimum_underlying_output_amount).try_into()?,
+                    Amount(*minimum_underlying_output_amount),
                     USD,
-                );
+                )?;
 
                 AMM::sell(
                     db,
@@ -126,33 +312,60 @@ impl Action {
                 *onion_skin,
                 (*layer_count).try_into()?,
             ),
-        };
-        if result.is_ok() {
-            System::increment_transaction_number(db, sender);
-            let transaction_id = System::increment_transaction_id(db);
-            db.commit();
-            Ok(transaction_id)
-        } else {
-            db.revert();
-            Err(result.err().unwrap())
+            Action::StopMining => Ellipticoin::stop_mining(db, sender),
+            Action::SubmitMultisigAction(account, action) => {
+                Multisig::submit(db, sender, *account, (**action).clone())
+            }
+            Action::TransferFrom(owner, recipient, underlying_amount, token) => {
+                let amount = Token::underlying_to_amount(db, Amount(*underlying_amount), *token)?;
+                Token::transfer_from(db, sender, *owner, *recipient, amount, *token)
+            }
+            Action::Undelegate => Governance::undelegate(db, sender),
+            Action::Vote(proposal_id, choice) => {
+                Governance::vote(db, sender, u64::from(*proposal_id) as usize, choice.clone())
+            }
+            Action::WithdrawBond => Ellipticoin::withdraw_bond(db, sender),
+            Action::WithdrawVote(proposal_id) => {
+                Governance::withdraw_vote(db, sender, u64::from(*proposal_id) as usize)
+            }
         }
     }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum Action {
-    AddLiquidity(Uint, Address),
-    Buy(Uint, Address, Uint),
-    CreatePool(Uint, Address, Uint),
-    CreateWithdrawlRequest(Uint, Address),
+    AddLiquidity(U256, Address),
+    AddToken(TokenMetadata),
+    Approve(Address, U256, Address),
+    ApproveMultisigAction(Address, Uint),
+    Batch(Vec<Action>),
+    Buy(U256, Address, U256),
+    CancelProposal(Uint),
+    Compound(Address, U256),
+    CreateMultisig(Vec<Address>, Uint),
+    CreatePool(U256, Address, U256),
+    CreateWithdrawlRequest(U256, Address),
+    Delegate(Address),
+    ExecuteProposal(Uint),
+    Harvest,
     ProcessEthereumMessages(Vec<EthereumMessage>, Uint),
     ProcessPolygonMessages(Vec<PolygonMessage>, Uint),
     Null,
-    Pay(Address, Uint, Address),
-    Sell(Uint, Address, Uint),
+    Pay(Address, U256, Address),
+    Sell(U256, Address, U256),
     RemoveLiquidity(Uint, Address),
+    RotateHashOnion([u8; 32], Uint),
     Seal([u8; 32]),
+    SealAt([u8; 32], Uint),
+    SetParameter(Parameter),
     StartMining(String, [u8; 32], Uint),
+    StopMining,
+    SubmitMultisigAction(Address, Box<Action>),
+    TransferFrom(Address, Address, U256, Address),
+    Undelegate,
+    Vote(Uint, Choice),
+    WithdrawBond,
+    WithdrawVote(Uint),
 }
 
 impl Default for Action {
@@ -166,6 +379,7 @@ db_accessors!(System {
     blocks() -> Vec<Block>;
     transaction_number(address: Address) -> u64;
     transaction_id_counter() -> u64;
+    block_fees() -> BTreeMap<Address, Amount>;
 });
 
 impl System {
@@ -174,23 +388,42 @@ impl System {
         transaction: Transaction,
         sender: Address,
     ) -> Result<u64, anyhow::Error> {
-        if Self::get_transaction_number(db, sender) + 1 != transaction.transaction_number {
-            return Err(anyhow!(
-                "Expected transaction number {} but got {}",
-                Self::get_transaction_number(db, sender) + 1,
-                transaction.transaction_number
-            ));
+        transaction.run(db, sender)
+    }
+
+    // Seals, hash onion rotations and bridge messages keep the chain running so
+    // they're free when they're sent by the bridge or by a miner who could seal
+    // the current block. Anyone else pays for them like any other action.
+    pub fn fee<B: Backend>(db: &mut Db<B>, sender: Address, action: &Action) -> Result<Amount> {
+        if action.keeps_chain_running()
+            && (sender == Bridge::address()
+                || Ellipticoin::can_seal(db, sender, action.timestamp()))
+        {
+            return Ok(Amount::ZERO);
         }
-        let result = transaction.action.run(db, sender);
-        if result.is_ok() {
-            System::increment_transaction_number(db, sender);
-            let transaction_id = System::increment_transaction_id(db);
-            db.commit();
-            Ok(transaction_id)
-        } else {
-            db.revert();
-            Err(result.err().unwrap())
+        Ok(Parameters::get_gas_price(db).checked_mul(action.gas(db))?)
+    }
+
+    // Fees are held by the system contract until the block is sealed.
+    pub fn charge_fee<B: Backend>(db: &mut Db<B>, payer: Address, fee: Amount) -> Result<()> {
+        if fee == Amount::ZERO {
+            return Ok(());
+        }
+        let fee_token = Parameters::get_fee_token(db);
+        charge!(db, payer, fee_token, fee)?;
+        let mut block_fees = Self::get_block_fees(db);
+        let block_fee = block_fees.entry(fee_token).or_default();
+        *block_fee = block_fee.checked_add(fee)?;
+        Self::set_block_fees(db, block_fees);
+        Ok(())
+    }
+
+    pub fn pay_block_fees<B: Backend>(db: &mut Db<B>, miner: Address) -> Result<()> {
+        for (token, amount) in Self::get_block_fees(db) {
+            pay!(db, miner, token, amount)?;
         }
+        Self::set_block_fees(db, Default::default());
+        Ok(())
     }
 
     pub fn get_next_transaction_number<B: Backend>(db: &mut Db<B>, address: Address) -> u64 {
@@ -201,7 +434,12 @@ impl System {
         }
     }
 
-    pub fn seal_block<B: Backend>(db: &mut Db<B>) {
+    pub fn seal_block<B: Backend>(
+        db: &mut Db<B>,
+        sealed_by: Address,
+        skipped_miners: Vec<Address>,
+        timestamp: Option<u64>,
+    ) {
         System::increment_block_number(db);
         let mut blocks = Self::get_blocks(db);
         let block = if let Some(last_block) = blocks.last() {
@@ -211,9 +449,17 @@ impl System {
             Block {
                 start_transaction_id,
                 transaction_count: current_transaction_id - start_transaction_id,
+                sealed_by,
+                skipped_miners,
+                timestamp,
             }
         } else {
-            Default::default()
+            Block {
+                sealed_by,
+                skipped_miners,
+                timestamp,
+                ..Default::default()
+            }
         };
         // let last_block = blocks.last();
         // let current_transaction_id = Self::get_transaction_id_counter(db);
@@ -245,7 +491,11 @@ impl System {
         legacy_signature: Vec<u8>,
     ) -> Result<()> {
         ed25519_verify(sender.as_ref(), &legacy_address, &legacy_signature)?;
-        Ellipticoin::harvest(db, Address(legacy_address[..20].try_into().unwrap()))?;
+        if Ellipticoin::get_issuance_rewards(db, Address(legacy_address[..20].try_into().unwrap()))
+            != Amount::ZERO
+        {
+            Ellipticoin::harvest(db, Address(legacy_address[..20].try_into().unwrap()))?;
+        }
         for token in [TOKENS.to_vec()].concat().iter() {
             let balance = Token::get_balance(
                 db,
@@ -259,7 +509,7 @@ impl System {
                 Address(legacy_address[..20].try_into().unwrap()),
             )
             .unwrap();
-            Token::credit(db, balance, *token, sender);
+            Token::credit(db, balance, *token, sender)?;
         }
 
         for token in TOKENS.iter() {
@@ -282,28 +532,264 @@ impl System {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::System;
+    use crate::{Miner, System};
     use ellipticoin_test_framework::{
         constants::{
-            actors::{ALICE, BOB},
+            actors::{ALICE, BOB, CAROL},
             tokens::APPLES,
         },
         new_db,
     };
-    use ellipticoin_types::Uint;
     use std::convert::TryFrom;
 
     #[test]
     fn test_run() {
         let mut db = new_db();
-        Token::set_balance(&mut db, ALICE, APPLES, 100);
+        Token::set_balance(&mut db, ALICE, APPLES, Amount::from(100));
         let transfer_transaction = Transaction {
-            transaction_number: 0,
-            action: Action::Pay(BOB, Uint::try_from(20u64).unwrap(), APPLES),
+            transaction_number: 1,
+            action: Action::Pay(BOB, U256::from(20), APPLES),
         };
+        let fee = System::fee(&mut db, ALICE, &transfer_transaction.action).unwrap();
+        Token::set_balance(&mut db, ALICE, USD, fee);
         transfer_transaction.run(&mut db, ALICE).unwrap();
         assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 80);
         assert_eq!(Token::get_balance(&mut db, BOB, APPLES), 20);
+        assert_eq!(Token::get_balance(&mut db, ALICE, USD), 0);
         assert_eq!(System::get_transaction_number(&mut db, ALICE), 1);
     }
+
+    #[test]
+    fn test_run_with_insufficient_fee() {
+        let mut db = new_db();
+        Token::set_balance(&mut db, ALICE, APPLES, Amount::from(100));
+        let transfer_transaction = Transaction {
+            transaction_number: 1,
+            action: Action::Pay(BOB, U256::from(20), APPLES),
+        };
+        assert!(transfer_transaction.run(&mut db, ALICE).is_err());
+        assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 100);
+        assert_eq!(System::get_transaction_number(&mut db, ALICE), 0);
+    }
+
+    #[test]
+    fn test_run_relayed() {
+        let mut db = new_db();
+        Token::set_balance(&mut db, ALICE, APPLES, Amount::from(100));
+        let transfer_transaction = Transaction {
+            transaction_number: 1,
+            action: Action::Pay(CAROL, U256::from(20), APPLES),
+        };
+        let fee = System::fee(&mut db, ALICE, &transfer_transaction.action).unwrap();
+        Token::set_balance(&mut db, BOB, USD, fee);
+        transfer_transaction
+            .run_relayed(&mut db, ALICE, BOB)
+            .unwrap();
+        assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 80);
+        assert_eq!(Token::get_balance(&mut db, CAROL, APPLES), 20);
+        assert_eq!(Token::get_balance(&mut db, BOB, USD), 0);
+        assert_eq!(System::get_transaction_number(&mut db, ALICE), 1);
+        assert_eq!(
+            transfer_transaction
+                .run_relayed(&mut db, ALICE, BOB)
+                .err()
+                .unwrap()
+                .to_string(),
+            "Expected transaction number 2 but got 1"
+        );
+    }
+
+    #[test]
+    fn test_batch() {
+        let mut db = new_db();
+        Token::set_balance(&mut db, ALICE, APPLES, Amount::from(100));
+        let batch_transaction = Transaction {
+            transaction_number: 1,
+            action: Action::Batch(vec![
+                Action::Pay(BOB, U256::from(20), APPLES),
+                Action::Pay(BOB, U256::from(30), APPLES),
+            ]),
+        };
+        let fee = System::fee(&mut db, ALICE, &batch_transaction.action).unwrap();
+        Token::set_balance(&mut db, ALICE, USD, fee);
+        batch_transaction.run(&mut db, ALICE).unwrap();
+        assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 50);
+        assert_eq!(Token::get_balance(&mut db, BOB, APPLES), 50);
+        assert_eq!(System::get_transaction_number(&mut db, ALICE), 1);
+    }
+
+    #[test]
+    fn test_failed_batch() {
+        let mut db = new_db();
+        Token::set_balance(&mut db, ALICE, APPLES, Amount::from(100));
+        let batch_transaction = Transaction {
+            transaction_number: 1,
+            action: Action::Batch(vec![
+                Action::Pay(BOB, U256::from(20), APPLES),
+                Action::WithdrawBond,
+            ]),
+        };
+        let fee = System::fee(&mut db, ALICE, &batch_transaction.action).unwrap();
+        Token::set_balance(&mut db, ALICE, USD, fee);
+        db.commit();
+        assert_eq!(
+            batch_transaction
+                .run(&mut db, ALICE)
+                .err()
+                .unwrap()
+                .to_string(),
+            "Step 1 failed: aaa1b967f4e3d67c4946ec6816b05f0207aad9cd has no bond to withdraw"
+        );
+        assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 100);
+        assert_eq!(Token::get_balance(&mut db, BOB, APPLES), 0);
+        assert_eq!(Token::get_balance(&mut db, ALICE, USD), 0);
+        assert_eq!(System::get_block_fees(&mut db)[&USD], fee);
+        assert_eq!(System::get_transaction_number(&mut db, ALICE), 1);
+        assert_eq!(
+            batch_transaction
+                .run(&mut db, ALICE)
+                .err()
+                .unwrap()
+                .to_string(),
+            "Expected transaction number 2 but got 1"
+        );
+    }
+
+    #[test]
+    fn test_gas() {
+        let mut db = new_db();
+        assert_eq!(
+            Action::Pay(BOB, U256::from(20), APPLES).gas(&mut db),
+            21_000
+        );
+        assert_eq!(Action::Seal([0; 32]).gas(&mut db), 50_000);
+        assert_eq!(
+            Action::Batch(vec![
+                Action::Pay(BOB, U256::from(20), APPLES),
+                Action::Harvest,
+            ])
+            .gas(&mut db),
+            71_000
+        );
+        assert_eq!(Action::Batch(vec![]).gas(&mut db), 21_000);
+    }
+
+    #[test]
+    fn test_multisig_gas() {
+        let mut db = new_db();
+        let account = Multisig::create(&mut db, vec![ALICE, BOB], 2).unwrap();
+        let action = Action::Pay(CAROL, U256::from(20), APPLES);
+        let submit = Action::SubmitMultisigAction(account, Box::new(action.clone()));
+        assert_eq!(submit.gas(&mut db), 71_000);
+        submit.run(&mut db, ALICE).unwrap();
+        assert_eq!(
+            Action::ApproveMultisigAction(account, Uint::try_from(0u64).unwrap()).gas(&mut db),
+            71_000
+        );
+        assert_eq!(
+            Action::ApproveMultisigAction(account, Uint::try_from(1u64).unwrap()).gas(&mut db),
+            50_000
+        );
+    }
+
+    #[test]
+    fn test_reads_state_written_before_block_fees() {
+        let mut db = new_db();
+        System::insert(&mut db, 0u16.to_le_bytes().to_vec(), 5u64);
+        System::insert(
+            &mut db,
+            1u16.to_le_bytes().to_vec(),
+            vec![Block {
+                start_transaction_id: 3,
+                transaction_count: 2,
+                ..Default::default()
+            }],
+        );
+        System::insert(
+            &mut db,
+            [2u16.to_le_bytes().to_vec(), ALICE.0.to_vec()].concat(),
+            7u64,
+        );
+        System::insert(&mut db, 3u16.to_le_bytes().to_vec(), 9u64);
+        assert_eq!(System::get_block_number(&mut db), 5);
+        assert_eq!(System::get_blocks(&mut db)[0].start_transaction_id, 3);
+        assert_eq!(System::get_transaction_number(&mut db, ALICE), 7);
+        assert_eq!(System::get_transaction_id_counter(&mut db), 9);
+        assert!(System::get_block_fees(&mut db).is_empty());
+    }
+
+    #[test]
+    fn test_chain_maintenance_fees_for_backup_miners() {
+        let mut db = new_db();
+        Ellipticoin::set_miners(
+            &mut db,
+            vec![
+                Miner {
+                    address: ALICE,
+                    host: "host1".to_string(),
+                    hash_onion_skin: [0; 32],
+                    hash_onion_layers_left: 1,
+                    bond: Amount::ZERO,
+                },
+                Miner {
+                    address: BOB,
+                    host: "host2".to_string(),
+                    hash_onion_skin: [0; 32],
+                    hash_onion_layers_left: 1,
+                    bond: Amount::ZERO,
+                },
+            ],
+        );
+        Ellipticoin::set_last_sealed_at(&mut db, Some(100));
+        let timeout = Parameters::get_block_time(&mut db) * Parameters::get_miner_timeout(&mut db);
+        let early_seal = Action::SealAt([0; 32], Uint::try_from(100 + timeout - 1).unwrap());
+        let seal = Action::SealAt([0; 32], Uint::try_from(100 + timeout).unwrap());
+        assert_eq!(
+            System::fee(&mut db, BOB, &early_seal).unwrap(),
+            Parameters::get_gas_price(&mut db)
+                .checked_mul(early_seal.gas(&mut db))
+                .unwrap()
+        );
+        assert_eq!(System::fee(&mut db, BOB, &seal).unwrap(), Amount::ZERO);
+        assert_eq!(
+            System::fee(&mut db, CAROL, &seal).unwrap(),
+            Parameters::get_gas_price(&mut db)
+                .checked_mul(seal.gas(&mut db))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_chain_maintenance_fees() {
+        let mut db = new_db();
+        Ellipticoin::set_miners(
+            &mut db,
+            vec![Miner {
+                address: ALICE,
+                host: "host1".to_string(),
+                hash_onion_skin: [0; 32],
+                hash_onion_layers_left: 1,
+                bond: Amount::ZERO,
+            }],
+        );
+        let seal = Action::Seal([0; 32]);
+        let messages = Action::ProcessPolygonMessages(vec![], Uint::try_from(1u64).unwrap());
+        assert_eq!(System::fee(&mut db, ALICE, &seal).unwrap(), Amount::ZERO);
+        assert_eq!(
+            System::fee(&mut db, Bridge::address(), &messages).unwrap(),
+            Amount::ZERO
+        );
+        assert_eq!(
+            System::fee(&mut db, BOB, &seal).unwrap(),
+            Parameters::get_gas_price(&mut db)
+                .checked_mul(seal.gas(&mut db))
+                .unwrap()
+        );
+        assert_eq!(
+            System::fee(&mut db, BOB, &messages).unwrap(),
+            Parameters::get_gas_price(&mut db)
+                .checked_mul(messages.gas(&mut db))
+                .unwrap()
+        );
+    }
 }
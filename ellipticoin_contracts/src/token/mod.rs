@@ -1,21 +1,22 @@
 pub mod macros;
 pub mod tokens;
 
-use crate::token::tokens::TOKEN_METADATA;
 use crate::{
     constants::{BASE_FACTOR, BASE_TOKEN_MANTISSA, EXCHANGE_RATE_MANTISSA},
     contract::{self, Contract},
-    token::tokens::USD,
-    AMM,
+    token::tokens::{TokenMetadata, GENESIS_TOKENS, USD},
+    Governance, AMM,
 };
 use anyhow::{anyhow, bail, Result};
 use ellipticoin_macros::db_accessors;
 use ellipticoin_types::{
+    amount::DECIMALS,
     db::{Backend, Db},
-    Address,
+    Address, Amount, ArithmeticError, U256,
 };
 use num_bigint::BigUint;
-use num_traits::{cast::ToPrimitive, pow};
+use num_traits::{pow, Zero};
+use std::convert::TryFrom;
 
 use hex;
 
@@ -26,74 +27,173 @@ impl Contract for Token {
 }
 
 db_accessors!(Token {
-    balance(address: Address, token: Address) -> u64;
+    balance(address: Address, token: Address) -> Amount;
     usd_exchange_rate() -> BigUint;
-    total_supply(token: Address) -> u64;
+    total_supply(token: Address) -> Amount;
+    token_metadata(token: Address) -> Option<TokenMetadata>;
+    tokens() -> Vec<Address>;
+    allowance(owner: Address, spender: Address, token: Address) -> Amount;
 });
 
 impl Token {
-    pub fn get_underlying_exchange_rate<B: Backend>(db: &mut Db<B>, token: Address) -> u64 {
-        Self::underlying_to_amount(db, BASE_FACTOR, token)
+    // Registers the genesis tokens which aren't in the registry yet so this
+    // can be run on existing state.
+    pub fn migrate<B: Backend>(db: &mut Db<B>) {
+        for token_metadata in GENESIS_TOKENS.iter() {
+            if Self::get_token_metadata(db, token_metadata.address).is_none() {
+                Self::register_token(db, token_metadata.clone());
+            }
+        }
+    }
+
+    pub fn add_token<B: Backend>(
+        db: &mut Db<B>,
+        sender: Address,
+        token_metadata: TokenMetadata,
+    ) -> Result<()> {
+        if sender != Governance::address() {
+            bail!("Tokens can only be added by governance")
+        }
+        if Self::get_token_metadata(db, token_metadata.address).is_some() {
+            bail!(
+                "Token {} is already registered",
+                hex::encode(token_metadata.address)
+            )
+        }
+        if token_metadata.decimals as u32 > DECIMALS {
+            bail!(
+                "Tokens with more than {} decimals are not supported",
+                DECIMALS
+            )
+        }
+        Self::register_token(db, token_metadata);
+        Ok(())
+    }
+
+    pub fn get_bridged_tokens<B: Backend>(db: &mut Db<B>) -> Vec<TokenMetadata> {
+        Self::get_tokens(db)
+            .into_iter()
+            .filter_map(|token| Self::get_token_metadata(db, token))
+            .filter(|token_metadata| token_metadata.bridge_address.is_some())
+            .collect()
+    }
+
+    fn register_token<B: Backend>(db: &mut Db<B>, token_metadata: TokenMetadata) {
+        let mut tokens = Self::get_tokens(db);
+        tokens.push(token_metadata.address);
+        Self::set_tokens(db, tokens);
+        Self::set_token_metadata(db, token_metadata.address, Some(token_metadata));
+    }
+
+    pub fn get_underlying_exchange_rate<B: Backend>(
+        db: &mut Db<B>,
+        token: Address,
+    ) -> Result<Amount> {
+        Self::underlying_to_amount(db, Amount::from(BASE_FACTOR), token)
     }
 
     pub fn get_underlying_balance<B: Backend>(
         db: &mut Db<B>,
         address: Address,
         token: Address,
-    ) -> u64 {
+    ) -> Result<Amount> {
         let balance = Self::get_balance(db, address, token);
         Self::amount_to_underlying(db, balance, token)
     }
 
-    pub fn get_underlying_total_supply<B: Backend>(db: &mut Db<B>, token: Address) -> u64 {
+    pub fn get_underlying_total_supply<B: Backend>(
+        db: &mut Db<B>,
+        token: Address,
+    ) -> Result<Amount> {
         let balance = Self::get_total_supply(db, token);
         Self::amount_to_underlying(db, balance, token)
     }
 
-    pub fn amount_to_underlying<B: Backend>(db: &mut Db<B>, amount: u64, token: Address) -> u64 {
+    pub fn amount_to_underlying<B: Backend>(
+        db: &mut Db<B>,
+        amount: Amount,
+        token: Address,
+    ) -> Result<Amount> {
         if token == USD {
             let usd_exchange_rate = Token::get_usd_exchange_rate(db);
-            (usd_exchange_rate * amount
-                / pow(
-                    BigUint::from(10u32),
-                    BASE_TOKEN_MANTISSA + EXCHANGE_RATE_MANTISSA,
-                ))
-            .to_u64()
-            .unwrap()
+            Ok(Amount(
+                U256::try_from(
+                    usd_exchange_rate * BigUint::from(amount.0)
+                        / pow(
+                            BigUint::from(10u32),
+                            BASE_TOKEN_MANTISSA + EXCHANGE_RATE_MANTISSA,
+                        ),
+                )
+                .map_err(|_| ArithmeticError::Overflow)?,
+            ))
         } else {
-            amount
+            Ok(amount)
         }
     }
 
     pub fn underlying_to_amount<B: Backend>(
         db: &mut Db<B>,
-        underlying_amount: u64,
+        underlying_amount: Amount,
         token: Address,
-    ) -> u64 {
+    ) -> Result<Amount> {
         if token == USD {
             let usd_exchange_rate = Token::get_usd_exchange_rate(db);
-            (pow(
-                BigUint::from(10u32),
-                BASE_TOKEN_MANTISSA + EXCHANGE_RATE_MANTISSA,
-            ) * underlying_amount
-                / usd_exchange_rate)
-                .to_u64()
-                .unwrap()
+            if usd_exchange_rate.is_zero() {
+                return Err(ArithmeticError::DivisionByZero.into());
+            }
+            Ok(Amount(
+                U256::try_from(
+                    pow(
+                        BigUint::from(10u32),
+                        BASE_TOKEN_MANTISSA + EXCHANGE_RATE_MANTISSA,
+                    ) * BigUint::from(underlying_amount.0)
+                        / usd_exchange_rate,
+                )
+                .map_err(|_| ArithmeticError::Overflow)?,
+            ))
         } else {
-            underlying_amount
+            Ok(underlying_amount)
         }
     }
 
-    pub fn get_price<B: Backend>(db: &mut Db<B>, token: Address) -> u64 {
+    pub fn native_to_amount<B: Backend>(
+        db: &mut Db<B>,
+        native_amount: U256,
+        token: Address,
+    ) -> Result<Amount> {
+        Ok(Amount(
+            native_amount
+                .checked_mul(Self::native_scale(db, token)?)
+                .ok_or(ArithmeticError::Overflow)?,
+        ))
+    }
+
+    pub fn amount_to_native<B: Backend>(
+        db: &mut Db<B>,
+        amount: Amount,
+        token: Address,
+    ) -> Result<U256> {
+        Ok(amount
+            .0
+            .checked_div(Self::native_scale(db, token)?)
+            .ok_or(ArithmeticError::DivisionByZero)?)
+    }
+
+    fn native_scale<B: Backend>(db: &mut Db<B>, token: Address) -> Result<U256> {
+        let token_metadata = Self::get_token_metadata(db, token).ok_or(anyhow!("Unknown token"))?;
+        Ok(U256::exp10(DECIMALS - token_metadata.decimals as u32))
+    }
+
+    pub fn get_price<B: Backend>(db: &mut Db<B>, token: Address) -> Result<Amount> {
         if token == USD {
-            BASE_FACTOR
+            Ok(Amount::from(BASE_FACTOR))
         } else {
             let token_supply = AMM::get_pool_supply_of_token(db, token.clone().into());
             let usd_supply = AMM::get_pool_supply_of_usd(db, token.clone().into());
             let price = if token_supply == 0 {
-                0
+                Amount::ZERO
             } else {
-                usd_supply * BASE_FACTOR / token_supply
+                usd_supply.div_fixed(token_supply)?
             };
             Self::amount_to_underlying(db, price, USD)
         }
@@ -103,57 +203,109 @@ impl Token {
         db: &mut Db<B>,
         sender: Address,
         recipient: Address,
-        underlying_amount: u64,
+        underlying_amount: Amount,
         token: Address,
     ) -> Result<()> {
         Self::debit(db, underlying_amount, token, sender)?;
-        Self::credit(db, underlying_amount, token, recipient);
+        Self::credit(db, underlying_amount, token, recipient)
+    }
+
+    pub fn approve<B: Backend>(
+        db: &mut Db<B>,
+        owner: Address,
+        spender: Address,
+        amount: Amount,
+        token: Address,
+    ) -> Result<()> {
+        Self::set_allowance(db, owner, spender, token, amount);
         Ok(())
     }
 
-    pub fn mint<B: Backend>(db: &mut Db<B>, amount: u64, token: Address, address: Address) {
-        Self::credit(db, amount, token, address);
+    pub fn transfer_from<B: Backend>(
+        db: &mut Db<B>,
+        spender: Address,
+        owner: Address,
+        recipient: Address,
+        amount: Amount,
+        token: Address,
+    ) -> Result<()> {
+        let allowance = Self::get_allowance(db, owner, spender, token);
+        if amount > allowance {
+            bail!(
+                "{} has insufficient allowance from {} have {} need {}",
+                hex::encode(spender),
+                hex::encode(owner),
+                allowance,
+                amount
+            )
+        }
+        Self::transfer(db, owner, recipient, amount, token)?;
+        // An allowance of `Amount::MAX` is an infinite approval and is never spent down.
+        if allowance != Amount::MAX {
+            Self::set_allowance(db, owner, spender, token, allowance.checked_sub(amount)?);
+        }
+        Ok(())
+    }
+
+    pub fn mint<B: Backend>(
+        db: &mut Db<B>,
+        amount: Amount,
+        token: Address,
+        address: Address,
+    ) -> Result<()> {
+        Self::credit(db, amount, token, address)?;
         let total_supply = Self::get_total_supply(db, token);
-        Self::set_total_supply(db, token, total_supply + amount);
+        Self::set_total_supply(db, token, total_supply.checked_add(amount)?);
+        Ok(())
     }
 
     pub fn burn<B: Backend>(
         db: &mut Db<B>,
-        amount: u64,
+        amount: Amount,
         token: Address,
         address: Address,
     ) -> Result<()> {
         Self::debit(db, amount, token, address)?;
         let total_supply = Self::get_total_supply(db, token);
-        Self::set_total_supply(db, token, total_supply - amount);
+        Self::set_total_supply(db, token, total_supply.checked_sub(amount)?);
         Ok(())
     }
 
-    pub fn credit<B: Backend>(db: &mut Db<B>, amount: u64, token: Address, address: Address) {
+    pub fn credit<B: Backend>(
+        db: &mut Db<B>,
+        amount: Amount,
+        token: Address,
+        address: Address,
+    ) -> Result<()> {
         let balance = Self::get_balance(db, address, token);
-        Self::set_balance(db, address, token, balance + amount)
+        Self::set_balance(db, address, token, balance.checked_add(amount)?);
+        Ok(())
     }
 
     pub fn debit<B: Backend>(
         db: &mut Db<B>,
-        amount: u64,
+        amount: Amount,
         token: Address,
         address: Address,
     ) -> Result<()> {
         let balance = Self::get_balance(db, address, token);
 
         if amount <= balance {
-            Ok(Self::set_balance(db, address, token, balance - amount))
+            Ok(Self::set_balance(
+                db,
+                address,
+                token,
+                balance.checked_sub(amount)?,
+            ))
         } else {
             bail!(
                 "{} has insufficient balance of {} have {} need {}",
                 hex::encode(address),
-                TOKEN_METADATA
-                    .get(&token)
+                Self::get_token_metadata(db, token)
                     .ok_or(anyhow!("Unknown token"))?
                     .symbol,
-                balance as f64 / 1000000.0,
-                amount as f64 / 1000000.0
+                balance.to_f64(),
+                amount.to_f64()
             )
         }
     }
@@ -161,21 +313,94 @@ impl Token {
 
 #[cfg(test)]
 mod tests {
-    use super::Token;
+    use super::{Token, TokenMetadata};
+    use crate::{contract::Contract, Governance};
     use ellipticoin_test_framework::{
         constants::{
-            actors::{ALICE, BOB},
+            actors::{ALICE, BOB, CAROL},
             tokens::APPLES,
         },
         new_db,
     };
+    use ellipticoin_types::Amount;
 
     #[test]
     fn test_transfer() {
         let mut db = new_db();
-        Token::set_balance(&mut db, ALICE, APPLES, 100);
-        Token::transfer(&mut db, ALICE, BOB, 20, APPLES).unwrap();
+        Token::set_balance(&mut db, ALICE, APPLES, Amount::from(100));
+        Token::transfer(&mut db, ALICE, BOB, Amount::from(20), APPLES).unwrap();
         assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 80);
         assert_eq!(Token::get_balance(&mut db, BOB, APPLES), 20);
     }
+
+    #[test]
+    fn test_transfer_from() {
+        let mut db = new_db();
+        Token::set_balance(&mut db, ALICE, APPLES, Amount::from(100));
+        Token::approve(&mut db, ALICE, BOB, Amount::from(30), APPLES).unwrap();
+        Token::transfer_from(&mut db, BOB, ALICE, CAROL, Amount::from(20), APPLES).unwrap();
+        assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 80);
+        assert_eq!(Token::get_balance(&mut db, CAROL, APPLES), 20);
+        assert_eq!(Token::get_allowance(&mut db, ALICE, BOB, APPLES), 10);
+        assert_eq!(
+            Token::transfer_from(&mut db, BOB, ALICE, CAROL, Amount::from(20), APPLES)
+                .err()
+                .unwrap()
+                .to_string(),
+            "b0b9416041676df4342b6c85fb69351e5ba69f4f has insufficient allowance from aaa1b967f4e3d67c4946ec6816b05f0207aad9cd have 10 need 20"
+        );
+    }
+
+    #[test]
+    fn test_transfer_from_with_infinite_approval() {
+        let mut db = new_db();
+        Token::set_balance(&mut db, ALICE, APPLES, Amount::from(100));
+        Token::approve(&mut db, ALICE, BOB, Amount::MAX, APPLES).unwrap();
+        Token::transfer_from(&mut db, BOB, ALICE, CAROL, Amount::from(20), APPLES).unwrap();
+        assert_eq!(Token::get_balance(&mut db, CAROL, APPLES), 20);
+        assert_eq!(
+            Token::get_allowance(&mut db, ALICE, BOB, APPLES),
+            Amount::MAX
+        );
+    }
+
+    #[test]
+    fn test_add_token() {
+        let mut db = new_db();
+        let apples = TokenMetadata {
+            address: APPLES,
+            name: "Apples".to_string(),
+            symbol: "APPLES".to_string(),
+            decimals: 18,
+            bridge_address: Some(APPLES),
+        };
+        assert_eq!(
+            Token::add_token(&mut db, ALICE, apples.clone())
+                .err()
+                .unwrap()
+                .to_string(),
+            "Tokens can only be added by governance"
+        );
+        Token::add_token(&mut db, Governance::address(), apples.clone()).unwrap();
+        assert_eq!(
+            Token::get_token_metadata(&mut db, APPLES),
+            Some(apples.clone())
+        );
+        assert!(Token::get_bridged_tokens(&mut db).contains(&apples));
+        assert_eq!(
+            Token::add_token(&mut db, Governance::address(), apples)
+                .err()
+                .unwrap()
+                .to_string(),
+            "Token a000000000000000000000000000000000000000 is already registered"
+        );
+    }
+
+    #[test]
+    fn test_migrate() {
+        let mut db = new_db();
+        let tokens = Token::get_tokens(&mut db);
+        Token::migrate(&mut db);
+        assert_eq!(Token::get_tokens(&mut db), tokens);
+    }
 }
@@ -1,6 +1,6 @@
 use crate::define_token;
 use ellipticoin_types::Address;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 
 define_token!(WBTC, "1bfd67037b42cf73acf2047067bd4f2c47d9bfd6");
 define_token!(ETH, "7ceb23fd6bc0add59e62ac25578270cff1b9f619");
@@ -14,99 +14,89 @@ define_token!(QUICK, "831753dd7087cac61ab5644b308642cc1c33dc13");
 define_token!(AAVE, "d6df932a45c0f255f85145f286ea0b292b21c90b");
 define_token!(UNI, "b33eaad8d922b1083446dc23f610c2567fb5180f");
 
-pub struct TokenMetadata<'a> {
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TokenMetadata {
+    pub address: Address,
+    #[serde(default)]
+    pub name: String,
+    pub symbol: String,
     pub decimals: u8,
-    pub symbol: &'a str,
+    pub bridge_address: Option<Address>,
 }
+
 lazy_static! {
-    pub static ref TOKEN_METADATA: HashMap<Address, TokenMetadata<'static>> = vec![
-        (
-            WBTC,
-            TokenMetadata {
-                decimals: 8,
-                symbol: "WBTC",
-            }
-        ),
-        (
-            ETH,
-            TokenMetadata {
-                decimals: 18,
-                symbol: "ETH",
-            }
-        ),
-        (
-            MSX,
-            TokenMetadata {
-                decimals: 6,
-                symbol: "MSX",
-            }
-        ),
-        (
-            CUSDC,
-            TokenMetadata {
-                decimals: 8,
-                symbol: "CUSDC",
-            }
-        ),
-        (
-            MATIC,
-            TokenMetadata {
-                decimals: 18,
-                symbol: "MATIC",
-            }
-        ),
-        (
-            COMP,
-            TokenMetadata {
-                decimals: 18,
-                symbol: "COMP",
-            }
-        ),
-        (
-            LINK,
-            TokenMetadata {
-                decimals: 18,
-                symbol: "LINK",
-            }
-        ),
-        (
-            QUICK,
-            TokenMetadata {
-                decimals: 18,
-                symbol: "QUICK",
-            }
-        ),
-        (
-            AAVE,
-            TokenMetadata {
-                decimals: 18,
-                symbol: "AAVE",
-            }
-        ),
-        (
-            UNI,
-            TokenMetadata {
-                decimals: 18,
-                symbol: "UNI",
-            }
-        ),
-    ]
-    .into_iter()
-    .collect();
-    pub static ref TOKEN_DECIMALS: HashMap<Address, u8> = vec![
-        (WBTC, 8),
-        (ETH, 18),
-        (MSX, 6),
-        (CUSDC, 8),
-        (MATIC, 18),
-        (COMP, 18),
-        (LINK, 18),
-        (QUICK, 18),
-        (AAVE, 18),
-        (UNI, 18),
-    ]
-    .into_iter()
-    .collect();
+    pub static ref GENESIS_TOKENS: Vec<TokenMetadata> = vec![
+        TokenMetadata {
+            address: WBTC,
+            name: "Wrapped BTC".to_string(),
+            symbol: "WBTC".to_string(),
+            decimals: 8,
+            bridge_address: Some(WBTC),
+        },
+        TokenMetadata {
+            address: ETH,
+            name: "Wrapped Ether".to_string(),
+            symbol: "ETH".to_string(),
+            decimals: 18,
+            bridge_address: Some(ETH),
+        },
+        TokenMetadata {
+            address: MSX,
+            name: "Moonshine".to_string(),
+            symbol: "MSX".to_string(),
+            decimals: 6,
+            bridge_address: Some(MSX),
+        },
+        TokenMetadata {
+            address: CUSDC,
+            name: "Compound USD Coin".to_string(),
+            symbol: "CUSDC".to_string(),
+            decimals: 8,
+            bridge_address: Some(CUSDC),
+        },
+        TokenMetadata {
+            address: MATIC,
+            name: "Wrapped Matic".to_string(),
+            symbol: "MATIC".to_string(),
+            decimals: 18,
+            bridge_address: Some(MATIC),
+        },
+        TokenMetadata {
+            address: COMP,
+            name: "Compound".to_string(),
+            symbol: "COMP".to_string(),
+            decimals: 18,
+            bridge_address: Some(COMP),
+        },
+        TokenMetadata {
+            address: LINK,
+            name: "ChainLink Token".to_string(),
+            symbol: "LINK".to_string(),
+            decimals: 18,
+            bridge_address: Some(LINK),
+        },
+        TokenMetadata {
+            address: QUICK,
+            name: "Quickswap".to_string(),
+            symbol: "QUICK".to_string(),
+            decimals: 18,
+            bridge_address: Some(QUICK),
+        },
+        TokenMetadata {
+            address: AAVE,
+            name: "Aave".to_string(),
+            symbol: "AAVE".to_string(),
+            decimals: 18,
+            bridge_address: Some(AAVE),
+        },
+        TokenMetadata {
+            address: UNI,
+            name: "Uniswap".to_string(),
+            symbol: "UNI".to_string(),
+            decimals: 18,
+            bridge_address: Some(UNI),
+        },
+    ];
     pub static ref TOKENS: [Address; 11] =
         [WBTC, ETH, MSX, CUSDC, MATIC, COMP, SOL, LINK, QUICK, AAVE, UNI,];
 }
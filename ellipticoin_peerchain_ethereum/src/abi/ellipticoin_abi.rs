@@ -9,9 +9,9 @@ lazy_static! {
             {
                 "inputs": [
                     {
-                        "internalType": "int64",
+                        "internalType": "uint256",
                         "name": "",
-                        "type": "int64"
+                        "type": "uint256"
                     },
                     {
                         "internalType": "address",
@@ -26,10 +26,53 @@ lazy_static! {
             },
             {
                 "inputs": [
+                    {
+                        "internalType": "address",
+                        "name": "",
+                        "type": "address"
+                    },
                     {
                         "internalType": "int64",
                         "name": "",
                         "type": "int64"
+                    }
+                ],
+                "name": "approveMultisigAction",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
+            {
+                "inputs": [
+                    {
+                        "components": [
+                            {
+                                "internalType": "address",
+                                "name": "to",
+                                "type": "address"
+                            },
+                            {
+                                "internalType": "bytes",
+                                "name": "data",
+                                "type": "bytes"
+                            }
+                        ],
+                        "internalType": "struct Playground.Step[]",
+                        "name": "steps",
+                        "type": "tuple[]"
+                    }
+                ],
+                "name": "batch",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
+            {
+                "inputs": [
+                    {
+                        "internalType": "uint256",
+                        "name": "",
+                        "type": "uint256"
                     },
                     {
                         "internalType": "address",
@@ -37,9 +80,9 @@ lazy_static! {
                         "type": "address"
                     },
                     {
-                        "internalType": "int64",
+                        "internalType": "uint256",
                         "name": "",
-                        "type": "int64"
+                        "type": "uint256"
                     }
                 ],
                 "name": "buy",
@@ -53,18 +96,67 @@ lazy_static! {
                         "internalType": "int64",
                         "name": "",
                         "type": "int64"
-                    },
+                    }
+                ],
+                "name": "cancelProposal",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
+            {
+                "inputs": [
                     {
                         "internalType": "address",
                         "name": "",
                         "type": "address"
                     },
+                    {
+                        "internalType": "uint256",
+                        "name": "",
+                        "type": "uint256"
+                    }
+                ],
+                "name": "compound",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
+            {
+                "inputs": [
+                    {
+                        "internalType": "address[]",
+                        "name": "",
+                        "type": "address[]"
+                    },
                     {
                         "internalType": "int64",
                         "name": "",
                         "type": "int64"
                     }
                 ],
+                "name": "createMultisig",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
+            {
+                "inputs": [
+                    {
+                        "internalType": "uint256",
+                        "name": "",
+                        "type": "uint256"
+                    },
+                    {
+                        "internalType": "address",
+                        "name": "",
+                        "type": "address"
+                    },
+                    {
+                        "internalType": "uint256",
+                        "name": "",
+                        "type": "uint256"
+                    }
+                ],
                 "name": "createPool",
                 "outputs": [],
                 "stateMutability": "nonpayable",
@@ -73,9 +165,9 @@ lazy_static! {
             {
                 "inputs": [
                     {
-                        "internalType": "int64",
+                        "internalType": "uint256",
                         "name": "",
-                        "type": "int64"
+                        "type": "uint256"
                     },
                     {
                         "internalType": "address",
@@ -88,6 +180,39 @@ lazy_static! {
                 "stateMutability": "nonpayable",
                 "type": "function"
             },
+            {
+                "inputs": [
+                    {
+                        "internalType": "address",
+                        "name": "",
+                        "type": "address"
+                    }
+                ],
+                "name": "delegate",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
+            {
+                "inputs": [
+                    {
+                        "internalType": "int64",
+                        "name": "",
+                        "type": "int64"
+                    }
+                ],
+                "name": "executeProposal",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
+            {
+                "inputs": [],
+                "name": "harvest",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
             {
                 "inputs": [
                     {
@@ -98,9 +223,9 @@ lazy_static! {
                                 "type": "uint8"
                             },
                             {
-                                "internalType": "int64",
+                                "internalType": "uint256",
                                 "name": "amount",
-                                "type": "int64"
+                                "type": "uint256"
                             },
                             {
                                 "internalType": "address",
@@ -151,6 +276,24 @@ lazy_static! {
                 "stateMutability": "nonpayable",
                 "type": "function"
             },
+            {
+                "inputs": [
+                    {
+                        "internalType": "bytes32",
+                        "name": "",
+                        "type": "bytes32"
+                    },
+                    {
+                        "internalType": "int64",
+                        "name": "",
+                        "type": "int64"
+                    }
+                ],
+                "name": "rotateHashOnion",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
             {
                 "inputs": [
                     {
@@ -166,10 +309,28 @@ lazy_static! {
             },
             {
                 "inputs": [
+                    {
+                        "internalType": "bytes32",
+                        "name": "",
+                        "type": "bytes32"
+                    },
                     {
                         "internalType": "int64",
                         "name": "",
                         "type": "int64"
+                    }
+                ],
+                "name": "sealAt",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
+            {
+                "inputs": [
+                    {
+                        "internalType": "uint256",
+                        "name": "",
+                        "type": "uint256"
                     },
                     {
                         "internalType": "address",
@@ -177,9 +338,9 @@ lazy_static! {
                         "type": "address"
                     },
                     {
-                        "internalType": "int64",
+                        "internalType": "uint256",
                         "name": "",
-                        "type": "int64"
+                        "type": "uint256"
                     }
                 ],
                 "name": "sell",
@@ -210,6 +371,36 @@ lazy_static! {
                 "stateMutability": "nonpayable",
                 "type": "function"
             },
+            {
+                "inputs": [],
+                "name": "stopMining",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
+            {
+                "inputs": [
+                    {
+                        "internalType": "address",
+                        "name": "",
+                        "type": "address"
+                    },
+                    {
+                        "internalType": "address",
+                        "name": "",
+                        "type": "address"
+                    },
+                    {
+                        "internalType": "bytes",
+                        "name": "",
+                        "type": "bytes"
+                    }
+                ],
+                "name": "submitMultisigAction",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
         {
                 "constant": false,
                 "inputs": [
@@ -233,6 +424,51 @@ lazy_static! {
                 "stateMutability": "nonpayable",
                 "type": "function"
             },
+            {
+                "inputs": [],
+                "name": "undelegate",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
+            {
+                "inputs": [
+                    {
+                        "internalType": "int64",
+                        "name": "",
+                        "type": "int64"
+                    },
+                    {
+                        "internalType": "bool",
+                        "name": "",
+                        "type": "bool"
+                    }
+                ],
+                "name": "vote",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
+            {
+                "inputs": [],
+                "name": "withdrawBond",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
+            {
+                "inputs": [
+                    {
+                        "internalType": "int64",
+                        "name": "",
+                        "type": "int64"
+                    }
+                ],
+                "name": "withdrawVote",
+                "outputs": [],
+                "stateMutability": "nonpayable",
+                "type": "function"
+            },
         ]
                                 )
         .to_string()
@@ -2,20 +2,22 @@ mod ellipticoin_abi;
 pub mod erc20_abi;
 mod signature_hashes;
 
-use crate::constants::ELLIPTICOIN_DECIMALS;
 use byte_slice_cast::AsByteSlice;
 use ellipticoin_abi::ELLIPTICOIN_ABI;
-use ellipticoin_contracts::token::tokens::TOKEN_METADATA;
 use ellipticoin_contracts::token::tokens::USD;
 use ellipticoin_contracts::{
     bridge::{EthereumMessage, PolygonMessage},
-    system::Action,
+    governance::Choice,
+    system::{Action, LegacyAction, LegacyPolygonMessage},
+    Token,
 };
-use ellipticoin_types::{Address, Uint};
+use ellipticoin_types::{
+    db::{Backend, Db},
+    Address, Amount, Uint, U256,
+};
+use erc20_abi::ERC20_ABI;
 
 use num_bigint::BigUint;
-use num_traits::pow;
-use num_traits::ToPrimitive;
 use std::{
     convert::{TryFrom, TryInto},
     fmt,
@@ -32,35 +34,105 @@ impl fmt::Display for AbiError {
     }
 }
 
-pub fn encode_action(action: &Action) -> Vec<u8> {
-    match action {
+pub fn encode_action<B: Backend>(db: &mut Db<B>, action: &Action) -> Result<Vec<u8>> {
+    Ok(match action {
         Action::AddLiquidity(amount, token) => vec![
             signature_hashes::ADD_LIQUIDITY.to_vec(),
-            serde_eth::to_vec(&((*amount).as_i64(), serde_eth::Address(token.into()))).unwrap(),
+            serde_eth::to_vec(&(
+                serde_eth::U256(BigUint::from(*amount)),
+                serde_eth::Address(token.into()),
+            ))
+            .unwrap(),
+        ],
+        Action::Approve(spender, underlying_amount, token) => vec![
+            signature_hashes::APPROVE.to_vec(),
+            serde_eth::to_vec(&(
+                serde_eth::Address(spender.into()),
+                serde_eth::U256(BigUint::from(encode_allowance(
+                    db,
+                    *underlying_amount,
+                    *token,
+                )?)),
+            ))
+            .unwrap(),
+        ],
+        Action::ApproveMultisigAction(account, id) => vec![
+            signature_hashes::APPROVE_MULTISIG_ACTION.to_vec(),
+            serde_eth::to_vec(&(serde_eth::Address(account.into()), (*id).as_i64())).unwrap(),
+        ],
+        Action::Batch(actions) => vec![
+            signature_hashes::BATCH.to_vec(),
+            ethabi::encode(&[ethabi::Token::Array(
+                actions
+                    .iter()
+                    .map(|action| {
+                        Ok(ethabi::Token::Tuple(vec![
+                            ethabi::Token::Address(nested_action_to(action).0.into()),
+                            ethabi::Token::Bytes(encode_action(db, action)?),
+                        ]))
+                    })
+                    .collect::<Result<Vec<ethabi::Token>>>()?,
+            )]),
         ],
         Action::Buy(underlying_input_amount, token, minimum_underlying_output_amount) => vec![
             signature_hashes::BUY.to_vec(),
             serde_eth::to_vec(&(
-                (*underlying_input_amount).as_i64(),
+                serde_eth::U256(BigUint::from(*underlying_input_amount)),
+                serde_eth::Address(token.into()),
+                serde_eth::U256(BigUint::from(*minimum_underlying_output_amount)),
+            ))
+            .unwrap(),
+        ],
+        Action::CancelProposal(proposal_id) => vec![
+            signature_hashes::CANCEL_PROPOSAL.to_vec(),
+            serde_eth::to_vec(&((*proposal_id).as_i64())).unwrap(),
+        ],
+        Action::Compound(token, minimum_underlying_output_amount) => vec![
+            signature_hashes::COMPOUND.to_vec(),
+            serde_eth::to_vec(&(
                 serde_eth::Address(token.into()),
-                (*minimum_underlying_output_amount).as_i64(),
+                serde_eth::U256(BigUint::from(*minimum_underlying_output_amount)),
             ))
             .unwrap(),
         ],
+        Action::CreateMultisig(owners, threshold) => vec![
+            signature_hashes::CREATE_MULTISIG.to_vec(),
+            ethabi::encode(&[
+                ethabi::Token::Array(
+                    owners
+                        .iter()
+                        .map(|owner| ethabi::Token::Address(owner.0.into()))
+                        .collect(),
+                ),
+                ethabi::Token::Int(u64::from(*threshold).into()),
+            ]),
+        ],
         Action::CreatePool(amount, token, initial_price) => vec![
             signature_hashes::CREATE_POOL.to_vec(),
             serde_eth::to_vec(&(
-                (*amount).as_i64(),
+                serde_eth::U256(BigUint::from(*amount)),
                 serde_eth::Address(token.into()),
-                (*initial_price).as_i64(),
+                serde_eth::U256(BigUint::from(*initial_price)),
             ))
             .unwrap(),
         ],
+        Action::Delegate(delegate) => vec![
+            signature_hashes::DELEGATE.to_vec(),
+            serde_eth::to_vec(&(serde_eth::Address(delegate.into()))).unwrap(),
+        ],
+        Action::ExecuteProposal(proposal_id) => vec![
+            signature_hashes::EXECUTE_PROPOSAL.to_vec(),
+            serde_eth::to_vec(&((*proposal_id).as_i64())).unwrap(),
+        ],
+        Action::Harvest => vec![signature_hashes::HARVEST.to_vec()],
         Action::Pay(recipient, underlying_amount, token) => vec![
             signature_hashes::TRANSFER.to_vec(),
             serde_eth::to_vec(&(
                 serde_eth::Address(recipient.into()),
-                serde_eth::U256(scale_up_token_decimals(*underlying_amount, *token).unwrap()),
+                serde_eth::U256(BigUint::from(
+                    Token::amount_to_native(db, Amount(*underlying_amount), *token)
+                        .map_err(|_| AbiError)?,
+                )),
             ))
             .unwrap(),
         ],
@@ -71,20 +143,51 @@ pub fn encode_action(action: &Action) -> Vec<u8> {
         Action::Sell(underlying_input_amount, token, minimum_underlying_output_amount) => vec![
             signature_hashes::SELL.to_vec(),
             serde_eth::to_vec(&(
-                (*underlying_input_amount).as_i64(),
+                serde_eth::U256(BigUint::from(*underlying_input_amount)),
                 serde_eth::Address(token.into()),
-                (*minimum_underlying_output_amount).as_i64(),
+                serde_eth::U256(BigUint::from(*minimum_underlying_output_amount)),
             ))
             .unwrap(),
         ],
+        Action::RotateHashOnion(onion_skin, layer_count) => vec![
+            signature_hashes::ROTATE_HASH_ONION.to_vec(),
+            serde_eth::to_vec(&(onion_skin, (*layer_count).as_i64())).unwrap(),
+        ],
         Action::Seal(onion_skin) => vec![
             signature_hashes::SEAL.to_vec(),
             serde_eth::to_vec(&(onion_skin)).unwrap(),
         ],
+        Action::SealAt(onion_skin, timestamp) => vec![
+            signature_hashes::SEAL_AT.to_vec(),
+            serde_eth::to_vec(&(onion_skin, (*timestamp).as_i64())).unwrap(),
+        ],
         Action::StartMining(host, onion_skin, layer_count) => vec![
             signature_hashes::START_MINING.to_vec(),
             serde_eth::to_vec(&(host, onion_skin, layer_count)).unwrap(),
         ],
+        Action::StopMining => vec![signature_hashes::STOP_MINING.to_vec()],
+        Action::TransferFrom(owner, recipient, underlying_amount, token) => vec![
+            signature_hashes::TRANSFER_FROM.to_vec(),
+            serde_eth::to_vec(&(
+                serde_eth::Address(owner.into()),
+                serde_eth::Address(recipient.into()),
+                serde_eth::U256(BigUint::from(
+                    Token::amount_to_native(db, Amount(*underlying_amount), *token)
+                        .map_err(|_| AbiError)?,
+                )),
+            ))
+            .unwrap(),
+        ],
+        Action::Undelegate => vec![signature_hashes::UNDELEGATE.to_vec()],
+        Action::Vote(proposal_id, choice) => vec![
+            signature_hashes::VOTE.to_vec(),
+            serde_eth::to_vec(&((*proposal_id).as_i64(), *choice == Choice::For)).unwrap(),
+        ],
+        Action::WithdrawBond => vec![signature_hashes::WITHDRAW_BOND.to_vec()],
+        Action::WithdrawVote(proposal_id) => vec![
+            signature_hashes::WITHDRAW_VOTE.to_vec(),
+            serde_eth::to_vec(&((*proposal_id).as_i64())).unwrap(),
+        ],
         Action::ProcessPolygonMessages(messages, block_number) => vec![
             signature_hashes::PROCESS_POLYGON_MESSAGES.to_vec(),
             ethabi::encode(
@@ -95,7 +198,7 @@ pub fn encode_action(action: &Action) -> Vec<u8> {
                             .map(|message| match message {
                                 PolygonMessage::Deposit(amount, token, address) => {
                                     ethabi::Token::Tuple(vec![
-                                        ethabi::Token::Uint((*amount).into()),
+                                        ethabi::Token::Uint(ethabi::ethereum_types::U256(amount.0)),
                                         ethabi::Token::Address(token.0.into()),
                                         ethabi::Token::Address(address.0.into()),
                                         ethabi::Token::Int(0.into()),
@@ -139,52 +242,158 @@ pub fn encode_action(action: &Action) -> Vec<u8> {
                 ][..],
             ),
         ],
+        Action::SubmitMultisigAction(account, action) => vec![
+            signature_hashes::SUBMIT_MULTISIG_ACTION.to_vec(),
+            ethabi::encode(&[
+                ethabi::Token::Address(account.0.into()),
+                ethabi::Token::Address(nested_action_to(action).0.into()),
+                ethabi::Token::Bytes(encode_action(db, action)?),
+            ]),
+        ],
         Action::CreateWithdrawlRequest(value, token) => vec![
-            ELLIPTICOIN_ABI.functions[3].method_id().to_vec(),
+            signature_hashes::CREATE_WITHDRAWL_REQUEST.to_vec(),
             ethereum_abi::Value::encode(&[encode(*value), encode(*token)]),
         ],
-        action => panic!("unknown action {:?}", action),
+        Action::AddToken(..) | Action::Null | Action::SetParameter(..) => return Err(AbiError),
+    }
+    .concat())
+}
+
+// Legacy transactions are verified against the data they were signed with.
+// Actions whose encoding hasn't changed are encoded as their upgraded action.
+pub fn encode_legacy_action<B: Backend>(db: &mut Db<B>, action: &LegacyAction) -> Result<Vec<u8>> {
+    Ok(match action {
+        LegacyAction::AddLiquidity(amount, token) => vec![
+            signature_hashes::LEGACY_ADD_LIQUIDITY.to_vec(),
+            serde_eth::to_vec(&((*amount).as_i64(), serde_eth::Address(token.into()))).unwrap(),
+        ],
+        LegacyAction::Buy(input_amount, token, minimum_output_amount) => vec![
+            signature_hashes::LEGACY_BUY.to_vec(),
+            serde_eth::to_vec(&(
+                (*input_amount).as_i64(),
+                serde_eth::Address(token.into()),
+                (*minimum_output_amount).as_i64(),
+            ))
+            .unwrap(),
+        ],
+        LegacyAction::CreatePool(amount, token, starting_price) => vec![
+            signature_hashes::LEGACY_CREATE_POOL.to_vec(),
+            serde_eth::to_vec(&(
+                (*amount).as_i64(),
+                serde_eth::Address(token.into()),
+                (*starting_price).as_i64(),
+            ))
+            .unwrap(),
+        ],
+        LegacyAction::CreateWithdrawlRequest(amount, token) => vec![
+            signature_hashes::LEGACY_CREATE_WITHDRAWL_REQUEST.to_vec(),
+            ethereum_abi::Value::encode(&[encode(*amount), encode(*token)]),
+        ],
+        LegacyAction::ProcessPolygonMessages(messages, block_number) => vec![
+            signature_hashes::LEGACY_PROCESS_POLYGON_MESSAGES.to_vec(),
+            ethabi::encode(
+                &[
+                    ethabi::Token::Array(
+                        messages
+                            .iter()
+                            .map(|message| match message {
+                                LegacyPolygonMessage::Deposit(amount, token, address) => {
+                                    ethabi::Token::Tuple(vec![
+                                        ethabi::Token::Uint((*amount).into()),
+                                        ethabi::Token::Address(token.0.into()),
+                                        ethabi::Token::Address(address.0.into()),
+                                        ethabi::Token::Int(0.into()),
+                                        ethabi::Token::FixedBytes(vec![0; 32]),
+                                    ])
+                                }
+                                LegacyPolygonMessage::ProcessWithdrawl(
+                                    withdrawl_id,
+                                    transaction_hash,
+                                ) => ethabi::Token::Tuple(vec![
+                                    ethabi::Token::Uint((0).into()),
+                                    ethabi::Token::Address([0; 20].into()),
+                                    ethabi::Token::Address([0; 20].into()),
+                                    ethabi::Token::Int((*withdrawl_id).into()),
+                                    ethabi::Token::FixedBytes(transaction_hash.to_vec().into()),
+                                ]),
+                            })
+                            .collect::<Vec<ethabi::Token>>(),
+                    ),
+                    ethabi::Token::Uint(<u64>::from(*block_number).into()),
+                ][..],
+            ),
+        ],
+        LegacyAction::Sell(input_amount, token, minimum_output_amount) => vec![
+            signature_hashes::LEGACY_SELL.to_vec(),
+            serde_eth::to_vec(&(
+                (*input_amount).as_i64(),
+                serde_eth::Address(token.into()),
+                (*minimum_output_amount).as_i64(),
+            ))
+            .unwrap(),
+        ],
+        action => {
+            let upgraded_action = action.upgrade(db).map_err(|_| AbiError)?;
+            vec![encode_action(db, &upgraded_action)?]
+        }
     }
-    .concat()
+    .concat())
 }
 
-pub fn decode_action(to: &[u8], value: &[u8], data: &[u8]) -> Result<Action> {
+pub fn decode_action<B: Backend>(
+    db: &mut Db<B>,
+    to: &[u8],
+    value: &[u8],
+    data: &[u8],
+) -> Result<Action> {
     if data.len() > 0 {
-        decode_transcation_data(Address::try_from(to).map_err(|_| AbiError)?, data)
+        decode_transcation_data(db, Address::try_from(to).map_err(|_| AbiError)?, data)
     } else {
         Ok(Action::Pay(
             Address::try_from(to).map_err(|_| AbiError)?,
-            decode_transcation_value(value),
+            decode_transcation_value(value)?,
             USD,
         ))
     }
 }
-pub fn decode_transcation_value(value: &[u8]) -> Uint {
-    Uint::try_from(
-        (BigUint::from_bytes_be(value)
-            / BigUint::from(pow(
-                BigUint::from(10u32),
-                18 as usize - *ELLIPTICOIN_DECIMALS,
-            )))
-        .to_u64()
-        .unwrap(),
-    )
-    .unwrap()
+pub fn decode_transcation_value(value: &[u8]) -> Result<U256> {
+    U256::from_be_bytes(value).map_err(|_| AbiError)
 }
-pub fn decode_transcation_data(to: Address, data: &[u8]) -> Result<Action> {
+pub fn decode_transcation_data<B: Backend>(
+    db: &mut Db<B>,
+    to: Address,
+    data: &[u8],
+) -> Result<Action> {
     let f = ELLIPTICOIN_ABI
         .decode_input_from_slice(data)
+        .or_else(|_| ERC20_ABI.decode_input_from_slice(data))
         .map_err(|_| AbiError)?;
     match f.0.name.as_ref() {
         "addLiquidity" => Ok(Action::AddLiquidity(
             decode(&f.1[0].value)?,
             decode(&f.1[1].value)?,
         )),
+        "approve" => Ok(Action::Approve(
+            decode(&f.1[0].value)?,
+            decode_allowance(db, &f.1[1].value, to)?,
+            to,
+        )),
+        "approveMultisigAction" => Ok(Action::ApproveMultisigAction(
+            decode(&f.1[0].value)?,
+            decode(&f.1[1].value)?,
+        )),
+        "batch" => Ok(Action::Batch(decode_batch(db, &data[4..])?)),
         "buy" => Ok(Action::Buy(
             decode(&f.1[0].value)?,
             decode(&f.1[1].value)?,
             decode(&f.1[2].value)?,
         )),
+        "cancelProposal" => Ok(Action::CancelProposal(decode(&f.1[0].value)?)),
+        "compound" => Ok(Action::Compound(
+            decode(&f.1[0].value)?,
+            decode(&f.1[1].value)?,
+        )),
+        "createMultisig" => decode_create_multisig(&data[4..]),
         "createPool" => Ok(Action::CreatePool(
             decode(&f.1[0].value)?,
             decode(&f.1[1].value)?,
@@ -194,6 +403,9 @@ pub fn decode_transcation_data(to: Address, data: &[u8]) -> Result<Action> {
             decode(&f.1[0].value)?,
             decode(&f.1[1].value)?,
         )),
+        "delegate" => Ok(Action::Delegate(decode(&f.1[0].value)?)),
+        "executeProposal" => Ok(Action::ExecuteProposal(decode(&f.1[0].value)?)),
+        "harvest" => Ok(Action::Harvest),
         "processEthereumMessages" => Ok(Action::ProcessEthereumMessages(
             vec![],
             decode(&f.1[0].value)?,
@@ -216,17 +428,128 @@ pub fn decode_transcation_data(to: Address, data: &[u8]) -> Result<Action> {
             decode(&f.1[0].value)?,
             decode(&f.1[1].value)?,
         )),
+        "rotateHashOnion" => Ok(Action::RotateHashOnion(
+            decode(&f.1[0].value)?,
+            decode(&f.1[1].value)?,
+        )),
         "seal" => Ok(Action::Seal(decode(&f.1[0].value)?)),
+        "sealAt" => Ok(Action::SealAt(
+            decode(&f.1[0].value)?,
+            decode(&f.1[1].value)?,
+        )),
         "startMining" => Ok(Action::StartMining(
             decode(&f.1[0].value)?,
             decode(&f.1[1].value)?,
             decode(&f.1[2].value)?,
         )),
+        "stopMining" => Ok(Action::StopMining),
+        "submitMultisigAction" => decode_submit_multisig_action(db, &data[4..]),
         "transfer" => Ok(Action::Pay(
             decode(&f.1[0].value)?,
-            decode_scaled_uint(&f.1[1].value, to)?,
+            decode_native_amount(db, &f.1[1].value, to)?,
+            to,
+        )),
+        "transferFrom" => Ok(Action::TransferFrom(
+            decode(&f.1[0].value)?,
+            decode(&f.1[1].value)?,
+            decode_native_amount(db, &f.1[2].value, to)?,
             to,
         )),
+        "undelegate" => Ok(Action::Undelegate),
+        "vote" => Ok(Action::Vote(
+            decode(&f.1[0].value)?,
+            if decode(&f.1[1].value)? {
+                Choice::For
+            } else {
+                Choice::Against
+            },
+        )),
+        "withdrawBond" => Ok(Action::WithdrawBond),
+        "withdrawVote" => Ok(Action::WithdrawVote(decode(&f.1[0].value)?)),
+        _ => Err(AbiError),
+    }
+}
+
+// Token actions are decoded using the address they're sent to so batched and
+// multisig actions carry that address along with their call data.
+fn nested_action_to(action: &Action) -> Address {
+    match action {
+        Action::Approve(_spender, _amount, token) => *token,
+        Action::Pay(_recipient, _amount, token) => *token,
+        Action::TransferFrom(_owner, _recipient, _amount, token) => *token,
+        _ => Default::default(),
+    }
+}
+
+fn decode_batch<B: Backend>(db: &mut Db<B>, data: &[u8]) -> Result<Vec<Action>> {
+    let tokens = ethabi::decode(
+        &[ethabi::ParamType::Array(Box::new(
+            ethabi::ParamType::Tuple(vec![ethabi::ParamType::Address, ethabi::ParamType::Bytes]),
+        ))],
+        data,
+    )
+    .map_err(|_| AbiError)?;
+    let steps = match tokens.into_iter().next() {
+        Some(ethabi::Token::Array(steps)) => steps,
+        _ => return Err(AbiError),
+    };
+    steps
+        .into_iter()
+        .map(|step| match step {
+            ethabi::Token::Tuple(step) => match &step[..] {
+                [ethabi::Token::Address(to), ethabi::Token::Bytes(data)] => {
+                    decode_transcation_data(db, Address(to.0), data)
+                }
+                _ => Err(AbiError),
+            },
+            _ => Err(AbiError),
+        })
+        .collect()
+}
+
+fn decode_create_multisig(data: &[u8]) -> Result<Action> {
+    let tokens = ethabi::decode(
+        &[
+            ethabi::ParamType::Array(Box::new(ethabi::ParamType::Address)),
+            ethabi::ParamType::Int(64),
+        ],
+        data,
+    )
+    .map_err(|_| AbiError)?;
+    match &tokens[..] {
+        [ethabi::Token::Array(owners), ethabi::Token::Int(threshold)] => {
+            Ok(Action::CreateMultisig(
+                owners
+                    .iter()
+                    .map(|owner| match owner {
+                        ethabi::Token::Address(owner) => Ok(Address(owner.0)),
+                        _ => Err(AbiError),
+                    })
+                    .collect::<Result<Vec<Address>>>()?,
+                Uint::try_from(threshold.low_u64()).map_err(|_| AbiError)?,
+            ))
+        }
+        _ => Err(AbiError),
+    }
+}
+
+fn decode_submit_multisig_action<B: Backend>(db: &mut Db<B>, data: &[u8]) -> Result<Action> {
+    let tokens = ethabi::decode(
+        &[
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Bytes,
+        ],
+        data,
+    )
+    .map_err(|_| AbiError)?;
+    match &tokens[..] {
+        [ethabi::Token::Address(account), ethabi::Token::Address(to), ethabi::Token::Bytes(data)] => {
+            Ok(Action::SubmitMultisigAction(
+                Address(account.0),
+                Box::new(decode_transcation_data(db, Address(to.0), data)?),
+            ))
+        }
         _ => Err(AbiError),
     }
 }
@@ -248,47 +571,49 @@ impl Encodable<'_> for Uint {
     }
 }
 
+impl Encodable<'_> for U256 {
+    fn encode(n: Self) -> ethereum_abi::Value {
+        ethereum_abi::Value::Uint(ethabi::ethereum_types::U256(n.0), 256)
+    }
+}
+
 impl Encodable<'_> for Address {
     fn encode(address: Self) -> ethereum_abi::Value {
         ethereum_abi::Value::Address(ethabi::ethereum_types::H160(address.0))
     }
 }
 
-fn decode_scaled_uint(value: &ethereum_abi::Value, token: Address) -> Result<Uint> {
-    if let ethereum_abi::Value::Uint(u256, 256) = value {
-        scale_down_token_decimals(BigUint::from_bytes_le(u256.as_byte_slice()), token)
-    } else {
-        Err(AbiError)
-    }
+fn decode_native_amount<B: Backend>(
+    db: &mut Db<B>,
+    value: &ethereum_abi::Value,
+    token: Address,
+) -> Result<U256> {
+    Ok(Token::native_to_amount(db, decode(value)?, token)
+        .map_err(|_| AbiError)?
+        .into())
 }
 
-fn scale_up_token_decimals(value: Uint, token: Address) -> Result<BigUint> {
-    Ok(scale_uint(
-        &BigUint::from(value.as_i64() as u64),
-        TOKEN_METADATA.get(&token).ok_or(AbiError)?.decimals as isize
-            - *ELLIPTICOIN_DECIMALS as isize,
-    ))
-}
-fn scale_down_token_decimals(value: BigUint, token: Address) -> Result<Uint> {
-    Ok(Uint::try_from(
-        scale_uint(
-            &value,
-            *ELLIPTICOIN_DECIMALS as isize
-                - TOKEN_METADATA.get(&token).ok_or(AbiError)?.decimals as isize,
-        )
-        .to_u64()
-        .ok_or(AbiError)?,
-    )
-    .map_err(|_| AbiError)?)
+// `MAX_UINT256` is the standard infinite approval and is passed through unscaled.
+fn encode_allowance<B: Backend>(db: &mut Db<B>, allowance: U256, token: Address) -> Result<U256> {
+    if allowance == U256::MAX {
+        Ok(U256::MAX)
+    } else {
+        Token::amount_to_native(db, Amount(allowance), token).map_err(|_| AbiError)
+    }
 }
 
-fn scale_uint(value: &BigUint, scale: isize) -> BigUint {
-    if scale == 0 {
-        value.clone()
-    } else if scale > 0 {
-        value * BigUint::from(pow(BigUint::from(10u8), scale.abs() as usize))
+fn decode_allowance<B: Backend>(
+    db: &mut Db<B>,
+    value: &ethereum_abi::Value,
+    token: Address,
+) -> Result<U256> {
+    let native_allowance: U256 = decode(value)?;
+    if native_allowance == U256::MAX {
+        Ok(U256::MAX)
     } else {
-        value / BigUint::from(pow(BigUint::from(10u8), scale.abs() as usize))
+        Ok(Token::native_to_amount(db, native_allowance, token)
+            .map_err(|_| AbiError)?
+            .into())
     }
 }
 
@@ -320,6 +645,16 @@ impl Decodable<'_> for [u8; 32] {
     }
 }
 
+impl Decodable<'_> for bool {
+    fn decode(value: &ethereum_abi::Value) -> Result<Self> {
+        if let ethereum_abi::Value::Bool(b) = value {
+            Ok(*b)
+        } else {
+            Err(AbiError)
+        }
+    }
+}
+
 impl Decodable<'_> for Address {
     fn decode(value: &ethereum_abi::Value) -> Result<Self> {
         if let ethereum_abi::Value::Address(h160) = value {
@@ -342,3 +677,13 @@ impl Decodable<'_> for Uint {
         }
     }
 }
+
+impl Decodable<'_> for U256 {
+    fn decode(value: &ethereum_abi::Value) -> Result<Self> {
+        if let ethereum_abi::Value::Uint(u256, _) = value {
+            Ok(U256(u256.0))
+        } else {
+            Err(AbiError)
+        }
+    }
+}
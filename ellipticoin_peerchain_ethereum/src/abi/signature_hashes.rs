@@ -1,11 +1,38 @@
 use hex_literal::hex;
-pub const ADD_LIQUIDITY: [u8; 4] = hex!("1ee8621d"); // createPool(int64,address)
-pub const BUY: [u8; 4] = hex!("09d351a0"); // buy(int64,address,int64,address)
+pub const ADD_LIQUIDITY: [u8; 4] = hex!("c95f9d0e"); // addLiquidity(uint256,address)
+pub const APPROVE: [u8; 4] = hex!("095ea7b3"); // approve(address,uint256)
+pub const APPROVE_MULTISIG_ACTION: [u8; 4] = hex!("b7871221"); // approveMultisigAction(address,int64)
+pub const BATCH: [u8; 4] = hex!("5773341e"); // batch((address,bytes)[])
+pub const BUY: [u8; 4] = hex!("2afaca20"); // buy(uint256,address,uint256)
 pub const TRANSFER: [u8; 4] = hex!("a9059cbb"); // transfer(address,uint256)
-pub const CREATE_POOL: [u8; 4] = hex!("9f2c4c6f"); // createPool(int64,address,int64)
-pub const PROCESS_POLYGON_MESSAGES: [u8; 4] = hex!("00d961d1"); // processPolygonMessages((uint8,int64,address,address,int64,bytes32)[])
+pub const CANCEL_PROPOSAL: [u8; 4] = hex!("4d13449a"); // cancelProposal(int64)
+pub const COMPOUND: [u8; 4] = hex!("18fc0f90"); // compound(address,uint256)
+pub const CREATE_MULTISIG: [u8; 4] = hex!("7c197836"); // createMultisig(address[],int64)
+pub const CREATE_POOL: [u8; 4] = hex!("674ba55d"); // createPool(uint256,address,uint256)
+pub const CREATE_WITHDRAWL_REQUEST: [u8; 4] = hex!("53f2fe8b"); // createWithdrawlRequest(uint256,address)
+pub const DELEGATE: [u8; 4] = hex!("5c19a95c"); // delegate(address)
+pub const EXECUTE_PROPOSAL: [u8; 4] = hex!("5d9e2530"); // executeProposal(int64)
+pub const HARVEST: [u8; 4] = hex!("4641257d"); // harvest()
+pub const PROCESS_POLYGON_MESSAGES: [u8; 4] = hex!("a3e39f18"); // processPolygonMessages((uint8,uint256,address,address,int64,bytes32)[])
 pub const PROCESS_ETHEREUM_MESSAGES: [u8; 4] = hex!("c2ad2d7e"); // processEthereumMessages((uint8,int64,address,address,int64,bytes32)[])
 pub const REMOVE_LIQUIDITY: [u8; 4] = hex!("e47f9ade"); // createPool(int64,address)
+pub const ROTATE_HASH_ONION: [u8; 4] = hex!("463c7c0f"); // rotateHashOnion(bytes32,int64)
 pub const SEAL: [u8; 4] = hex!("b07eeda8"); // seal(bytes32)
-pub const SELL: [u8; 4] = hex!("255f7e5b"); // sell(int64,address,int64,address)
+pub const SEAL_AT: [u8; 4] = hex!("fe236d47"); // sealAt(bytes32,int64)
+pub const SELL: [u8; 4] = hex!("67cdf6c9"); // sell(uint256,address,uint256)
 pub const START_MINING: [u8; 4] = hex!("cc0b4376"); // startMining(string,bytes32,int64)
+pub const STOP_MINING: [u8; 4] = hex!("da408679"); // stopMining()
+pub const SUBMIT_MULTISIG_ACTION: [u8; 4] = hex!("b5b0ac15"); // submitMultisigAction(address,address,bytes)
+pub const TRANSFER_FROM: [u8; 4] = hex!("23b872dd"); // transferFrom(address,address,uint256)
+pub const UNDELEGATE: [u8; 4] = hex!("92ab89bb"); // undelegate()
+pub const VOTE: [u8; 4] = hex!("f4b618dc"); // vote(int64,bool)
+pub const WITHDRAW_BOND: [u8; 4] = hex!("66eb9cec"); // withdrawBond()
+pub const WITHDRAW_VOTE: [u8; 4] = hex!("d7e49d46"); // withdrawVote(int64)
+
+// Selectors legacy transactions were signed with before amounts became uint256s.
+pub const LEGACY_ADD_LIQUIDITY: [u8; 4] = hex!("1ee8621d"); // addLiquidity(int64,address)
+pub const LEGACY_BUY: [u8; 4] = hex!("09d351a0"); // buy(int64,address,int64)
+pub const LEGACY_CREATE_POOL: [u8; 4] = hex!("9f2c4c6f"); // createPool(int64,address,int64)
+pub const LEGACY_CREATE_WITHDRAWL_REQUEST: [u8; 4] = hex!("0d186ad0"); // createWithdrawlRequest(int64,address)
+pub const LEGACY_PROCESS_POLYGON_MESSAGES: [u8; 4] = hex!("00d961d1"); // processPolygonMessages((uint8,int64,address,address,int64,bytes32)[])
+pub const LEGACY_SELL: [u8; 4] = hex!("255f7e5b"); // sell(int64,address,int64)
@@ -1,5 +1,4 @@
 use crate::{helpers::eth_address, json_rpc::Provider};
-use ellipticoin_contracts::constants::BASE_FACTOR;
 use ellipticoin_types::Address;
 use hex_literal::hex;
 use k256::ecdsa::SigningKey;
@@ -15,7 +14,6 @@ lazy_static! {
         .unwrap()
     };
     pub static ref ADDRESS: Address = eth_address(&PRIVATE_KEY.verifying_key());
-    pub static ref ELLIPTICOIN_DECIMALS: usize = BASE_FACTOR.to_string().len() - 1;
     pub static ref ETHEREUM_WEB3_URL: String =
         env::var("ETHEREUM_WEB3_URL").expect("ETHEREUM_WEB3_URL not set");
     pub static ref POLYGON_WEB3_URL: String =
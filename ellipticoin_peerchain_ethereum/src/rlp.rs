@@ -1,83 +1,490 @@
+use ellipticoin_types::Address;
 use num_bigint::BigUint;
-use num_traits::{FromPrimitive, ToPrimitive};
+use std::{convert::TryInto, fmt, mem::size_of};
 
-pub fn encode(input: Vec<Vec<u8>>) -> Vec<u8> {
-    let mut result = input
-        .iter()
-        .map(encode_item)
-        .collect::<Vec<Vec<u8>>>()
-        .concat();
-    if result.len() > 55 {
-        let length = BigUint::from_usize(result.len()).unwrap();
-        let length_length = length.to_bytes_be().len();
-        result = vec![
-            [vec![length_length as u8 + 0xf7u8], length.to_bytes_be()].concat(),
-            result,
-        ]
-        .concat();
-    } else {
-        result.insert(0, result.len() as u8 + 0xc0u8);
+pub type Result<T> = std::result::Result<T, DecodeError>;
+
+// Lists nested deeper than this are rejected so that untrusted input can't
+// overflow the stack.
+pub const MAX_DEPTH: usize = 32;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Item {
+    Bytes(Vec<u8>),
+    List(Vec<Item>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodeError {
+    UnexpectedEnd,
+    TrailingBytes,
+    NonCanonicalSingleByte,
+    NonCanonicalLength,
+    LengthOverflow,
+    LeadingZeros,
+    IntegerOverflow,
+    ExpectedBytes,
+    ExpectedList,
+    InvalidLength { expected: usize, got: usize },
+    TooDeep,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => write!(f, "rlp input ended unexpectedly"),
+            DecodeError::TrailingBytes => write!(f, "rlp input has trailing bytes"),
+            DecodeError::NonCanonicalSingleByte => {
+                write!(f, "single byte below 0x80 encoded as a string")
+            }
+            DecodeError::NonCanonicalLength => write!(f, "rlp length is not in canonical form"),
+            DecodeError::LengthOverflow => write!(f, "rlp length overflows"),
+            DecodeError::LeadingZeros => write!(f, "rlp integer has leading zeros"),
+            DecodeError::IntegerOverflow => write!(f, "rlp integer overflows"),
+            DecodeError::ExpectedBytes => write!(f, "expected rlp bytes got a list"),
+            DecodeError::ExpectedList => write!(f, "expected rlp list got bytes"),
+            DecodeError::InvalidLength { expected, got } => {
+                write!(f, "expected length {} got {}", expected, got)
+            }
+            DecodeError::TooDeep => write!(f, "rlp lists are nested too deeply"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl Item {
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            Item::Bytes(bytes) if bytes.len() == 1 && bytes[0] < 0x80 => bytes.to_vec(),
+            Item::Bytes(bytes) => [encode_length(bytes.len(), 0x80), bytes.to_vec()].concat(),
+            Item::List(items) => {
+                let payload = items.iter().map(Item::encode).collect::<Vec<_>>().concat();
+                [encode_length(payload.len(), 0xc0), payload].concat()
+            }
+        }
+    }
+
+    pub fn as_bytes(&self) -> Result<&[u8]> {
+        match self {
+            Item::Bytes(bytes) => Ok(bytes),
+            Item::List(_) => Err(DecodeError::ExpectedBytes),
+        }
+    }
+
+    pub fn as_list(&self) -> Result<&[Item]> {
+        match self {
+            Item::List(items) => Ok(items),
+            Item::Bytes(_) => Err(DecodeError::ExpectedList),
+        }
+    }
+
+    // Returns the items of a list which is expected to have `length` items.
+    pub fn as_fields(&self, length: usize) -> Result<&[Item]> {
+        let items = self.as_list()?;
+        if items.len() != length {
+            return Err(DecodeError::InvalidLength {
+                expected: length,
+                got: items.len(),
+            });
+        }
+        Ok(items)
     }
-    result
 }
 
-pub fn encode_item(input: &Vec<u8>) -> Vec<u8> {
-    if input.len() == 0 {
-        vec![0x80]
-    } else if input.len() == 1 && input[0] < 0x7f {
-        input.to_vec()
-    } else if input.len() < 55 {
-        [vec![input.len() as u8 + 0x80u8], input.to_vec()].concat()
+fn encode_length(length: usize, offset: u8) -> Vec<u8> {
+    if length < 56 {
+        vec![offset + length as u8]
     } else {
-        let length = BigUint::from_usize(input.len()).unwrap();
-        let length_length = length.to_bytes_be().len();
-        vec![
-            [vec![length_length as u8 + 0xb7u8], length.to_bytes_be()].concat(),
-            input.to_vec(),
-        ]
-        .concat()
+        let length_bytes = trim_leading_zeros(&(length as u64).to_be_bytes());
+        [vec![offset + 55 + length_bytes.len() as u8], length_bytes].concat()
     }
 }
 
-pub fn decode(input: &[u8]) -> Vec<Vec<u8>> {
-    if input[0] == 0xf8 && input[1] as usize == input.len() - 2 {
-        decode_next(&input[2..], vec![])
+fn trim_leading_zeros(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .cloned()
+        .skip_while(|byte| *byte == 0)
+        .collect()
+}
+
+pub fn decode_item(input: &[u8]) -> Result<Item> {
+    let (item, rest) = decode_next(input, 0)?;
+    if rest.len() > 0 {
+        return Err(DecodeError::TrailingBytes);
+    }
+    Ok(item)
+}
+
+fn decode_next(input: &[u8], depth: usize) -> Result<(Item, &[u8])> {
+    let prefix = *input.first().ok_or(DecodeError::UnexpectedEnd)?;
+    match prefix {
+        0x00..=0x7f => Ok((Item::Bytes(vec![prefix]), &input[1..])),
+        0x80..=0xbf => {
+            let (payload, rest) = split_payload(input, 0x80)?;
+            if payload.len() == 1 && payload[0] < 0x80 {
+                return Err(DecodeError::NonCanonicalSingleByte);
+            }
+            Ok((Item::Bytes(payload.to_vec()), rest))
+        }
+        0xc0..=0xff => {
+            if depth == MAX_DEPTH {
+                return Err(DecodeError::TooDeep);
+            }
+            let (mut payload, rest) = split_payload(input, 0xc0)?;
+            let mut items = vec![];
+            while payload.len() > 0 {
+                let (item, remaining) = decode_next(payload, depth + 1)?;
+                items.push(item);
+                payload = remaining;
+            }
+            Ok((Item::List(items), rest))
+        }
+    }
+}
+
+fn split_payload(input: &[u8], offset: u8) -> Result<(&[u8], &[u8])> {
+    let prefix = (input[0] - offset) as usize;
+    let (header_length, length) = if prefix < 56 {
+        (1, prefix)
     } else {
-        panic!("not a simple list")
+        let length_length = prefix - 55;
+        let length_bytes = input
+            .get(1..1 + length_length)
+            .ok_or(DecodeError::UnexpectedEnd)?;
+        if length_bytes[0] == 0 {
+            return Err(DecodeError::NonCanonicalLength);
+        }
+        if length_length > size_of::<usize>() {
+            return Err(DecodeError::LengthOverflow);
+        }
+        let length = length_bytes
+            .iter()
+            .fold(0usize, |length, byte| (length << 8) | *byte as usize);
+        if length < 56 {
+            return Err(DecodeError::NonCanonicalLength);
+        }
+        (1 + length_length, length)
+    };
+    let end = header_length
+        .checked_add(length)
+        .ok_or(DecodeError::LengthOverflow)?;
+    let payload = input
+        .get(header_length..end)
+        .ok_or(DecodeError::UnexpectedEnd)?;
+    Ok((payload, &input[end..]))
+}
+
+pub fn encode<E: Encodable + ?Sized>(value: &E) -> Vec<u8> {
+    value.to_rlp_item().encode()
+}
+
+pub fn decode<D: Decodable>(input: &[u8]) -> Result<D> {
+    D::from_rlp_item(&decode_item(input)?)
+}
+
+pub trait Encodable {
+    fn to_rlp_item(&self) -> Item;
+}
+
+pub trait Decodable: Sized {
+    fn from_rlp_item(item: &Item) -> Result<Self>;
+}
+
+impl Encodable for Item {
+    fn to_rlp_item(&self) -> Item {
+        self.clone()
+    }
+}
+
+impl Decodable for Item {
+    fn from_rlp_item(item: &Item) -> Result<Self> {
+        Ok(item.clone())
+    }
+}
+
+impl Encodable for [u8] {
+    fn to_rlp_item(&self) -> Item {
+        Item::Bytes(self.to_vec())
+    }
+}
+
+impl Encodable for Vec<u8> {
+    fn to_rlp_item(&self) -> Item {
+        Item::Bytes(self.to_vec())
+    }
+}
+
+impl Decodable for Vec<u8> {
+    fn from_rlp_item(item: &Item) -> Result<Self> {
+        Ok(item.as_bytes()?.to_vec())
+    }
+}
+
+impl<T: Encodable> Encodable for Vec<T> {
+    fn to_rlp_item(&self) -> Item {
+        Item::List(self.iter().map(Encodable::to_rlp_item).collect())
+    }
+}
+
+impl<T: Decodable> Decodable for Vec<T> {
+    fn from_rlp_item(item: &Item) -> Result<Self> {
+        item.as_list()?.iter().map(T::from_rlp_item).collect()
+    }
+}
+
+impl Encodable for u64 {
+    fn to_rlp_item(&self) -> Item {
+        Item::Bytes(trim_leading_zeros(&self.to_be_bytes()))
     }
 }
 
-fn decode_next(input: &[u8], mut output: Vec<Vec<u8>>) -> Vec<Vec<u8>> {
-    let length = match input[0] {
-        0x00..=0x7f => {
-            output.push(vec![input[0]]);
-            0usize
+impl Decodable for u64 {
+    fn from_rlp_item(item: &Item) -> Result<Self> {
+        let bytes = integer_bytes(item)?;
+        if bytes.len() > size_of::<u64>() {
+            return Err(DecodeError::IntegerOverflow);
         }
-        0x80 => {
-            output.push(vec![]);
-            0usize
+        Ok(bytes.iter().fold(0u64, |n, byte| (n << 8) | *byte as u64))
+    }
+}
+
+impl Encodable for BigUint {
+    fn to_rlp_item(&self) -> Item {
+        Item::Bytes(trim_leading_zeros(&self.to_bytes_be()))
+    }
+}
+
+impl Decodable for BigUint {
+    fn from_rlp_item(item: &Item) -> Result<Self> {
+        Ok(BigUint::from_bytes_be(integer_bytes(item)?))
+    }
+}
+
+impl Encodable for Address {
+    fn to_rlp_item(&self) -> Item {
+        Item::Bytes(self.0.to_vec())
+    }
+}
+
+impl Decodable for Address {
+    fn from_rlp_item(item: &Item) -> Result<Self> {
+        let bytes = item.as_bytes()?;
+        Ok(Address(bytes.try_into().map_err(|_| {
+            DecodeError::InvalidLength {
+                expected: 20,
+                got: bytes.len(),
+            }
+        })?))
+    }
+}
+
+fn integer_bytes(item: &Item) -> Result<&[u8]> {
+    let bytes = item.as_bytes()?;
+    if bytes.first() == Some(&0) {
+        return Err(DecodeError::LeadingZeros);
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(s: &str) -> Item {
+        Item::Bytes(s.as_bytes().to_vec())
+    }
+
+    fn list(items: Vec<Item>) -> Item {
+        Item::List(items)
+    }
+
+    fn integer(n: u64) -> Item {
+        n.to_rlp_item()
+    }
+
+    // From https://github.com/ethereum/tests/blob/develop/RLPTests/rlptest.json
+    #[test]
+    fn test_valid_vectors() {
+        let vectors = vec![
+            (bytes(""), "80"),
+            (Item::Bytes(vec![0x00]), "00"),
+            (Item::Bytes(vec![0x01]), "01"),
+            (Item::Bytes(vec![0x7f]), "7f"),
+            (bytes("dog"), "83646f67"),
+            (
+                bytes("Lorem ipsum dolor sit amet, consectetur adipisicing eli"),
+                "b74c6f72656d20697073756d20646f6c6f722073697420616d65742c20636f6e7365637465747572206164697069736963696e6720656c69",
+            ),
+            (
+                bytes("Lorem ipsum dolor sit amet, consectetur adipisicing elit"),
+                "b8384c6f72656d20697073756d20646f6c6f722073697420616d65742c20636f6e7365637465747572206164697069736963696e6720656c6974",
+            ),
+            (integer(0), "80"),
+            (integer(1), "01"),
+            (integer(16), "10"),
+            (integer(79), "4f"),
+            (integer(127), "7f"),
+            (integer(128), "8180"),
+            (integer(1000), "8203e8"),
+            (integer(100000), "830186a0"),
+            (
+                "83729609699884896815286331701780722"
+                    .parse::<BigUint>()
+                    .unwrap()
+                    .to_rlp_item(),
+                "8f102030405060708090a0b0c0d0e0f2",
+            ),
+            (
+                "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+                    .parse::<BigUint>()
+                    .unwrap()
+                    .to_rlp_item(),
+                "a1010000000000000000000000000000000000000000000000000000000000000000",
+            ),
+            (list(vec![]), "c0"),
+            (
+                list(vec![bytes("dog"), bytes("god"), bytes("cat")]),
+                "cc83646f6783676f6483636174",
+            ),
+            (
+                list(vec![bytes("zw"), list(vec![integer(4)]), integer(1)]),
+                "c6827a77c10401",
+            ),
+            (
+                list(vec![list(vec![list(vec![]), list(vec![])]), list(vec![])]),
+                "c4c2c0c0c0",
+            ),
+            (
+                list(vec![
+                    list(vec![]),
+                    list(vec![list(vec![])]),
+                    list(vec![list(vec![]), list(vec![list(vec![])])]),
+                ]),
+                "c7c0c1c0c3c0c1c0",
+            ),
+            (
+                list(
+                    (1..=4)
+                        .map(|n| {
+                            list(vec![
+                                bytes(&format!("key{}", n)),
+                                bytes(&format!("val{}", n)),
+                            ])
+                        })
+                        .collect(),
+                ),
+                "ecca846b6579318476616c31ca846b6579328476616c32ca846b6579338476616c33ca846b6579348476616c34",
+            ),
+        ];
+        for (item, encoded) in vectors {
+            assert_eq!(hex::encode(item.encode()), encoded);
+            assert_eq!(decode_item(&hex::decode(encoded).unwrap()).unwrap(), item);
         }
-        0x81..=0xb7 => {
-            let length = input[0] as usize - 0x80 as usize;
-            output.push(input[1..length + 1].to_vec());
-            length
+    }
+
+    // From https://github.com/ethereum/tests/blob/develop/RLPTests/invalidRLPTest.json
+    #[test]
+    fn test_invalid_vectors() {
+        let vectors = vec![
+            ("", DecodeError::UnexpectedEnd),
+            ("bf0f000000000000021111", DecodeError::UnexpectedEnd),
+            ("f80180", DecodeError::NonCanonicalLength),
+            ("f80100", DecodeError::NonCanonicalLength),
+            ("8100", DecodeError::NonCanonicalSingleByte),
+            ("8101", DecodeError::NonCanonicalSingleByte),
+            ("817f", DecodeError::NonCanonicalSingleByte),
+            ("b90040", DecodeError::NonCanonicalLength),
+            ("f90040", DecodeError::NonCanonicalLength),
+            (
+                "b81000112233445566778899aabbccddeeff",
+                DecodeError::NonCanonicalLength,
+            ),
+            ("f803112233", DecodeError::NonCanonicalLength),
+            ("81", DecodeError::UnexpectedEnd),
+            (
+                "a0000102030405060708090a0b0c0d0e0f10",
+                DecodeError::UnexpectedEnd,
+            ),
+            ("c60102030405", DecodeError::UnexpectedEnd),
+            ("c50102030405060708", DecodeError::TrailingBytes),
+        ];
+        for (encoded, error) in vectors {
+            assert_eq!(decode_item(&hex::decode(encoded).unwrap()), Err(error));
         }
-        0xb8..=0xbf => {
-            let length_length = input[0] as usize - 0xb7 as usize;
-            let length = BigUint::from_bytes_be(&input[1..1 + length_length])
-                .to_usize()
-                .unwrap();
-            output.push(input[1 + length_length..1 + length_length + length].to_vec());
-            length + length_length
+    }
+
+    #[test]
+    fn test_nesting_depth() {
+        let nested = |depth| (0..depth).fold(list(vec![]), |item, _| list(vec![item]));
+        assert!(decode_item(&nested(MAX_DEPTH - 1).encode()).is_ok());
+        assert_eq!(
+            decode_item(&nested(MAX_DEPTH).encode()),
+            Err(DecodeError::TooDeep)
+        );
+        let mut encoded = vec![0xc0];
+        for _ in 0..10_000 {
+            encoded = [encode_length(encoded.len(), 0xc0), encoded].concat();
         }
+        assert_eq!(decode_item(&encoded), Err(DecodeError::TooDeep));
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(decode::<u64>(&encode(&1000u64)), Ok(1000));
+        assert_eq!(
+            decode::<u64>(&hex::decode("820001").unwrap()),
+            Err(DecodeError::LeadingZeros)
+        );
+        assert_eq!(
+            decode::<u64>(&hex::decode("89010000000000000000").unwrap()),
+            Err(DecodeError::IntegerOverflow)
+        );
+    }
 
-        header => {
-            panic!("unknown rlp header: {}", header)
+    #[derive(Debug, PartialEq)]
+    struct Withdrawl {
+        nonce: u64,
+        to: Address,
+        data: Vec<u8>,
+    }
+
+    impl Encodable for Withdrawl {
+        fn to_rlp_item(&self) -> Item {
+            Item::List(vec![
+                self.nonce.to_rlp_item(),
+                self.to.to_rlp_item(),
+                self.data.to_rlp_item(),
+            ])
         }
-    };
-    if length == input.len() - 1 {
-        output
-    } else {
-        decode_next(&input[length + 1..input.len()], output)
+    }
+
+    impl Decodable for Withdrawl {
+        fn from_rlp_item(item: &Item) -> Result<Self> {
+            let fields = item.as_fields(3)?;
+            Ok(Self {
+                nonce: Decodable::from_rlp_item(&fields[0])?,
+                to: Decodable::from_rlp_item(&fields[1])?,
+                data: Decodable::from_rlp_item(&fields[2])?,
+            })
+        }
+    }
+
+    #[test]
+    fn test_structs() {
+        let withdrawl = Withdrawl {
+            nonce: 1,
+            to: Address([1; 20]),
+            data: vec![0; 3],
+        };
+        assert_eq!(decode(&encode(&withdrawl)), Ok(withdrawl));
+        assert_eq!(
+            decode::<Withdrawl>(&encode(&vec![1u64, 2u64])),
+            Err(DecodeError::InvalidLength {
+                expected: 3,
+                got: 2
+            })
+        );
     }
 }
@@ -6,40 +6,49 @@ pub use lazy_static::lazy_static;
 pub use withdrawls::process_withdrawl;
 
 use crate::constants::POLL_INTERVAL;
-use crate::constants::{
-    BRIDGE_ADDRESS, ELLIPTICOIN_DECIMALS, PROVIDER, SAFE_ADDRESS, TRANSFER_TOPIC,
-};
+use crate::constants::{BRIDGE_ADDRESS, PROVIDER, SAFE_ADDRESS, TRANSFER_TOPIC};
 use async_std::{prelude::*, stream};
 use ellipticoin_contracts::{
     bridge::PolygonMessage,
-    token::tokens::{MATIC, TOKENS, TOKEN_DECIMALS},
+    token::tokens::{TokenMetadata, MATIC},
 };
 
 use async_std::sync::RwLock;
 use ellipticoin_peerchain_ethereum::json_rpc::Block;
 use ellipticoin_peerchain_ethereum::json_rpc::{encode_address_topic, encode_topic};
-use ellipticoin_types::Address;
+use ellipticoin_types::{Address, U256};
 use futures::stream::StreamExt;
 use futures::stream::TryStreamExt;
-use num_bigint::BigUint;
-use num_traits::{pow::pow, ToPrimitive};
 use serde_json::json;
-use std::convert::TryInto;
+use std::{
+    convert::{TryFrom, TryInto},
+    future::Future,
+};
 use surf;
 
 lazy_static! {
     pub static ref BLOCK_NUMBER: RwLock<u64> = RwLock::new(0);
 }
 
-pub fn event_stream(latest_block: u64) -> impl Stream<Item = (Vec<PolygonMessage>, u64)> {
+pub fn event_stream<F, Fut>(
+    latest_block: u64,
+    bridged_tokens: F,
+) -> impl Stream<Item = (Vec<PolygonMessage>, u64)>
+where
+    F: Fn() -> Fut + Copy,
+    Fut: Future<Output = Vec<TokenMetadata>>,
+{
     Box::pin(
         stream::once(())
             .chain(stream::interval(*POLL_INTERVAL))
-            .filter_map(move |_| async move { poll(latest_block).await }),
+            .filter_map(move |_| async move { poll(latest_block, bridged_tokens().await).await }),
     )
 }
 
-pub async fn poll(latest_block: u64) -> Option<(Vec<PolygonMessage>, u64)> {
+pub async fn poll(
+    latest_block: u64,
+    bridged_tokens: Vec<TokenMetadata>,
+) -> Option<(Vec<PolygonMessage>, u64)> {
     let mut block_number = BLOCK_NUMBER.write().await;
     let current_block = PROVIDER.get_current_block().await.unwrap();
     if current_block <= *block_number {
@@ -55,7 +64,9 @@ pub async fn poll(latest_block: u64) -> Option<(Vec<PolygonMessage>, u64)> {
 
         let messages = vec![
             get_matic_deposits(from_block, current_block).await.unwrap(),
-            get_token_deposits(from_block, current_block).await.unwrap(),
+            get_token_deposits(from_block, current_block, &bridged_tokens)
+                .await
+                .unwrap(),
         ]
         .concat();
         if messages.len() > 0 {
@@ -81,14 +92,15 @@ async fn get_matic_deposits(
             transaction.to == Some(SAFE_ADDRESS) || transaction.to == Some(BRIDGE_ADDRESS)
         })
         .filter(|transaction| transaction.from != SAFE_ADDRESS)
-        .map(|transaction| {
+        .filter_map(|transaction| {
+            let amount = U256::try_from(transaction.value).ok()?;
             println!(
                 "{} {} {}",
-                scale_down(transaction.value.clone(), 18u8),
+                amount,
                 hex::encode(MATIC.0),
                 hex::encode(transaction.from)
             );
-            PolygonMessage::Deposit(scale_down(transaction.value, 18u8), MATIC, transaction.from)
+            Some(PolygonMessage::Deposit(amount, MATIC, transaction.from))
         })
         .collect())
 }
@@ -96,12 +108,16 @@ async fn get_matic_deposits(
 async fn get_token_deposits(
     from_block: u64,
     to_block: u64,
+    bridged_tokens: &[TokenMetadata],
 ) -> Result<Vec<PolygonMessage>, surf::Error> {
     Ok(PROVIDER
         .get_logs(
             from_block,
             to_block,
-            TOKENS.to_vec(),
+            bridged_tokens
+                .iter()
+                .filter_map(|token| token.bridge_address)
+                .collect(),
             json!([
                 encode_topic(&TRANSFER_TOPIC),
                 null,
@@ -117,28 +133,19 @@ async fn get_token_deposits(
             let from = Address(log.topics[1][12..].try_into().unwrap());
             from != SAFE_ADDRESS
         })
-        .map(|log| {
-            let token = log.address;
+        .filter_map(|log| {
+            let token = bridged_tokens
+                .iter()
+                .find(|token| token.bridge_address == Some(log.address))?;
             let from = Address(log.topics[1][12..].try_into().unwrap());
-            let amount = BigUint::from_bytes_be(&log.data);
-            let scaled_amount = scale_down(amount, *TOKEN_DECIMALS.get(&token).unwrap());
+            let amount = U256::from_be_bytes(&log.data).ok()?;
             println!(
                 "{} {} {}",
-                scaled_amount,
-                hex::encode(token),
+                amount,
+                hex::encode(token.address),
                 hex::encode(from)
             );
-            PolygonMessage::Deposit(scaled_amount, token, from)
+            Some(PolygonMessage::Deposit(amount, token.address, from))
         })
         .collect())
 }
-
-fn scale_down(amount: BigUint, decimals: u8) -> u64 {
-    (amount
-        / BigUint::from(pow(
-            BigUint::from(10u32),
-            decimals as usize - *ELLIPTICOIN_DECIMALS,
-        )))
-    .to_u64()
-    .unwrap()
-}
@@ -5,19 +5,20 @@ use crate::{
 };
 use ellipticoin_contracts::bridge::PendingWithdrawl;
 use ellipticoin_peerchain_ethereum::{crypto::sign, rlp};
+use ellipticoin_types::U256;
 use ethereum_abi::Value;
 use num_bigint::BigUint;
 use num_traits::FromPrimitive;
 use std::collections::HashMap;
 
-pub async fn process_withdrawl(pending_withdrawl: &PendingWithdrawl) {
+pub async fn process_withdrawl(pending_withdrawl: &PendingWithdrawl, native_amount: U256) {
     let transaction_number = PROVIDER.get_transaction_count(&ADDRESS).await.unwrap();
     let transaction_data = vec![
         BRIDGE_ABI.functions[6].method_id().to_vec(),
         Value::encode(&vec![
             Value::Address(pending_withdrawl.token.0.into()),
             Value::Address(pending_withdrawl.to.0.into()),
-            Value::Uint(pending_withdrawl.amount.into(), 64),
+            Value::Uint(ethabi::ethereum_types::U256(native_amount.0), 256),
             Value::Uint(pending_withdrawl.id.into(), 64),
         ]),
     ]
@@ -34,12 +35,12 @@ pub async fn process_withdrawl(pending_withdrawl: &PendingWithdrawl) {
         vec![],
         vec![],
     ];
-    let signature = sign(&rlp::encode(transaction.clone()));
+    let signature = sign(&rlp::encode(&transaction));
     transaction[6] = encode_u64(CHAIN_ID * 2 + 35 + signature.v[0] as u64);
     transaction[7] = signature.r.to_vec();
     transaction[8] = signature.s.to_vec();
     PROVIDER
-        .send_raw_transaction(&rlp::encode(transaction))
+        .send_raw_transaction(&rlp::encode(&transaction))
         .await
         .unwrap();
 }
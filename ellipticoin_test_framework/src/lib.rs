@@ -17,10 +17,10 @@ pub mod test_backend;
 
 use constants::actors::ALICE;
 use ellipticoin_contracts::constants::{BASE_FACTOR, BASE_TOKEN_MANTISSA, EXCHANGE_RATE_MANTISSA};
-use ellipticoin_contracts::{Ellipticoin, Token};
+use ellipticoin_contracts::{Ellipticoin, Parameters, Token};
 use ellipticoin_types::{
     db::{Backend, Db},
-    Address,
+    Address, Amount,
 };
 use num_bigint::BigUint;
 use num_traits::pow;
@@ -52,10 +52,15 @@ pub fn generate_hash_onion(layers: usize, center: Vec<u8>) -> Vec<Vec<u8>> {
 
 pub fn new_db() -> Db<TestBackend> {
     let backend = TestBackend::new();
-    Db {
+    let mut db = Db {
         backend,
         transaction_state: Default::default(),
-    }
+        savepoints: Default::default(),
+    };
+    Parameters::migrate(&mut db);
+    Token::migrate(&mut db);
+    db.commit();
+    db
 }
 pub fn setup<B: Backend>(db: &mut Db<B>, balances: HashMap<Address, Vec<(u64, Address)>>) {
     Token::set_usd_exchange_rate(
@@ -67,7 +72,7 @@ pub fn setup<B: Backend>(db: &mut Db<B>, balances: HashMap<Address, Vec<(u64, Ad
     );
     for (address, balances) in balances.iter() {
         for (balance, token) in balances.iter() {
-            Token::set_balance(db, *address, *token, *balance);
+            Token::set_balance(db, *address, *token, Amount::from(*balance));
         }
     }
     db.commit()
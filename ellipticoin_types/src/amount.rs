@@ -0,0 +1,254 @@
+use crate::U256;
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+};
+
+pub const BASE_FACTOR: u64 = 1_000_000;
+pub const DECIMALS: u32 = 18;
+const LEGACY_DECIMALS: u32 = 6;
+
+#[derive(Copy, Serialize, Debug, Default, PartialEq, Clone, Eq, Hash, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct Amount(pub U256);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArithmeticError {
+    Overflow,
+    Underflow,
+    DivisionByZero,
+}
+
+impl Display for ArithmeticError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ArithmeticError::Overflow => write!(f, "arithmetic overflow"),
+            ArithmeticError::Underflow => write!(f, "arithmetic underflow"),
+            ArithmeticError::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+impl std::error::Error for ArithmeticError {}
+
+impl Amount {
+    pub const ZERO: Amount = Amount(U256::ZERO);
+    pub const MAX: Amount = Amount(U256::MAX);
+
+    // Amounts used to be stored as u64s with 6 decimals of precision.
+    pub fn from_legacy(amount: u64) -> Amount {
+        Amount(
+            U256::from(amount)
+                .checked_mul(U256::exp10(DECIMALS - LEGACY_DECIMALS))
+                .unwrap(),
+        )
+    }
+
+    pub fn checked_add(self, other: Amount) -> Result<Amount, ArithmeticError> {
+        self.0
+            .checked_add(other.0)
+            .map(Amount)
+            .ok_or(ArithmeticError::Overflow)
+    }
+
+    pub fn checked_sub(self, other: Amount) -> Result<Amount, ArithmeticError> {
+        self.0
+            .checked_sub(other.0)
+            .map(Amount)
+            .ok_or(ArithmeticError::Underflow)
+    }
+
+    pub fn checked_mul(self, n: u64) -> Result<Amount, ArithmeticError> {
+        self.0
+            .checked_mul(U256::from(n))
+            .map(Amount)
+            .ok_or(ArithmeticError::Overflow)
+    }
+
+    pub fn checked_div(self, n: u64) -> Result<Amount, ArithmeticError> {
+        self.0
+            .checked_div(U256::from(n))
+            .map(Amount)
+            .ok_or(ArithmeticError::DivisionByZero)
+    }
+
+    // Computes `self * numerator / denominator` without overflowing the
+    // intermediate product.
+    pub fn proportion_of(
+        self,
+        numerator: Amount,
+        denominator: Amount,
+    ) -> Result<Amount, ArithmeticError> {
+        if denominator.0.is_zero() {
+            return Err(ArithmeticError::DivisionByZero);
+        }
+        U256::try_from(
+            BigUint::from(self.0) * BigUint::from(numerator.0) / BigUint::from(denominator.0),
+        )
+        .map(Amount)
+        .map_err(|_| ArithmeticError::Overflow)
+    }
+
+    pub fn mul_fixed(self, other: Amount) -> Result<Amount, ArithmeticError> {
+        self.proportion_of(other, Amount::from(BASE_FACTOR))
+    }
+
+    pub fn div_fixed(self, other: Amount) -> Result<Amount, ArithmeticError> {
+        self.proportion_of(Amount::from(BASE_FACTOR), other)
+    }
+
+    pub fn percentage_of(self, total: Amount) -> Result<u64, ArithmeticError> {
+        u64::try_from(self.proportion_of(Amount::from(100), total)?.0)
+            .map_err(|_| ArithmeticError::Overflow)
+    }
+
+    pub fn sum<I: IntoIterator<Item = Amount>>(amounts: I) -> Result<Amount, ArithmeticError> {
+        amounts
+            .into_iter()
+            .try_fold(Amount::ZERO, |sum, amount| sum.checked_add(amount))
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        BigUint::from(self.0).to_f64().unwrap() / 10f64.powi(DECIMALS as i32)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredAmount {
+    Legacy(u64),
+    Current(U256),
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match StoredAmount::deserialize(deserializer)? {
+            StoredAmount::Legacy(amount) => Ok(Amount::from_legacy(amount)),
+            StoredAmount::Current(amount) => Ok(Amount(amount)),
+        }
+    }
+}
+
+// Fixed point values such as prices are scaled by `BASE_FACTOR` rather than by
+// the token precision so legacy values are read without being rescaled.
+pub fn deserialize_fixed<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Amount, D::Error> {
+    match StoredAmount::deserialize(deserializer)? {
+        StoredAmount::Legacy(amount) => Ok(Amount::from(amount)),
+        StoredAmount::Current(amount) => Ok(Amount(amount)),
+    }
+}
+
+impl From<u64> for Amount {
+    fn from(n: u64) -> Self {
+        Amount(U256::from(n))
+    }
+}
+
+impl From<U256> for Amount {
+    fn from(n: U256) -> Self {
+        Amount(n)
+    }
+}
+
+impl From<Amount> for U256 {
+    fn from(amount: Amount) -> Self {
+        amount.0
+    }
+}
+
+impl PartialEq<u64> for Amount {
+    fn eq(&self, other: &u64) -> bool {
+        self.0 == U256::from(*other)
+    }
+}
+
+impl Display for Amount {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_add_overflow() {
+        assert_eq!(
+            Amount(U256::MAX).checked_add(Amount::from(1)),
+            Err(ArithmeticError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_checked_sub_underflow() {
+        assert_eq!(
+            Amount::from(1)
+                .checked_sub(Amount::from(2))
+                .err()
+                .unwrap()
+                .to_string(),
+            "arithmetic underflow"
+        );
+    }
+
+    #[test]
+    fn test_mul_fixed() {
+        assert_eq!(
+            Amount::from(3 * BASE_FACTOR).mul_fixed(Amount::from(BASE_FACTOR / 2)),
+            Ok(Amount::from(3 * BASE_FACTOR / 2))
+        );
+        assert_eq!(
+            Amount(U256::MAX).mul_fixed(Amount::from(2 * BASE_FACTOR)),
+            Err(ArithmeticError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_proportion_of_zero() {
+        assert_eq!(
+            Amount::from(1).proportion_of(Amount::from(1), Amount::ZERO),
+            Err(ArithmeticError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn test_legacy_amounts_are_rescaled() {
+        let legacy_amount = serde_cbor::to_vec(&1_500_000u64).unwrap();
+        assert_eq!(
+            serde_cbor::from_slice::<Amount>(&legacy_amount).unwrap(),
+            Amount(
+                U256::exp10(18)
+                    .checked_mul(U256::from(3))
+                    .unwrap()
+                    .checked_div(U256::from(2))
+                    .unwrap()
+            )
+        );
+        let amount = Amount::from_legacy(1);
+        assert_eq!(
+            serde_cbor::from_slice::<Amount>(&serde_cbor::to_vec(&amount).unwrap()).unwrap(),
+            amount
+        );
+    }
+
+    #[test]
+    fn test_legacy_fixed_point_values_are_not_rescaled() {
+        let legacy_price = serde_cbor::to_vec(&1_500_000u64).unwrap();
+        assert_eq!(
+            deserialize_fixed(&mut serde_cbor::Deserializer::from_slice(&legacy_price)).unwrap(),
+            Amount::from(3 * BASE_FACTOR / 2)
+        );
+        let price = Amount::from(U256::exp10(30));
+        assert_eq!(
+            deserialize_fixed(&mut serde_cbor::Deserializer::from_slice(
+                &serde_cbor::to_vec(&price).unwrap()
+            ))
+            .unwrap(),
+            price
+        );
+    }
+}
@@ -4,6 +4,9 @@ use std::collections::HashMap;
 pub struct Db<B: Backend> {
     pub backend: B,
     pub transaction_state: HashMap<Vec<u8>, Vec<u8>>,
+    // Each savepoint records the value every key had in `transaction_state`
+    // before it was first written after the savepoint was created.
+    pub savepoints: Vec<HashMap<Vec<u8>, Option<Vec<u8>>>>,
 }
 
 impl<B: Backend> Db<B> {
@@ -33,10 +36,17 @@ impl<B: Backend> Db<B> {
     where
         Self: Sized,
     {
-        self.transaction_state.insert(
-            [namespace.to_le_bytes().to_vec(), key.into()].concat(),
-            serde_cbor::to_vec(&value).unwrap(),
-        );
+        let full_key = [namespace.to_le_bytes().to_vec(), key.into()].concat();
+        if let Some(savepoint) = self.savepoints.last_mut() {
+            if !savepoint.contains_key(&full_key) {
+                savepoint.insert(
+                    full_key.clone(),
+                    self.transaction_state.get(&full_key).cloned(),
+                );
+            }
+        }
+        self.transaction_state
+            .insert(full_key, serde_cbor::to_vec(&value).unwrap());
     }
 
     pub fn insert_raw(&mut self, key: &[u8], value: &[u8])
@@ -50,11 +60,41 @@ impl<B: Backend> Db<B> {
         for (key, value) in &self.transaction_state {
             Backend::insert(&mut self.backend, &key, &value);
         }
-        self.transaction_state.clear();
+        let keys = self.transaction_state.drain().map(|(key, _)| key).collect();
+        self.savepoints.clear();
+        Backend::on_commit(&mut self.backend, keys);
     }
 
     pub fn revert(&mut self) {
         self.transaction_state.clear();
+        self.savepoints.clear();
+    }
+
+    pub fn checkpoint(&mut self) -> usize {
+        self.savepoints.push(Default::default());
+        self.savepoints.len() - 1
+    }
+
+    pub fn rollback_to(&mut self, savepoint: usize) {
+        while self.savepoints.len() > savepoint {
+            for (key, value) in self.savepoints.pop().unwrap() {
+                match value {
+                    Some(value) => self.transaction_state.insert(key, value),
+                    None => self.transaction_state.remove(&key),
+                };
+            }
+        }
+    }
+
+    pub fn release(&mut self, savepoint: usize) {
+        while self.savepoints.len() > savepoint {
+            let released = self.savepoints.pop().unwrap();
+            if let Some(parent) = self.savepoints.last_mut() {
+                for (key, value) in released {
+                    parent.entry(key).or_insert(value);
+                }
+            }
+        }
     }
 
     pub fn flush(&mut self) {
@@ -72,4 +112,87 @@ pub trait Backend: Send + Sync {
     fn flush(&mut self)
     where
         Self: Sized;
+    // Called with the keys written by `Db::commit` so that changes can be
+    // pushed to subscribers.
+    fn on_commit(&mut self, _keys: Vec<Vec<u8>>)
+    where
+        Self: Sized,
+    {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct MemoryBackend(HashMap<Vec<u8>, Vec<u8>>, Vec<Vec<u8>>);
+
+    impl Backend for MemoryBackend {
+        fn get(&self, key: &[u8]) -> Vec<u8> {
+            self.0.get(key).cloned().unwrap_or_default()
+        }
+
+        fn insert(&mut self, key: &[u8], value: &[u8]) {
+            self.0.insert(key.to_vec(), value.to_vec());
+        }
+
+        fn flush(&mut self) {}
+
+        fn on_commit(&mut self, keys: Vec<Vec<u8>>) {
+            self.1 = keys;
+        }
+    }
+
+    fn new_db() -> Db<MemoryBackend> {
+        Db {
+            backend: Default::default(),
+            transaction_state: Default::default(),
+            savepoints: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_rollback_to() {
+        let mut db = new_db();
+        db.insert(0, "a", 1u64);
+        let savepoint = db.checkpoint();
+        db.insert(0, "a", 2u64);
+        db.insert(0, "b", 2u64);
+        db.rollback_to(savepoint);
+        assert_eq!(db.get::<_, u64>(0, "a"), 1);
+        assert_eq!(db.get::<_, u64>(0, "b"), 0);
+        db.commit();
+        assert_eq!(db.get::<_, u64>(0, "a"), 1);
+    }
+
+    #[test]
+    fn test_nested_savepoints() {
+        let mut db = new_db();
+        let outer = db.checkpoint();
+        db.insert(0, "a", 1u64);
+        let inner = db.checkpoint();
+        db.insert(0, "a", 2u64);
+        db.insert(0, "b", 2u64);
+        db.release(inner);
+        assert_eq!(db.get::<_, u64>(0, "a"), 2);
+        db.rollback_to(outer);
+        assert_eq!(db.get::<_, u64>(0, "a"), 0);
+        assert_eq!(db.get::<_, u64>(0, "b"), 0);
+        assert!(db.savepoints.is_empty());
+    }
+
+    #[test]
+    fn test_on_commit() {
+        let mut db = new_db();
+        db.insert(0, "a", 1u64);
+        let savepoint = db.checkpoint();
+        db.insert(0, "b", 1u64);
+        db.rollback_to(savepoint);
+        db.commit();
+        assert_eq!(
+            db.backend.1,
+            vec![[0u16.to_le_bytes().to_vec(), b"a".to_vec()].concat()]
+        );
+    }
 }
@@ -1,8 +1,12 @@
+pub mod amount;
 pub mod db;
 pub mod traits;
+pub mod u256;
 
+pub use amount::{Amount, ArithmeticError};
 pub use db::Db;
 use std::ops::{BitXor, Shr};
+pub use u256::U256;
 pub const ADDRESS_LENGTH: usize = 20;
 use hex;
 use num_bigint::{BigInt, BigUint};
@@ -24,6 +24,6 @@ impl ToKey for Address {
 }
 
 pub trait Run: core::fmt::Debug {
-    fn sender(&self) -> Result<Address>;
+    fn sender<B: Backend>(&self, db: &mut Db<B>) -> Result<Address>;
     fn run<B: Backend>(&self, db: &mut Db<B>) -> Result<u64>;
 }
@@ -0,0 +1,248 @@
+use num_bigint::BigUint;
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct U256(pub [u64; 4]);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct U256Overflow;
+
+impl Display for U256Overflow {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "value is greater than the max uint256")
+    }
+}
+
+impl std::error::Error for U256Overflow {}
+
+impl U256 {
+    pub const ZERO: U256 = U256([0; 4]);
+    pub const MAX: U256 = U256([u64::MAX; 4]);
+
+    pub const fn from_u64(n: u64) -> Self {
+        U256([n, 0, 0, 0])
+    }
+
+    pub fn exp10(exponent: u32) -> Self {
+        (0..exponent).fold(U256::from_u64(1), |n, _| {
+            n.checked_mul(U256::from_u64(10))
+                .expect("exponent too large")
+        })
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == U256::ZERO
+    }
+
+    pub fn checked_add(self, other: U256) -> Option<U256> {
+        let mut result = [0u64; 4];
+        let mut carry = false;
+        for i in 0..4 {
+            let (sum, overflow_1) = self.0[i].overflowing_add(other.0[i]);
+            let (sum, overflow_2) = sum.overflowing_add(carry as u64);
+            result[i] = sum;
+            carry = overflow_1 || overflow_2;
+        }
+        if carry {
+            None
+        } else {
+            Some(U256(result))
+        }
+    }
+
+    pub fn checked_sub(self, other: U256) -> Option<U256> {
+        let mut result = [0u64; 4];
+        let mut borrow = false;
+        for i in 0..4 {
+            let (difference, underflow_1) = self.0[i].overflowing_sub(other.0[i]);
+            let (difference, underflow_2) = difference.overflowing_sub(borrow as u64);
+            result[i] = difference;
+            borrow = underflow_1 || underflow_2;
+        }
+        if borrow {
+            None
+        } else {
+            Some(U256(result))
+        }
+    }
+
+    pub fn checked_mul(self, other: U256) -> Option<U256> {
+        U256::try_from(BigUint::from(self) * BigUint::from(other)).ok()
+    }
+
+    pub fn checked_div(self, other: U256) -> Option<U256> {
+        if other.is_zero() {
+            None
+        } else {
+            U256::try_from(BigUint::from(self) / BigUint::from(other)).ok()
+        }
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (i, limb) in self.0.iter().rev().enumerate() {
+            bytes[i * 8..(i + 1) * 8].copy_from_slice(&limb.to_be_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_be_bytes(bytes: &[u8]) -> Result<Self, U256Overflow> {
+        if bytes.len() > 32 {
+            return Err(U256Overflow);
+        }
+        let mut padded = [0u8; 32];
+        padded[32 - bytes.len()..].copy_from_slice(bytes);
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().rev().enumerate() {
+            let mut limb_bytes = [0u8; 8];
+            limb_bytes.copy_from_slice(&padded[i * 8..(i + 1) * 8]);
+            *limb = u64::from_be_bytes(limb_bytes);
+        }
+        Ok(U256(limbs))
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<u64> for U256 {
+    fn from(n: u64) -> Self {
+        U256::from_u64(n)
+    }
+}
+
+impl TryFrom<U256> for u64 {
+    type Error = U256Overflow;
+    fn try_from(n: U256) -> Result<Self, Self::Error> {
+        if n.0[1..].iter().all(|limb| *limb == 0) {
+            Ok(n.0[0])
+        } else {
+            Err(U256Overflow)
+        }
+    }
+}
+
+impl From<U256> for BigUint {
+    fn from(n: U256) -> Self {
+        BigUint::from_bytes_be(&n.to_be_bytes())
+    }
+}
+
+impl TryFrom<BigUint> for U256 {
+    type Error = U256Overflow;
+    fn try_from(n: BigUint) -> Result<Self, Self::Error> {
+        U256::from_be_bytes(&n.to_bytes_be())
+    }
+}
+
+impl FromStr for U256 {
+    type Err = U256Overflow;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        U256::try_from(BigUint::from_str(s).map_err(|_| U256Overflow)?)
+    }
+}
+
+impl Display for U256 {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", BigUint::from(*self))
+    }
+}
+
+impl Serialize for U256 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = self.to_be_bytes();
+        let leading_zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+        serializer.serialize_bytes(&bytes[leading_zeros..])
+    }
+}
+
+struct U256Visitor;
+
+impl<'de> Visitor<'de> for U256Visitor {
+    type Value = U256;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("an unsigned integer or up to 32 big endian bytes")
+    }
+
+    fn visit_u64<E: de::Error>(self, n: u64) -> Result<Self::Value, E> {
+        Ok(U256::from(n))
+    }
+
+    fn visit_i64<E: de::Error>(self, n: i64) -> Result<Self::Value, E> {
+        u64::try_from(n)
+            .map(U256::from)
+            .map_err(|_| E::custom(format!("expected positive integer got: {}", n)))
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        U256::from_be_bytes(bytes).map_err(E::custom)
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<Self::Value, E> {
+        self.visit_bytes(&bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for U256 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(U256Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_add_carries() {
+        assert_eq!(
+            U256::from(u64::MAX).checked_add(U256::from(1)),
+            Some(U256([0, 1, 0, 0]))
+        );
+        assert_eq!(U256::MAX.checked_add(U256::from(1)), None);
+    }
+
+    #[test]
+    fn test_checked_sub_borrows() {
+        assert_eq!(
+            U256([0, 1, 0, 0]).checked_sub(U256::from(1)),
+            Some(U256::from(u64::MAX))
+        );
+        assert_eq!(U256::ZERO.checked_sub(U256::from(1)), None);
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(U256([0, 1, 0, 0]) > U256::from(u64::MAX));
+    }
+
+    #[test]
+    fn test_serialization() {
+        let n = U256::exp10(30);
+        let bytes = serde_cbor::to_vec(&n).unwrap();
+        assert_eq!(serde_cbor::from_slice::<U256>(&bytes).unwrap(), n);
+        assert_eq!(
+            serde_cbor::from_slice::<U256>(&serde_cbor::to_vec(&42u64).unwrap()).unwrap(),
+            U256::from(42)
+        );
+    }
+}
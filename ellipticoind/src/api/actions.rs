@@ -0,0 +1,337 @@
+use crate::api::types::{Action, ActionArgument};
+use ellipticoin_contracts::{
+    bridge::{EthereumMessage, PolygonMessage},
+    parameters::Parameter,
+    Action as ContractAction,
+};
+use ellipticoin_types::{Address, Uint, U256};
+
+impl From<&ContractAction> for Action {
+    fn from(action: &ContractAction) -> Self {
+        match action {
+            ContractAction::AddLiquidity(amount, token) => action_with(
+                "addLiquidity",
+                vec![
+                    amount_argument("amount", amount),
+                    address_argument("token", token),
+                ],
+            ),
+            ContractAction::AddToken(token_metadata) => action_with(
+                "addToken",
+                vec![
+                    address_argument("address", &token_metadata.address),
+                    argument("name", &token_metadata.name),
+                    argument("symbol", &token_metadata.symbol),
+                    argument("decimals", token_metadata.decimals),
+                ]
+                .into_iter()
+                .chain(
+                    token_metadata
+                        .bridge_address
+                        .as_ref()
+                        .map(|bridge_address| address_argument("bridgeAddress", bridge_address)),
+                )
+                .collect(),
+            ),
+            ContractAction::Approve(spender, amount, token) => action_with(
+                "approve",
+                vec![
+                    address_argument("spender", spender),
+                    amount_argument("amount", amount),
+                    address_argument("token", token),
+                ],
+            ),
+            ContractAction::ApproveMultisigAction(account, id) => action_with(
+                "approveMultisigAction",
+                vec![
+                    address_argument("account", account),
+                    uint_argument("id", id),
+                ],
+            ),
+            ContractAction::Batch(actions) => Action {
+                name: "batch".to_string(),
+                arguments: vec![],
+                actions: actions.iter().map(Action::from).collect(),
+            },
+            ContractAction::Buy(input_amount, token, minimum_output_amount) => action_with(
+                "buy",
+                vec![
+                    amount_argument("inputAmount", input_amount),
+                    address_argument("token", token),
+                    amount_argument("minimumOutputAmount", minimum_output_amount),
+                ],
+            ),
+            ContractAction::CancelProposal(proposal_id) => action_with(
+                "cancelProposal",
+                vec![uint_argument("proposalId", proposal_id)],
+            ),
+            ContractAction::Compound(token, minimum_output_amount) => action_with(
+                "compound",
+                vec![
+                    address_argument("token", token),
+                    amount_argument("minimumOutputAmount", minimum_output_amount),
+                ],
+            ),
+            ContractAction::CreateMultisig(owners, threshold) => action_with(
+                "createMultisig",
+                owners
+                    .iter()
+                    .map(|owner| address_argument("owner", owner))
+                    .chain(vec![uint_argument("threshold", threshold)])
+                    .collect(),
+            ),
+            ContractAction::CreatePool(amount, token, starting_price) => action_with(
+                "createPool",
+                vec![
+                    amount_argument("amount", amount),
+                    address_argument("token", token),
+                    amount_argument("startingPrice", starting_price),
+                ],
+            ),
+            ContractAction::CreateWithdrawlRequest(amount, token) => action_with(
+                "createWithdrawlRequest",
+                vec![
+                    amount_argument("amount", amount),
+                    address_argument("token", token),
+                ],
+            ),
+            ContractAction::Delegate(delegate) => {
+                action_with("delegate", vec![address_argument("delegate", delegate)])
+            }
+            ContractAction::ExecuteProposal(proposal_id) => action_with(
+                "executeProposal",
+                vec![uint_argument("proposalId", proposal_id)],
+            ),
+            ContractAction::Harvest => action_with("harvest", vec![]),
+            ContractAction::ProcessEthereumMessages(messages, block_number) => Action {
+                name: "processEthereumMessages".to_string(),
+                arguments: vec![uint_argument("blockNumber", block_number)],
+                actions: messages
+                    .iter()
+                    .map(|message| match message {
+                        EthereumMessage::SetUSDExchangeRate(usd_exchange_rate) => action_with(
+                            "setUSDExchangeRate",
+                            vec![argument("usdExchangeRate", usd_exchange_rate)],
+                        ),
+                    })
+                    .collect(),
+            },
+            ContractAction::ProcessPolygonMessages(messages, block_number) => Action {
+                name: "processPolygonMessages".to_string(),
+                arguments: vec![uint_argument("blockNumber", block_number)],
+                actions: messages
+                    .iter()
+                    .map(|message| match message {
+                        PolygonMessage::Deposit(amount, token, recipient) => action_with(
+                            "deposit",
+                            vec![
+                                amount_argument("amount", amount),
+                                address_argument("token", token),
+                                address_argument("recipient", recipient),
+                            ],
+                        ),
+                        PolygonMessage::ProcessWithdrawl(id, transaction_hash) => action_with(
+                            "processWithdrawl",
+                            vec![
+                                argument("id", id),
+                                bytes_argument("transactionHash", transaction_hash),
+                            ],
+                        ),
+                    })
+                    .collect(),
+            },
+            ContractAction::Null => action_with("null", vec![]),
+            ContractAction::Pay(recipient, amount, token) => action_with(
+                "pay",
+                vec![
+                    address_argument("recipient", recipient),
+                    amount_argument("amount", amount),
+                    address_argument("token", token),
+                ],
+            ),
+            ContractAction::Sell(input_amount, token, minimum_output_amount) => action_with(
+                "sell",
+                vec![
+                    amount_argument("inputAmount", input_amount),
+                    address_argument("token", token),
+                    amount_argument("minimumOutputAmount", minimum_output_amount),
+                ],
+            ),
+            ContractAction::RemoveLiquidity(percentage, token) => action_with(
+                "removeLiquidity",
+                vec![
+                    uint_argument("percentage", percentage),
+                    address_argument("token", token),
+                ],
+            ),
+            ContractAction::RotateHashOnion(hash_onion_skin, layer_count) => action_with(
+                "rotateHashOnion",
+                vec![
+                    bytes_argument("hashOnionSkin", hash_onion_skin),
+                    uint_argument("layerCount", layer_count),
+                ],
+            ),
+            ContractAction::Seal(hash_onion_skin) => action_with(
+                "seal",
+                vec![bytes_argument("hashOnionSkin", hash_onion_skin)],
+            ),
+            ContractAction::SealAt(hash_onion_skin, timestamp) => action_with(
+                "sealAt",
+                vec![
+                    bytes_argument("hashOnionSkin", hash_onion_skin),
+                    uint_argument("timestamp", timestamp),
+                ],
+            ),
+            ContractAction::SetParameter(parameter) => {
+                action_with("setParameter", parameter_arguments(parameter))
+            }
+            ContractAction::StartMining(host, hash_onion_skin, layer_count) => action_with(
+                "startMining",
+                vec![
+                    argument("host", host),
+                    bytes_argument("hashOnionSkin", hash_onion_skin),
+                    uint_argument("layerCount", layer_count),
+                ],
+            ),
+            ContractAction::StopMining => action_with("stopMining", vec![]),
+            ContractAction::SubmitMultisigAction(account, action) => Action {
+                name: "submitMultisigAction".to_string(),
+                arguments: vec![address_argument("account", account)],
+                actions: vec![Action::from(action.as_ref())],
+            },
+            ContractAction::TransferFrom(owner, recipient, amount, token) => action_with(
+                "transferFrom",
+                vec![
+                    address_argument("owner", owner),
+                    address_argument("recipient", recipient),
+                    amount_argument("amount", amount),
+                    address_argument("token", token),
+                ],
+            ),
+            ContractAction::Undelegate => action_with("undelegate", vec![]),
+            ContractAction::Vote(proposal_id, choice) => action_with(
+                "vote",
+                vec![
+                    uint_argument("proposalId", proposal_id),
+                    argument("choice", format!("{:?}", choice)),
+                ],
+            ),
+            ContractAction::WithdrawBond => action_with("withdrawBond", vec![]),
+            ContractAction::WithdrawVote(proposal_id) => action_with(
+                "withdrawVote",
+                vec![uint_argument("proposalId", proposal_id)],
+            ),
+        }
+    }
+}
+
+fn parameter_arguments(parameter: &Parameter) -> Vec<ActionArgument> {
+    let (name, values) = match parameter {
+        Parameter::BlockTime(block_time) => ("blockTime", vec![argument("value", block_time)]),
+        Parameter::ExecutionDelay(execution_delay) => {
+            ("executionDelay", vec![argument("value", execution_delay)])
+        }
+        Parameter::Fee(fee) => ("fee", vec![argument("value", fee)]),
+        Parameter::FeeToken(fee_token) => ("feeToken", vec![address_argument("value", fee_token)]),
+        Parameter::GasPrice(gas_price) => ("gasPrice", vec![argument("value", gas_price)]),
+        Parameter::Guardian(guardian) => ("guardian", vec![address_argument("value", guardian)]),
+        Parameter::IssuanceSchedule(issuance_schedule) => (
+            "issuanceSchedule",
+            vec![
+                argument("initialBlockReward", issuance_schedule.initial_block_reward),
+                argument("blocksPerEra", issuance_schedule.blocks_per_era),
+                argument("numberOfEras", issuance_schedule.number_of_eras),
+                argument("lastIssuanceBlock", issuance_schedule.last_issuance_block),
+            ],
+        ),
+        Parameter::MinerBond(miner_bond) => ("minerBond", vec![argument("value", miner_bond)]),
+        Parameter::MinerTimeout(miner_timeout) => {
+            ("minerTimeout", vec![argument("value", miner_timeout)])
+        }
+        Parameter::MinerUnbondingPeriod(miner_unbonding_period) => (
+            "minerUnbondingPeriod",
+            vec![argument("value", miner_unbonding_period)],
+        ),
+        Parameter::MinimumProposalThreshold(minimum_proposal_threshold) => (
+            "minimumProposalThreshold",
+            vec![argument("value", minimum_proposal_threshold)],
+        ),
+        Parameter::MissedBlockPenalty(missed_block_penalty) => (
+            "missedBlockPenalty",
+            vec![argument("value", missed_block_penalty)],
+        ),
+        Parameter::RatificationThreshold(ratification_threshold) => (
+            "ratificationThreshold",
+            vec![argument("value", ratification_threshold)],
+        ),
+    };
+    [vec![argument("parameter", name)], values].concat()
+}
+
+fn action_with(name: &str, arguments: Vec<ActionArgument>) -> Action {
+    Action {
+        name: name.to_string(),
+        arguments,
+        actions: vec![],
+    }
+}
+
+fn argument<T: ToString>(name: &str, value: T) -> ActionArgument {
+    ActionArgument {
+        name: name.to_string(),
+        value: value.to_string(),
+    }
+}
+
+// Amounts are in the underlying units they were submitted in.
+fn amount_argument(name: &str, amount: &U256) -> ActionArgument {
+    argument(name, amount)
+}
+
+fn uint_argument(name: &str, value: &Uint) -> ActionArgument {
+    argument(name, u64::from(*value))
+}
+
+fn address_argument(name: &str, address: &Address) -> ActionArgument {
+    argument(name, format!("0x{}", address))
+}
+
+fn bytes_argument(name: &str, bytes: &[u8]) -> ActionArgument {
+    argument(name, base64::encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ellipticoin_test_framework::constants::{
+        actors::{ALICE, BOB},
+        tokens::APPLES,
+    };
+
+    #[test]
+    fn test_pay() {
+        assert_eq!(
+            Action::from(&ContractAction::Pay(BOB, U256::from(20), APPLES)),
+            Action {
+                name: "pay".to_string(),
+                arguments: vec![
+                    argument("recipient", format!("0x{}", BOB)),
+                    argument("amount", "20"),
+                    argument("token", format!("0x{}", APPLES)),
+                ],
+                actions: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_nested_actions() {
+        let action = Action::from(&ContractAction::SubmitMultisigAction(
+            ALICE,
+            Box::new(ContractAction::Batch(vec![ContractAction::Harvest])),
+        ));
+        assert_eq!(action.name, "submitMultisigAction");
+        assert_eq!(action.actions[0].name, "batch");
+        assert_eq!(action.actions[0].actions[0].name, "harvest");
+    }
+}
@@ -1,10 +1,15 @@
 extern crate juniper;
-use crate::api::{mutations::Mutations, query_root::QueryRoot};
-use juniper::{graphql_value, EmptySubscription, Variables};
+use crate::api::{mutations::Mutations, query_root::QueryRoot, subscription_root::Subscriptions};
+use async_std::task::spawn;
+use futures::{SinkExt, StreamExt};
+use juniper::{graphql_value, DefaultScalarValue, Variables};
+use juniper_graphql_ws::{ClientMessage, Connection, ConnectionConfig, Output};
 use serde_json::json;
-use std::fmt;
+use std::{fmt, sync::Arc};
 use tide::{http::StatusCode, Body, Request, Response};
+use tide_websockets::{Message, WebSocketConnection};
 
+#[derive(Clone)]
 pub struct Context {}
 impl juniper::Context for Context {}
 
@@ -33,7 +38,7 @@ impl fmt::Display for Error {
     }
 }
 
-pub type Schema = juniper::RootNode<'static, QueryRoot, Mutations, EmptySubscription<Context>>;
+pub type Schema = juniper::RootNode<'static, QueryRoot, Mutations, Subscriptions>;
 pub async fn handle_graphql(mut request: Request<()>) -> tide::Result {
     let ctx = Context {};
 
@@ -57,7 +62,7 @@ pub async fn handle_graphql(mut request: Request<()>) -> tide::Result {
     let (res, errors) = juniper::execute(
         &query,
         None,
-        &Schema::new(QueryRoot, Mutations, EmptySubscription::new()),
+        &Schema::new(QueryRoot, Mutations, Subscriptions),
         &variables,
         &ctx,
     )
@@ -74,3 +79,35 @@ pub async fn handle_graphql(mut request: Request<()>) -> tide::Result {
         }))?)
         .build())
 }
+
+// Subscriptions are served over WebSockets using the `graphql-ws` protocol.
+pub async fn handle_graphql_ws(
+    _request: Request<()>,
+    connection: WebSocketConnection,
+) -> tide::Result<()> {
+    let (mut sink, mut stream) = Connection::new(
+        Arc::new(Schema::new(QueryRoot, Mutations, Subscriptions)),
+        ConnectionConfig::new(Context {}),
+    )
+    .split();
+    let mut incoming = connection.clone();
+    spawn(async move {
+        while let Some(Ok(Message::Text(text))) = incoming.next().await {
+            let message: ClientMessage<DefaultScalarValue> = match serde_json::from_str(&text) {
+                Ok(message) => message,
+                Err(_) => break,
+            };
+            if sink.send(message.into()).await.is_err() {
+                break;
+            }
+        }
+        sink.close().await.ok();
+    });
+    while let Some(output) = stream.next().await {
+        match output {
+            Output::Message(message) => connection.send_json(&message).await?,
+            Output::Close { .. } => break,
+        }
+    }
+    Ok(())
+}
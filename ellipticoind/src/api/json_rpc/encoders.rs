@@ -1,10 +1,12 @@
 use super::helpers::left_pad;
+use crate::events::Log;
+use ellipticoin_types::U256;
 use num_bigint::BigUint;
-use num_traits::{pow, Zero};
+use num_traits::Zero;
 use serde_json::{json, Value};
 
-pub fn encode_token_amount(amount: u64) -> Value {
-    encode_amount(BigUint::from(amount) * BigUint::from(pow(BigUint::from(10u32), 12)))
+pub fn encode_token_amount(amount: U256) -> Value {
+    encode_amount(BigUint::from(amount))
 }
 
 pub fn encode_amount(amount: BigUint) -> Value {
@@ -18,8 +20,44 @@ pub fn encode_amount(amount: BigUint) -> Value {
     }
 }
 
+pub fn encode_block(block_number: u64, transaction_hashes: &[Vec<u8>]) -> Value {
+    json!(
+    {
+          "hash":encode_u64_as_hash(block_number),
+          "parentHash":encode_u64_as_hash(block_number - 1),
+          "number": encode_amount(block_number.into()),
+          "miner": encode_bytes(&[0; 20].to_vec()),
+          "extraData": encode_bytes(&vec![]),
+          "gasLimit": encode_amount(0u32.into()),
+          "gasUsed": encode_amount(0u32.into()),
+          "timestamp": encode_amount(0u32.into()),
+          "transactions": transaction_hashes
+              .iter()
+              .map(|transaction_hash| encode_bytes(transaction_hash))
+              .collect::<Vec<_>>(),
+      })
+}
+
+pub fn encode_log(log: &Log) -> Value {
+    json!({
+        "address": encode_bytes(&log.address.0),
+        "topics": log.topics.iter().map(|topic| encode_bytes(topic)).collect::<Vec<_>>(),
+        "data": encode_bytes(&log.data),
+        "blockNumber": encode_amount(log.transaction_id.into()),
+        "blockHash": encode_u64_as_hash(log.transaction_id),
+        "transactionHash": encode_bytes(&log.transaction_hash),
+        "transactionIndex": "0x0",
+        "logIndex": encode_amount(log.log_index.into()),
+        "removed": false,
+    })
+}
+
 pub fn encode_u64_as_hash(n: u64) -> Value {
-    encode_bytes(&left_pad(&n.to_be_bytes(), 32))
+    encode_bytes(&u64_to_hash(n))
+}
+
+pub fn u64_to_hash(n: u64) -> Vec<u8> {
+    left_pad(&n.to_be_bytes(), 32)
 }
 
 pub fn encode_bytes(bytes: &[u8]) -> Value {
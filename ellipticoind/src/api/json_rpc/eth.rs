@@ -1,33 +1,38 @@
 use super::{
-    encoders::{encode_amount, encode_bytes, encode_token_amount, encode_u64_as_hash},
-    errors::{Result, PARSE_ERROR},
+    encoders::{
+        encode_amount, encode_block, encode_bytes, encode_token_amount, encode_u64_as_hash,
+    },
+    errors::{Result, EXECUTION_REVERTED, PARSE_ERROR},
     parsers::{parse_address, parse_block_tag, parse_bytes, parse_signed_transaction, parse_u64},
 };
 use crate::api::json_rpc::errors::SMART_CONTACT_ERROR;
 use crate::{
     aquire_db_read_lock,
     config::OPTS,
-    constants::{DB, DEFAULT_GAS_LIMIT},
-    transaction,
+    constants::{DB, SYNCING},
+    indexer, transaction,
 };
-use ellipticoin_contracts::{
-    token::tokens::{TOKEN_METADATA, USD},
-    System,
+use ellipticoin_contracts::{token::tokens::USD, Action, Ellipticoin, Parameters, System, Token};
+use ellipticoin_peerchain_ethereum::abi::{decode_action, erc20_abi};
+use ellipticoin_types::{
+    db::{Backend, Db},
+    Address, Amount, U256,
 };
-use ellipticoin_peerchain_ethereum::abi::erc20_abi;
-use ellipticoin_types::Address;
-use num_bigint::BigUint;
-use num_traits::Zero;
+use num_bigint::{BigInt, BigUint, Sign};
 use serde_json::{json, Value};
 use std::convert::TryFrom;
 use std::convert::TryInto;
 
+const MAX_FEE_HISTORY_BLOCK_COUNT: u64 = 1024;
+
 pub fn chain_id(_params: &Value) -> Result<Value> {
     Ok(json!(encode_amount(OPTS.chain_id.into())))
 }
 
-pub fn estimate_gas(_params: &Value) -> Result<Value> {
-    Ok(json!(encode_amount(DEFAULT_GAS_LIMIT.into())))
+pub async fn estimate_gas(params: &Value) -> Result<Value> {
+    let (action, _balance_changes) = simulate(&params[0]).await?;
+    let mut db = aquire_db_read_lock!();
+    Ok(encode_amount(action.gas(&mut db).into()))
 }
 
 pub async fn block_number(_params: &Value) -> Result<Value> {
@@ -41,19 +46,12 @@ pub async fn get_block_by_number(params: &Value) -> Result<Value> {
     let mut db = aquire_db_read_lock!();
     let block_number = parse_block_tag(&params[0]).await?;
     if System::get_transaction_id_counter(&mut db) >= block_number {
-        Ok(json!(
-        {
-              "hash":encode_u64_as_hash(block_number),
-              "parentHash":encode_u64_as_hash(block_number - 1),
-              "number": encode_amount(block_number.into()),
-              "miner": encode_bytes(&[0; 20].to_vec()),
-              "extraData": encode_bytes(&vec![]),
-              "gasLimit": encode_amount(0u32.into()),
-              "gasUsed": encode_amount(0u32.into()),
-              "timestamp": encode_amount(0u32.into()),
-              "transactions": vec![encode_u64_as_hash(block_number)],
-          })
-        .into())
+        let transaction_hashes: Vec<Vec<u8>> =
+            indexer::get_transaction(&db.backend.guard.db, block_number)
+                .map(|transaction| transaction.hash)
+                .into_iter()
+                .collect();
+        Ok(encode_block(block_number, &transaction_hashes))
     } else {
         Ok(json!(null))
     }
@@ -67,6 +65,12 @@ pub async fn get_block_by_hash(params: &Value) -> Result<Value> {
     .await
 }
 
+pub async fn get_block_transaction_count_by_number(params: &Value) -> Result<Value> {
+    let block_number = parse_block_tag(&params[0]).await?;
+    let mut db = aquire_db_read_lock!();
+    Ok(block_transaction_count(&mut db, block_number))
+}
+
 pub async fn get_code(_params: &Value) -> Result<Value> {
     Ok(encode_bytes(&vec![]))
 }
@@ -83,13 +87,28 @@ pub async fn get_transaction_count(params: &Value) -> Result<Value> {
     ))
 }
 
-pub async fn get_transaction_receipt(params: &Value) -> Result<Value> {
+pub async fn get_transaction_by_hash(params: &Value) -> Result<Value> {
+    let hash = parse_bytes(&params[0])?;
     let mut db = aquire_db_read_lock!();
-    if System::get_transaction_id_counter(&mut db) >= parse_u64(&params[0])? {
+    match indexer::get_transaction_by_hash(&db.backend.guard.db, &hash) {
+        Some(transaction) => match transaction.result {
+            Ok(transaction_id) => encode_transaction(&mut db, transaction_id, &transaction),
+            Err(_) => Ok(json!(null)),
+        },
+        None => Ok(json!(null)),
+    }
+}
+
+pub async fn get_transaction_receipt(params: &Value) -> Result<Value> {
+    let hash = parse_bytes(&params[0])?;
+    let db = aquire_db_read_lock!();
+    if let Some(Ok(transaction_id)) = indexer::get_transaction_by_hash(&db.backend.guard.db, &hash)
+        .map(|transaction| transaction.result)
+    {
         Ok(json!(
         {
-          "blockHash":"0x0000000000000000000000000000000000000000000000000000000000000001",
-          "blockNumber":"0x1",
+          "blockHash": encode_u64_as_hash(transaction_id),
+          "blockNumber": encode_amount(transaction_id.into()),
           "cumulativeGasUsed": "0x0",
           "transactionIndex": "0x0",
           "effectiveGasPrice": "0x0",
@@ -106,7 +125,38 @@ pub async fn get_transaction_receipt(params: &Value) -> Result<Value> {
 }
 
 pub async fn gas_price(_params: &Value) -> Result<Value> {
-    Ok(encode_token_amount(Zero::zero()))
+    let mut db = aquire_db_read_lock!();
+    Ok(encode_token_amount(underlying_gas_price(&mut db)?.0))
+}
+
+// Fees are a fixed price per unit of gas so every block has the same base fee
+// and there are no priority fees to report.
+pub async fn fee_history(params: &Value) -> Result<Value> {
+    let block_count = match params[0].as_u64() {
+        Some(block_count) => block_count,
+        None => parse_u64(&params[0])?,
+    };
+    let newest_block = parse_block_tag(&params[1]).await?;
+    let block_count = block_count
+        .min(MAX_FEE_HISTORY_BLOCK_COUNT)
+        .min(newest_block + 1);
+    let mut db = aquire_db_read_lock!();
+    let gas_price = encode_token_amount(underlying_gas_price(&mut db)?.0);
+    let mut fee_history = json!({
+        "oldestBlock": encode_amount((newest_block + 1 - block_count).into()),
+        "baseFeePerGas": vec![gas_price; block_count as usize + 1],
+        "gasUsedRatio": vec![0; block_count as usize],
+    });
+    if let Some(reward_percentiles) = params[2].as_array() {
+        fee_history["reward"] = json!(vec![
+            vec![
+                encode_amount(0u32.into());
+                reward_percentiles.len()
+            ];
+            block_count as usize
+        ]);
+    }
+    Ok(fee_history)
 }
 
 pub async fn get_balance(params: &Value) -> Result<Value> {
@@ -118,44 +168,65 @@ pub async fn get_balance(params: &Value) -> Result<Value> {
         .map_err(|_| PARSE_ERROR.clone())?;
 
     let balance =
-        ellipticoin_contracts::Token::get_underlying_balance(&mut db, address.clone().into(), USD);
+        ellipticoin_contracts::Token::get_underlying_balance(&mut db, address.clone().into(), USD)
+            .map_err(|err| (SMART_CONTACT_ERROR.clone(), err.to_string()))?;
+
+    Ok(encode_token_amount(balance.0))
+}
+
+pub async fn syncing(_params: &Value) -> Result<Value> {
+    if !*SYNCING.lock().await {
+        return Ok(json!(false));
+    }
+    let mut db = aquire_db_read_lock!();
+    let current_block = encode_amount(System::get_block_number(&mut db).into());
+    Ok(json!({
+        "startingBlock": encode_amount(0u32.into()),
+        "currentBlock": current_block,
+        "highestBlock": current_block,
+    }))
+}
 
-    Ok(encode_token_amount(balance))
+pub fn accounts(_params: &Value) -> Result<Value> {
+    Ok(json!([]))
 }
 
 pub async fn send_raw_transaction(params: &Value) -> Result<Value> {
-    let signed_transaction = parse_signed_transaction(&params[0])?;
-    let transaction_id = transaction::dispatch(signed_transaction)
+    let signed_transaction = {
+        let mut db = aquire_db_read_lock!();
+        parse_signed_transaction(&mut db, &params[0])?
+    };
+    let hash = signed_transaction.hash();
+    transaction::dispatch(signed_transaction)
         .await
         .map_err(|err| (SMART_CONTACT_ERROR.clone(), err.to_string()))?;
-    Ok(encode_u64_as_hash(transaction_id))
+    Ok(encode_bytes(&hash))
 }
 
 pub async fn call(params: &Value) -> Result<Value> {
     let to = parse_address(params[0].get("to").ok_or(PARSE_ERROR.clone())?)?;
-    if TOKEN_METADATA.get(&to).is_some() {
-        let f = erc20_abi::ERC20_ABI
-            .decode_input_from_slice(&parse_bytes(
-                params[0].get("data").ok_or(PARSE_ERROR.clone())?,
-            )?)
-            .map_err(|_| PARSE_ERROR.clone())?;
+    let token_metadata = {
+        let mut db = aquire_db_read_lock!();
+        Token::get_token_metadata(&mut db, to)
+    };
+    if let Some(token_metadata) = token_metadata {
+        let f = match erc20_abi::ERC20_ABI.decode_input_from_slice(&parse_bytes(
+            params[0].get("data").ok_or(PARSE_ERROR.clone())?,
+        )?) {
+            Ok(f) => f,
+            Err(_) => return dry_run(params).await,
+        };
 
         match f.0.name.as_ref() {
+            "name" => Ok(json!(encode_bytes(&ethereum_abi::Value::encode(&[
+                ethereum_abi::Value::String(token_metadata.name)
+            ])))),
             "symbol" => Ok(json!(encode_bytes(&ethereum_abi::Value::encode(&[
-                ethereum_abi::Value::String(
-                    TOKEN_METADATA
-                        .get(&to)
-                        .ok_or(PARSE_ERROR.clone())?
-                        .symbol
-                        .to_string()
-                )
+                ethereum_abi::Value::String(token_metadata.symbol)
             ])))),
             "decimals" => Ok(json!(encode_bytes(&ethereum_abi::Value::encode(&[
                 ethereum_abi::Value::Uint(
-                    ethabi::ethereum_types::U256::try_from(
-                        TOKEN_METADATA.get(&to).ok_or(PARSE_ERROR.clone())?.decimals
-                    )
-                    .unwrap(),
+                    ethabi::ethereum_types::U256::try_from(token_metadata.decimals).unwrap(),
                     256
                 )
             ])))),
@@ -168,13 +239,278 @@ pub async fn call(params: &Value) -> Result<Value> {
                 };
 
                 let balance =
-                    ellipticoin_contracts::Token::get_underlying_balance(&mut db, address, to);
+                    ellipticoin_contracts::Token::get_underlying_balance(&mut db, address, to)
+                        .map_err(|err| (SMART_CONTACT_ERROR.clone(), err.to_string()))?;
+
+                Ok(encode_token_amount(
+                    Token::amount_to_native(&mut db, balance, to)
+                        .map_err(|err| (SMART_CONTACT_ERROR.clone(), err.to_string()))?,
+                ))
+            }
+            "allowance" => {
+                let mut db = aquire_db_read_lock!();
+                let (owner, spender) = match (&f.1[0].value, &f.1[1].value) {
+                    (
+                        ethereum_abi::Value::Address(owner),
+                        ethereum_abi::Value::Address(spender),
+                    ) => (Address(owner.0), Address(spender.0)),
+                    _ => return Err(PARSE_ERROR.clone()),
+                };
+                let allowance = Token::get_allowance(&mut db, owner, spender, to);
+                if allowance == Amount::MAX {
+                    return Ok(encode_token_amount(U256::MAX));
+                }
+                let underlying_allowance = Token::amount_to_underlying(&mut db, allowance, to)
+                    .map_err(|err| (SMART_CONTACT_ERROR.clone(), err.to_string()))?;
 
-                Ok(encode_token_amount(balance))
+                Ok(encode_token_amount(
+                    Token::amount_to_native(&mut db, underlying_allowance, to)
+                        .map_err(|err| (SMART_CONTACT_ERROR.clone(), err.to_string()))?,
+                ))
             }
-            _ => Ok(json!(null)),
+            "totalSupply" => {
+                let mut db = aquire_db_read_lock!();
+                let total_supply = Token::get_underlying_total_supply(&mut db, to)
+                    .map_err(|err| (SMART_CONTACT_ERROR.clone(), err.to_string()))?;
+
+                Ok(encode_token_amount(
+                    Token::amount_to_native(&mut db, total_supply, to)
+                        .map_err(|err| (SMART_CONTACT_ERROR.clone(), err.to_string()))?,
+                ))
+            }
+            _ => dry_run(params).await,
         }
     } else {
-        Ok(json!(null))
+        dry_run(params).await
+    }
+}
+
+// Returns the change in each of the sender's token balances as an ABI encoded
+// `(address,int256)[]`.
+async fn dry_run(params: &Value) -> Result<Value> {
+    let (_action, balance_changes) = simulate(&params[0]).await?;
+    Ok(encode_bytes(&ethabi::encode(&[ethabi::Token::Array(
+        balance_changes
+            .into_iter()
+            .map(|(token, change)| {
+                Ok(ethabi::Token::Tuple(vec![
+                    ethabi::Token::Address(token.0.into()),
+                    encode_int256(change)?,
+                ]))
+            })
+            .collect::<Result<_>>()?,
+    )])))
+}
+
+async fn simulate(call: &Value) -> Result<(Action, Vec<(Address, BigInt)>)> {
+    let from = match call.get("from") {
+        Some(from) => parse_address(from)?,
+        None => Default::default(),
+    };
+    let to = parse_bytes(call.get("to").ok_or(PARSE_ERROR.clone())?)?;
+    let value = parse_bytes(&call["value"])?;
+    let data = parse_bytes(&call["data"])?;
+    let mut db = aquire_db_read_lock!();
+    let action = decode_action(&mut db, &to, &value, &data).map_err(|_| PARSE_ERROR.clone())?;
+    let balance_changes = simulate_action(&mut db, from, &action)?;
+    Ok((action, balance_changes))
+}
+
+// Runs the action and returns the change in each of the sender's token
+// balances. Its writes are reverted instead of being committed.
+fn simulate_action<B: Backend>(
+    db: &mut Db<B>,
+    from: Address,
+    action: &Action,
+) -> Result<Vec<(Address, BigInt)>> {
+    let mut tokens = [Token::get_tokens(db), vec![Ellipticoin::address()]].concat();
+    tokens.sort();
+    tokens.dedup();
+    let balances_before = underlying_balances(db, from, &tokens)?;
+    let result = System::fee(db, from, action)
+        .and_then(|fee| System::charge_fee(db, from, fee))
+        .and_then(|_| action.run_in_savepoint(db, from))
+        .map_err(|err| {
+            (
+                EXECUTION_REVERTED.clone(),
+                format!("execution reverted: {}", err),
+            )
+        })
+        .and_then(|_| underlying_balances(db, from, &tokens));
+    db.revert();
+    let balances_after = result?;
+    Ok(tokens
+        .into_iter()
+        .zip(balances_before.into_iter().zip(balances_after))
+        .filter(|(_token, (before, after))| before != after)
+        .map(|(token, (before, after))| {
+            (
+                token,
+                BigInt::from(BigUint::from(after.0)) - BigInt::from(BigUint::from(before.0)),
+            )
+        })
+        .collect())
+}
+
+fn block_transaction_count<B: Backend>(db: &mut Db<B>, block_number: u64) -> Value {
+    match System::get_blocks(db).get(block_number as usize) {
+        Some(block) => encode_amount(block.transaction_count.into()),
+        None => json!(null),
+    }
+}
+
+// Each transaction is presented as its own block so its block hash and block
+// number are derived from its transaction id.
+fn encode_transaction<B: Backend>(
+    db: &mut Db<B>,
+    transaction_id: u64,
+    transaction: &indexer::IndexedTransaction,
+) -> Result<Value> {
+    let action = &transaction.action;
+    Ok(json!({
+        "hash": encode_bytes(&transaction.hash),
+        "blockHash": encode_u64_as_hash(transaction_id),
+        "blockNumber": encode_amount(transaction_id.into()),
+        "transactionIndex": "0x0",
+        "from": encode_bytes(&transaction.sender.0),
+        "to": transaction::to(action)
+            .ok()
+            .map(|to| encode_bytes(&to.0)),
+        "nonce": encode_amount(transaction.transaction_number.into()),
+        "gas": encode_amount(action.gas(db).into()),
+        "gasPrice": encode_token_amount(underlying_gas_price(db)?.0),
+        "input": encode_bytes(&transaction::data(db, action).unwrap_or_default()),
+        "value": encode_amount(BigUint::from_bytes_be(&transaction::value(action))),
+    }))
+}
+
+fn underlying_gas_price<B: Backend>(db: &mut Db<B>) -> Result<Amount> {
+    let fee_token = Parameters::get_fee_token(db);
+    let gas_price = Parameters::get_gas_price(db);
+    Token::amount_to_underlying(db, gas_price, fee_token)
+        .map_err(|err| (SMART_CONTACT_ERROR.clone(), err.to_string()))
+}
+
+fn underlying_balances<B: Backend>(
+    db: &mut Db<B>,
+    address: Address,
+    tokens: &[Address],
+) -> Result<Vec<Amount>> {
+    tokens
+        .iter()
+        .map(|token| {
+            Token::get_underlying_balance(db, address, *token)
+                .map_err(|err| (SMART_CONTACT_ERROR.clone(), err.to_string()))
+        })
+        .collect()
+}
+
+// Balance changes can be up to 257 bits wide so they're checked before they're
+// encoded as an int256.
+fn encode_int256(n: BigInt) -> Result<ethabi::Token> {
+    let bytes = n.to_signed_bytes_be();
+    if bytes.len() > 32 {
+        return Err((
+            SMART_CONTACT_ERROR.clone(),
+            format!("{} overflows an int256", n),
+        ));
+    }
+    let fill = if n.sign() == Sign::Minus { 0xff } else { 0 };
+    let mut padded = vec![fill; 32 - bytes.len()];
+    padded.extend(bytes);
+    Ok(ethabi::Token::Int(
+        ethabi::ethereum_types::U256::from_big_endian(&padded),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ellipticoin_test_framework::{
+        constants::{
+            actors::{ALICE, BOB},
+            tokens::APPLES,
+        },
+        new_db, setup,
+    };
+    use maplit::hashmap;
+    use num_traits::One;
+
+    #[test]
+    fn test_simulate_action_is_not_committed() {
+        let mut db = new_db();
+        setup(
+            &mut db,
+            hashmap! {
+                ALICE => vec![(100, APPLES)],
+            },
+        );
+        let action = Action::Pay(BOB, U256::from(20), APPLES);
+        let fee = System::fee(&mut db, ALICE, &action).unwrap();
+        Token::set_balance(&mut db, ALICE, USD, fee);
+        db.commit();
+        assert_eq!(
+            simulate_action(&mut db, ALICE, &action).unwrap(),
+            vec![(USD, -BigInt::from(BigUint::from(fee.0)))]
+        );
+        assert!(db.transaction_state.is_empty());
+        assert_eq!(Token::get_balance(&mut db, ALICE, APPLES), 100);
+        assert_eq!(Token::get_balance(&mut db, BOB, APPLES), 0);
+        assert_eq!(Token::get_balance(&mut db, ALICE, USD), fee);
+    }
+
+    #[test]
+    fn test_block_transaction_count() {
+        let mut db = new_db();
+        System::seal_block(&mut db, ALICE, vec![], None);
+        System::increment_transaction_id(&mut db);
+        System::increment_transaction_id(&mut db);
+        System::seal_block(&mut db, ALICE, vec![], None);
+        assert_eq!(block_transaction_count(&mut db, 0), json!("0x0"));
+        assert_eq!(block_transaction_count(&mut db, 1), json!("0x2"));
+        assert_eq!(block_transaction_count(&mut db, 2), json!(null));
+    }
+
+    #[test]
+    fn test_encode_transaction() {
+        let mut db = new_db();
+        setup(&mut db, hashmap! {});
+        let action = Action::Pay(BOB, U256::from(20), APPLES);
+        let transaction = indexer::IndexedTransaction {
+            id: 0,
+            hash: vec![1; 32],
+            sender: ALICE,
+            transaction_number: 3,
+            block_number: 1,
+            action: action.clone(),
+            result: Ok(7),
+        };
+        let encoded = encode_transaction(&mut db, 7, &transaction).unwrap();
+        assert_eq!(encoded["hash"], encode_bytes(&[1; 32]));
+        assert_eq!(encoded["blockHash"], encode_u64_as_hash(7));
+        assert_eq!(encoded["blockNumber"], json!("0x7"));
+        assert_eq!(encoded["from"], encode_bytes(&ALICE.0));
+        assert_eq!(encoded["to"], encode_bytes(&APPLES.0));
+        assert_eq!(encoded["nonce"], json!("0x3"));
+        assert_eq!(encoded["gas"], encode_amount(action.gas(&mut db).into()));
+        assert_eq!(
+            encoded["input"],
+            encode_bytes(&transaction::data(&mut db, &action).unwrap())
+        );
+        assert_eq!(encoded["value"], json!("0x0"));
+    }
+
+    #[test]
+    fn test_encode_int256() {
+        let max = (BigInt::one() << 255) - 1;
+        let min = -(BigInt::one() << 255);
+        assert_eq!(
+            encode_int256(BigInt::from(-1)).unwrap(),
+            ethabi::Token::Int(ethabi::ethereum_types::U256::max_value())
+        );
+        assert!(encode_int256(max.clone()).is_ok());
+        assert!(encode_int256(min.clone()).is_ok());
+        assert!(encode_int256(max + 1).is_err());
+        assert!(encode_int256(min - 1).is_err());
     }
 }
@@ -4,36 +4,73 @@ mod eth;
 mod helpers;
 mod net;
 mod parsers;
+mod subscriptions;
 mod transaction;
+mod web3;
 
-use errors::Error;
+use errors::{Error, INVALID_REQUEST};
+pub use parsers::parse_raw_transaction;
 use serde::Deserialize;
 use serde_json::{json, Value};
+pub use subscriptions::handle_websocket;
 use tide::Request;
 
 #[derive(Deserialize, Clone)]
 pub struct RequestJSON {
     id: Value,
     method: String,
+    #[serde(default)]
     params: Value,
 }
 
 pub async fn handle_json_rpc(mut request: Request<()>) -> tide::Result {
-    let request_json: RequestJSON = request.body_json().await?;
+    let body: Value = request.body_json().await?;
+    Ok(handle_body(body).await.into())
+}
+
+pub async fn handle_body(body: Value) -> Value {
+    match body {
+        Value::Array(requests) if requests.is_empty() => {
+            error_response(&Value::Null, INVALID_REQUEST.clone())
+        }
+        Value::Array(requests) => {
+            let mut responses = vec![];
+            for request in requests {
+                responses.push(handle_request(request).await);
+            }
+            json!(responses)
+        }
+        request => handle_request(request).await,
+    }
+}
+
+async fn handle_request(request: Value) -> Value {
+    let request_json: RequestJSON = match serde_json::from_value(request) {
+        Ok(request_json) => request_json,
+        Err(_) => return error_response(&Value::Null, INVALID_REQUEST.clone()),
+    };
     let result = match request_json.method.as_ref() {
+        "eth_accounts" => eth::accounts(&request_json.params),
         "eth_blockNumber" => eth::block_number(&request_json.params).await,
         "eth_call" => eth::call(&request_json.params).await,
         "eth_chainId" => eth::chain_id(&request_json.params),
-        "eth_estimateGas" => eth::estimate_gas(&request_json.params),
+        "eth_estimateGas" => eth::estimate_gas(&request_json.params).await,
+        "eth_feeHistory" => eth::fee_history(&request_json.params).await,
         "eth_gasPrice" => eth::gas_price(&request_json.params).await,
         "eth_getBalance" => eth::get_balance(&request_json.params).await,
         "eth_getBlockByHash" => eth::get_block_by_hash(&request_json.params).await,
         "eth_getBlockByNumber" => eth::get_block_by_number(&request_json.params).await,
+        "eth_getBlockTransactionCountByNumber" => {
+            eth::get_block_transaction_count_by_number(&request_json.params).await
+        }
         "eth_getCode" => eth::get_code(&request_json.params).await,
+        "eth_getTransactionByHash" => eth::get_transaction_by_hash(&request_json.params).await,
         "eth_getTransactionCount" => eth::get_transaction_count(&request_json.params).await,
         "eth_getTransactionReceipt" => eth::get_transaction_receipt(&request_json.params).await,
         "eth_sendRawTransaction" => eth::send_raw_transaction(&request_json.params).await,
+        "eth_syncing" => eth::syncing(&request_json.params).await,
         "net_version" => net::version(&request_json),
+        "web3_clientVersion" => web3::client_version(&request_json),
         _ => return unsupported_method(&request_json),
     };
 
@@ -43,21 +80,67 @@ pub async fn handle_json_rpc(mut request: Request<()>) -> tide::Result {
     }
 }
 
-pub fn unsupported_method(request_json: &RequestJSON) -> tide::Result {
-    Ok(
-json!({"jsonrpc": "2.0", "id": request_json.id, "error": {"code": -32601, "message": format!("Unsupported method [\"{}\"]", request_json.method)}}).into())
+pub fn unsupported_method(request_json: &RequestJSON) -> Value {
+    json!({"jsonrpc": "2.0", "id": request_json.id, "error": {"code": -32601, "message": format!("Unsupported method [\"{}\"]", request_json.method)}})
 }
 
-pub fn error_response(id: &Value, error: Error) -> tide::Result {
-    Ok(json!(
+pub fn error_response(id: &Value, error: Error) -> Value {
+    json!(
     {"jsonrpc":"2.0","id": id,"error":{"code":error.0,"message":error.1}}
         )
-    .into())
 }
 
-pub fn ok_response(id: &Value, result: &Value) -> tide::Result {
-    Ok(json!(
+pub fn ok_response(id: &Value, result: &Value) -> Value {
+    json!(
     {"jsonrpc": "2.0", "id": id, "result": result}
     )
-    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[async_std::test]
+    async fn test_empty_batch() {
+        assert_eq!(
+            handle_body(json!([])).await,
+            error_response(&Value::Null, INVALID_REQUEST.clone())
+        );
+    }
+
+    #[async_std::test]
+    async fn test_batch() {
+        let responses = handle_body(json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "eth_accounts"},
+            {"jsonrpc": "2.0", "id": "2", "method": "web3_clientVersion"},
+        ]))
+        .await;
+        assert_eq!(
+            responses,
+            json!([
+                ok_response(&json!(1), &json!([])),
+                ok_response(
+                    &json!("2"),
+                    &json!(format!("ellipticoind/{}", env!("CARGO_PKG_VERSION")))
+                ),
+            ])
+        );
+    }
+
+    #[async_std::test]
+    async fn test_batch_with_errors() {
+        let responses = handle_body(json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "eth_unknown"},
+            {"jsonrpc": "2.0", "id": 2},
+            {"jsonrpc": "2.0", "id": 3, "method": "net_version"},
+        ]))
+        .await;
+        assert_eq!(responses[0]["id"], json!(1));
+        assert_eq!(responses[0]["error"]["code"], json!(-32601));
+        assert_eq!(
+            responses[1],
+            error_response(&Value::Null, INVALID_REQUEST.clone())
+        );
+        assert_eq!(responses[2], ok_response(&json!(3), &json!("24")));
+    }
 }
@@ -1,12 +1,22 @@
-use super::{
-    errors,
-    errors::{Result, PARSE_ERROR},
+use super::errors::{Result, PARSE_ERROR};
+use crate::{
+    aquire_db_read_lock,
+    config::OPTS,
+    constants::{
+        DB, EIP_1559_TRANSACTION_TYPE, EIP_2930_TRANSACTION_TYPE, LEGACY_TRANSACTION_TYPE,
+    },
+    transaction::SignedTransaction,
 };
-use crate::{aquire_db_read_lock, constants::DB, transaction::SignedTransaction};
 use ellipticoin_contracts::{system::Transaction, System};
 use ellipticoin_peerchain_ethereum::signature::Signature;
-use ellipticoin_peerchain_ethereum::{abi::decode_action, rlp};
-use ellipticoin_types::Address;
+use ellipticoin_peerchain_ethereum::{
+    abi::decode_action,
+    rlp::{self, Decodable, Item},
+};
+use ellipticoin_types::{
+    db::{Backend, Db},
+    Address,
+};
 use num_bigint::BigUint;
 use num_traits::ToPrimitive;
 use serde_json::Value;
@@ -46,25 +56,158 @@ pub fn parse_bytes(value: &Value) -> Result<Vec<u8>> {
     Ok(hex::decode(padded_hex_string).or(Err(PARSE_ERROR.clone()))?)
 }
 
-pub fn parse_signed_transaction(value: &Value) -> Result<SignedTransaction> {
-    let transaction_attributes = rlp::decode(&parse_bytes(&value)?);
-    Ok(SignedTransaction(
-        Transaction {
-            action: decode_action(
-                &transaction_attributes[3],
-                &transaction_attributes[4],
-                &transaction_attributes[5],
-            )
-            .map_err(|_| errors::PARSE_ERROR.clone())?,
-            transaction_number: BigUint::from_bytes_le(&transaction_attributes[0])
-                .to_u64()
-                .ok_or(errors::PARSE_ERROR.clone())?,
+pub fn parse_signed_transaction<B: Backend>(
+    db: &mut Db<B>,
+    value: &Value,
+) -> Result<SignedTransaction> {
+    parse_raw_transaction(db, &parse_bytes(&value)?)
+}
+
+pub fn parse_raw_transaction<B: Backend>(
+    db: &mut Db<B>,
+    bytes: &[u8],
+) -> Result<SignedTransaction> {
+    match bytes.first() {
+        Some(&transaction_type)
+            if transaction_type == EIP_2930_TRANSACTION_TYPE
+                || transaction_type == EIP_1559_TRANSACTION_TYPE =>
+        {
+            parse_typed_transaction(db, OPTS.chain_id, transaction_type, &bytes[1..])
+        }
+        _ => parse_legacy_transaction(db, bytes),
+    }
+}
+
+fn parse_typed_transaction<B: Backend>(
+    db: &mut Db<B>,
+    expected_chain_id: u64,
+    transaction_type: u8,
+    bytes: &[u8],
+) -> Result<SignedTransaction> {
+    let item = rlp::decode_item(bytes).map_err(|_| PARSE_ERROR.clone())?;
+    let mut fields = item
+        .as_fields(if transaction_type == EIP_1559_TRANSACTION_TYPE {
+            12
+        } else {
+            11
+        })
+        .map_err(|_| PARSE_ERROR.clone())?
+        .to_vec();
+    let chain_id: u64 = decode_field(&fields.remove(0))?;
+    if chain_id != expected_chain_id {
+        return Err(PARSE_ERROR.clone());
+    }
+    let nonce = decode_field(&fields.remove(0))?;
+    let max_priority_fee_per_gas = if transaction_type == EIP_1559_TRANSACTION_TYPE {
+        decode_field(&fields.remove(0))?
+    } else {
+        vec![]
+    };
+    let gas_price: Vec<u8> = decode_field(&fields[0])?;
+    let gas_limit: Vec<u8> = decode_field(&fields[1])?;
+    let to: Vec<u8> = decode_field(&fields[2])?;
+    let value: Vec<u8> = decode_field(&fields[3])?;
+    let data: Vec<u8> = decode_field(&fields[4])?;
+    fields[5].as_list().map_err(|_| PARSE_ERROR.clone())?;
+    let y_parity: u64 = decode_field(&fields[6])?;
+    let r: Vec<u8> = decode_field(&fields[7])?;
+    let s: Vec<u8> = decode_field(&fields[8])?;
+    Ok(SignedTransaction::EthereumTyped {
+        transaction_type,
+        transaction: Transaction {
+            action: decode_action(db, &to, &value, &data).map_err(|_| PARSE_ERROR.clone())?,
+            transaction_number: nonce,
         },
-        Signature::from_v_r_s(
-            &transaction_attributes[6],
-            &transaction_attributes[7],
-            &transaction_attributes[8],
-        )
-        .map_err(|_| errors::PARSE_ERROR.clone())?,
-    ))
+        max_priority_fee_per_gas,
+        gas_price,
+        gas_limit,
+        access_list: fields[5].encode(),
+        signature: Signature::from_v_r_s(&[y_parity as u8], &r, &s)
+            .map_err(|_| PARSE_ERROR.clone())?,
+    })
+}
+
+fn parse_legacy_transaction<B: Backend>(db: &mut Db<B>, bytes: &[u8]) -> Result<SignedTransaction> {
+    let item = rlp::decode_item(bytes).map_err(|_| PARSE_ERROR.clone())?;
+    let fields = item.as_fields(9).map_err(|_| PARSE_ERROR.clone())?;
+    let gas_price: Vec<u8> = decode_field(&fields[1])?;
+    let gas_limit: Vec<u8> = decode_field(&fields[2])?;
+    let to: Vec<u8> = decode_field(&fields[3])?;
+    let value: Vec<u8> = decode_field(&fields[4])?;
+    let data: Vec<u8> = decode_field(&fields[5])?;
+    let v: Vec<u8> = decode_field(&fields[6])?;
+    let r: Vec<u8> = decode_field(&fields[7])?;
+    let s: Vec<u8> = decode_field(&fields[8])?;
+    Ok(SignedTransaction::EthereumTyped {
+        transaction_type: LEGACY_TRANSACTION_TYPE,
+        transaction: Transaction {
+            action: decode_action(db, &to, &value, &data).map_err(|_| PARSE_ERROR.clone())?,
+            transaction_number: decode_field(&fields[0])?,
+        },
+        max_priority_fee_per_gas: vec![],
+        gas_price,
+        gas_limit,
+        access_list: vec![],
+        signature: Signature::from_v_r_s(&v, &r, &s).map_err(|_| PARSE_ERROR.clone())?,
+    })
+}
+
+fn decode_field<D: Decodable>(item: &Item) -> Result<D> {
+    D::from_rlp_item(item).map_err(|_| PARSE_ERROR.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ellipticoin_contracts::{system::Action, token::tokens::USD};
+    use ellipticoin_test_framework::new_db;
+    use ellipticoin_types::U256;
+
+    // Sent from 0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266 on chain 24 with a
+    // nonce of 1. Each sends a value of 10^15 to 0x3535...3535.
+    const EIP_2930_TRANSACTION: &str = "01f86c1801843b9aca0082520894353535353535353535353535353535353535353587038d7ea4c6800080c080a0de5628105a8e6d20e6514228f57f83f41fa8ea19bf3e90df43229dd3e5a8a43fa071468f8cf380c058d45709db6755a9cbf828d7762bf34861e77a642a94080903";
+    const EIP_1559_TRANSACTION: &str = "02f8711801843b9aca00847735940082520894353535353535353535353535353535353535353587038d7ea4c6800080c001a0c8ca3c20f1d0d8180680b93bcfc34b21b38bf33ee88ce4644218bb2713dc3442a0783712d9a5befa0d9145c3006cd48daf22f9c7cb9d16e25a9e662e339b9f033b";
+    const SENDER: [u8; 20] = hex_literal::hex!("f39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+
+    fn parse(raw_transaction: &str) -> SignedTransaction {
+        let bytes = hex::decode(raw_transaction).unwrap();
+        parse_typed_transaction(&mut new_db(), 24, bytes[0], &bytes[1..]).unwrap()
+    }
+
+    #[test]
+    fn test_eip_2930_sender() {
+        let signed_transaction = parse(EIP_2930_TRANSACTION);
+        assert_eq!(
+            signed_transaction
+                .recover_address_on_chain(&mut new_db(), 24)
+                .unwrap(),
+            Address(SENDER)
+        );
+    }
+
+    #[test]
+    fn test_eip_1559_sender() {
+        let signed_transaction = parse(EIP_1559_TRANSACTION);
+        assert_eq!(signed_transaction.transaction().transaction_number, 1);
+        assert_eq!(
+            signed_transaction.transaction().action,
+            Action::Pay(
+                Address([0x35; 20]),
+                U256::from(1_000_000_000_000_000u64),
+                USD
+            )
+        );
+        assert_eq!(
+            signed_transaction
+                .recover_address_on_chain(&mut new_db(), 24)
+                .unwrap(),
+            Address(SENDER)
+        );
+    }
+
+    #[test]
+    fn test_typed_transaction_on_another_chain() {
+        let bytes = hex::decode(EIP_1559_TRANSACTION).unwrap();
+        assert!(parse_typed_transaction(&mut new_db(), 1, bytes[0], &bytes[1..]).is_err());
+    }
 }
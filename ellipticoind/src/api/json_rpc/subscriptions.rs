@@ -0,0 +1,238 @@
+use super::{
+    encoders::{encode_block, encode_bytes, encode_log},
+    error_response,
+    errors::{Result, INVALID_PARAMS, PARSE_ERROR},
+    handle_body, ok_response,
+    parsers::{parse_address, parse_bytes},
+};
+use crate::events::{self, Event, Log};
+use async_std::task::{spawn, JoinHandle};
+use ellipticoin_types::Address;
+use futures::stream::StreamExt;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tide::Request;
+use tide_websockets::{Message, WebSocketConnection};
+
+enum Subscription {
+    NewHeads,
+    Logs(LogFilter),
+    NewPendingTransactions,
+}
+
+struct LogFilter {
+    addresses: Vec<Address>,
+    topics: Vec<Option<Vec<Vec<u8>>>>,
+}
+
+// Requests other than `eth_subscribe` and `eth_unsubscribe` are handled the
+// same way as they are over HTTP.
+pub async fn handle_websocket(
+    _request: Request<()>,
+    mut connection: WebSocketConnection,
+) -> tide::Result<()> {
+    let mut subscriptions: HashMap<String, JoinHandle<()>> = HashMap::new();
+    while let Some(Ok(message)) = connection.next().await {
+        let response = match message {
+            Message::Text(text) => match serde_json::from_str(&text) {
+                Ok(body) => handle_message(&connection, &mut subscriptions, body).await,
+                Err(_) => error_response(&Value::Null, PARSE_ERROR.clone()),
+            },
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        if connection.send_json(&response).await.is_err() {
+            break;
+        }
+    }
+    for (_id, subscription) in subscriptions {
+        subscription.cancel().await;
+    }
+    Ok(())
+}
+
+async fn handle_message(
+    connection: &WebSocketConnection,
+    subscriptions: &mut HashMap<String, JoinHandle<()>>,
+    body: Value,
+) -> Value {
+    match body["method"].as_str() {
+        Some("eth_subscribe") => subscribe(connection, subscriptions, &body),
+        Some("eth_unsubscribe") => unsubscribe(subscriptions, &body).await,
+        _ => handle_body(body).await,
+    }
+}
+
+fn subscribe(
+    connection: &WebSocketConnection,
+    subscriptions: &mut HashMap<String, JoinHandle<()>>,
+    request: &Value,
+) -> Value {
+    let subscription = match parse_subscription(&request["params"]) {
+        Ok(subscription) => subscription,
+        Err(err) => return error_response(&request["id"], err),
+    };
+    let id = encode_bytes(&rand::random::<[u8; 16]>());
+    let subscription_id = id.clone();
+    let connection = connection.clone();
+    let events = events::subscribe();
+    subscriptions.insert(
+        id.as_str().unwrap().to_string(),
+        spawn(async move {
+            while let Ok(event) = events.recv().await {
+                if let Some(result) = subscription.notification(&event) {
+                    if connection
+                        .send_json(&json!({
+                            "jsonrpc": "2.0",
+                            "method": "eth_subscription",
+                            "params": {"subscription": subscription_id, "result": result},
+                        }))
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            }
+        }),
+    );
+    ok_response(&request["id"], &id)
+}
+
+async fn unsubscribe(
+    subscriptions: &mut HashMap<String, JoinHandle<()>>,
+    request: &Value,
+) -> Value {
+    let subscription = request["params"][0]
+        .as_str()
+        .and_then(|id| subscriptions.remove(id));
+    if let Some(subscription) = subscription {
+        subscription.cancel().await;
+        ok_response(&request["id"], &json!(true))
+    } else {
+        ok_response(&request["id"], &json!(false))
+    }
+}
+
+fn parse_subscription(params: &Value) -> Result<Subscription> {
+    match params[0].as_str() {
+        Some("newHeads") => Ok(Subscription::NewHeads),
+        Some("logs") => Ok(Subscription::Logs(parse_log_filter(&params[1])?)),
+        Some("newPendingTransactions") => Ok(Subscription::NewPendingTransactions),
+        _ => Err(INVALID_PARAMS.clone()),
+    }
+}
+
+fn parse_log_filter(value: &Value) -> Result<LogFilter> {
+    let addresses = match &value["address"] {
+        Value::Null => vec![],
+        Value::Array(addresses) => addresses.iter().map(parse_address).collect::<Result<_>>()?,
+        address => vec![parse_address(address)?],
+    };
+    let topics = match &value["topics"] {
+        Value::Null => vec![],
+        Value::Array(topics) => topics
+            .iter()
+            .map(|topic| match topic {
+                Value::Null => Ok(None),
+                Value::Array(topics) => {
+                    Ok(Some(topics.iter().map(parse_bytes).collect::<Result<_>>()?))
+                }
+                topic => Ok(Some(vec![parse_bytes(topic)?])),
+            })
+            .collect::<Result<_>>()?,
+        _ => return Err(INVALID_PARAMS.clone()),
+    };
+    Ok(LogFilter { addresses, topics })
+}
+
+impl Subscription {
+    fn notification(&self, event: &Event) -> Option<Value> {
+        match (self, event) {
+            (Subscription::NewHeads, Event::NewHead(block_number, transaction_hash)) => {
+                Some(encode_block(*block_number, &[transaction_hash.clone()]))
+            }
+            (Subscription::Logs(filter), Event::Log(log)) if filter.matches(log) => {
+                Some(encode_log(log))
+            }
+            (Subscription::NewPendingTransactions, Event::PendingTransaction(hash)) => {
+                Some(encode_bytes(hash))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl LogFilter {
+    // A `null` topic matches anything and a list of topics matches any of them.
+    fn matches(&self, log: &Log) -> bool {
+        (self.addresses.is_empty() || self.addresses.contains(&log.address))
+            && self
+                .topics
+                .iter()
+                .enumerate()
+                .all(|(index, topic)| match topic {
+                    None => true,
+                    Some(topics) => log
+                        .topics
+                        .get(index)
+                        .map_or(false, |log_topic| topics.contains(log_topic)),
+                })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(address: Address, topics: Vec<Vec<u8>>) -> Log {
+        Log {
+            transaction_id: 1,
+            transaction_hash: vec![1; 32],
+            log_index: 0,
+            address,
+            topics,
+            data: vec![],
+        }
+    }
+
+    #[test]
+    fn test_address_filter() {
+        let filter = LogFilter {
+            addresses: vec![Address([1; 20]), Address([2; 20])],
+            topics: vec![],
+        };
+        assert!(filter.matches(&log(Address([1; 20]), vec![])));
+        assert!(filter.matches(&log(Address([2; 20]), vec![])));
+        assert!(!filter.matches(&log(Address([3; 20]), vec![])));
+        assert!(LogFilter {
+            addresses: vec![],
+            topics: vec![],
+        }
+        .matches(&log(Address([3; 20]), vec![])));
+    }
+
+    #[test]
+    fn test_null_topic_filter() {
+        let filter = LogFilter {
+            addresses: vec![],
+            topics: vec![None, Some(vec![vec![2]])],
+        };
+        assert!(filter.matches(&log(Address([1; 20]), vec![vec![1], vec![2]])));
+        assert!(filter.matches(&log(Address([1; 20]), vec![vec![3], vec![2]])));
+        assert!(!filter.matches(&log(Address([1; 20]), vec![vec![1], vec![3]])));
+        assert!(!filter.matches(&log(Address([1; 20]), vec![vec![1]])));
+    }
+
+    #[test]
+    fn test_or_topic_filter() {
+        let filter = LogFilter {
+            addresses: vec![],
+            topics: vec![Some(vec![vec![1], vec![2]])],
+        };
+        assert!(filter.matches(&log(Address([1; 20]), vec![vec![1]])));
+        assert!(filter.matches(&log(Address([1; 20]), vec![vec![2], vec![3]])));
+        assert!(!filter.matches(&log(Address([1; 20]), vec![vec![3]])));
+        assert!(!filter.matches(&log(Address([1; 20]), vec![])));
+    }
+}
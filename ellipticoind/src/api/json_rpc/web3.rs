@@ -0,0 +1,7 @@
+use super::errors::Result;
+use super::RequestJSON;
+use serde_json::{json, Value};
+
+pub fn client_version(_request_json: &RequestJSON) -> Result<Value> {
+    Ok(json!(format!("ellipticoind/{}", env!("CARGO_PKG_VERSION"))))
+}
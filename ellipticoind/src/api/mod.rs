@@ -1,4 +1,5 @@
 pub use futures::stream::StreamExt;
+mod actions;
 pub mod app;
 mod blocks;
 pub mod graphql;
@@ -8,10 +9,12 @@ mod middleware;
 mod mutations;
 mod query_root;
 mod routes;
+mod subscription_root;
 mod types;
 use async_std::task::spawn;
 use std::net::SocketAddr;
 use tide::listener::Listener;
+use tide_websockets::WebSocket;
 pub mod views;
 pub struct API {
     pub app: tide::Server<()>,
@@ -29,11 +32,21 @@ impl API {
     }
 }
 
-pub async fn start(socket: SocketAddr) {
+pub async fn start(socket: SocketAddr, websocket_socket: SocketAddr) {
     let api = API::new();
     let mut listener = api.app.bind(socket).await.unwrap();
     for info in listener.info().iter() {
         println!("Server listening on {}", info);
     }
     spawn(async move { listener.accept().await.unwrap() });
+
+    let mut websocket_app = tide::new();
+    websocket_app
+        .at("/")
+        .get(WebSocket::new(json_rpc::handle_websocket));
+    let mut websocket_listener = websocket_app.bind(websocket_socket).await.unwrap();
+    for info in websocket_listener.info().iter() {
+        println!("WebSocket server listening on {}", info);
+    }
+    spawn(async move { websocket_listener.accept().await.unwrap() });
 }
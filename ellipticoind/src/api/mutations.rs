@@ -1,7 +1,15 @@
-use crate::api::{graphql::Context, types::Bytes};
-
+use crate::{
+    api::{
+        graphql::Context,
+        json_rpc::parse_raw_transaction,
+        types::{Bytes, PostedTransaction, TransactionReceipt},
+    },
+    aquire_db_read_lock,
+    constants::DB,
+    transaction::{self, SignedTransaction},
+};
+use async_std::task::spawn;
 use juniper::FieldError;
-use std::string::ToString;
 
 pub struct Mutations;
 
@@ -9,19 +17,51 @@ pub struct Mutations;
     Context = Context,
 )]
 impl Mutations {
-    // pub async fn post_transaction(
-    //     _context: &Context,
-    //     transaction: Bytes,
-    // ) -> Result<Option<String>, FieldError> {
-    //     let signed_transaction: ellipticoin_peerchain_ethereum::SignedTransaction =
-    //         serde_cbor::from_slice(&transaction.0).map_err(|err| Error(err.to_string()))?;
-    //     Ok(
-    //         transaction::dispatch(SignedTransaction::Ethereum(signed_transaction))
-    //             .await
-    //             .map_err(|err| err.to_string())
-    //             .err(),
-    //     )
-    // }
+    // Accepts either a raw Ethereum transaction or a CBOR encoded
+    // `SignedTransaction`. The hash is the one pending transactions are
+    // announced with and can be looked up with the `transaction` query or
+    // `eth_getTransactionByHash` once the transaction has run.
+    pub async fn post_transaction(
+        _context: &Context,
+        transaction: Bytes,
+        wait_for_receipt: Option<bool>,
+    ) -> Result<PostedTransaction, FieldError> {
+        let (signed_transaction, gas_used) = {
+            let mut db = aquire_db_read_lock!();
+            let signed_transaction: SignedTransaction = match serde_cbor::from_slice(&transaction.0)
+            {
+                Ok(signed_transaction) => signed_transaction,
+                Err(_) => parse_raw_transaction(&mut db, &transaction.0)
+                    .map_err(|(_code, message)| FieldError::from(message))?,
+            };
+            // Ethereum transactions are signed over the ABI encoding of their
+            // action so this also rejects actions which can't be encoded.
+            signed_transaction.sender(&mut db)?;
+            let gas_used = signed_transaction.transaction().action.gas(&mut db);
+            (signed_transaction, gas_used)
+        };
+        let hash = Bytes(signed_transaction.hash());
+        if wait_for_receipt.unwrap_or(false) {
+            let transaction_id = transaction::dispatch(signed_transaction).await?;
+            Ok(PostedTransaction {
+                hash,
+                receipt: Some(TransactionReceipt {
+                    transaction_id: transaction_id.into(),
+                    gas_used: gas_used.into(),
+                }),
+            })
+        } else {
+            spawn(async move {
+                if let Err(err) = transaction::dispatch(signed_transaction).await {
+                    println!("Failed to run posted transaction: {}", err);
+                }
+            });
+            Ok(PostedTransaction {
+                hash,
+                receipt: None,
+            })
+        }
+    }
 
     pub async fn post_block(_context: &Context, _block: Bytes) -> Result<bool, FieldError> {
         Ok(true)
@@ -5,16 +5,21 @@ use crate::{
     },
     aquire_db_read_lock,
     constants::DB,
+    indexer,
 };
 use anyhow::anyhow;
 use ellipticoin_contracts::{
-    governance, order_book, Ellipticoin, Governance, OrderBook, System, AMM,
+    governance, multisig, order_book, Ellipticoin, Governance, Multisig, OrderBook, System, AMM,
 };
 use ellipticoin_peerchain_ethereum::constants::BRIDGE_ADDRESS;
+use ellipticoin_types::db::{Backend, Db};
 
 use juniper::FieldError;
 use std::convert::{TryFrom, TryInto};
 
+const DEFAULT_PAGE_SIZE: i32 = 20;
+const MAX_PAGE_SIZE: i32 = 100;
+
 pub struct QueryRoot;
 #[juniper::graphql_object(
     Context = Context,
@@ -36,35 +41,7 @@ impl QueryRoot {
         address: Address,
     ) -> Result<Vec<Token>, FieldError> {
         let mut db = aquire_db_read_lock!();
-        Ok(tokens
-            .iter()
-            .cloned()
-            .map(|token| {
-                let balance = ellipticoin_contracts::Token::get_underlying_balance(
-                    &mut db,
-                    address.clone().into(),
-                    token.clone().into(),
-                );
-                let price = ellipticoin_contracts::Token::get_price(&mut db, token.clone().into());
-                let underlying_exchange_rate =
-                    ellipticoin_contracts::Token::get_underlying_exchange_rate(
-                        &mut db,
-                        token.clone().into(),
-                    );
-                let total_supply = ellipticoin_contracts::Token::get_underlying_total_supply(
-                    &mut db,
-                    token.clone().into(),
-                );
-
-                Token {
-                    address: token,
-                    balance: balance.into(),
-                    price: price.into(),
-                    underlying_exchange_rate: underlying_exchange_rate.into(),
-                    total_supply: total_supply.into(),
-                }
-            })
-            .collect())
+        get_tokens(&mut db, tokens, address)
     }
 
     async fn liquidity_tokens(
@@ -73,50 +50,12 @@ impl QueryRoot {
         address: Address,
     ) -> Result<Vec<LiquidityToken>, FieldError> {
         let mut db = aquire_db_read_lock!();
-        Ok(tokens
-            .iter()
-            .cloned()
-            .map(|token| {
-                let balance =
-                    AMM::get_balance(&mut db, address.clone().into(), token.clone().into());
-                let total_supply = AMM::get_total_supply(&mut db, token.clone().into());
-                let pool_supply_of_token =
-                    AMM::get_pool_supply_of_token(&mut db, token.clone().into());
-                let pool_supply_of_usd = AMM::get_pool_supply_of_usd(&mut db, token.clone().into());
-                let underlying_pool_supply_of_usd =
-                    AMM::get_underlying_pool_supply_of_usd(&mut db, token.clone().into());
-
-                LiquidityToken {
-                    token_address: token,
-                    balance: U64(balance),
-                    total_supply: U64(total_supply),
-                    pool_supply_of_token: U64(pool_supply_of_token),
-                    pool_supply_of_usd: U64(pool_supply_of_usd),
-                    underlying_pool_supply_of_usd: U64(underlying_pool_supply_of_usd),
-                }
-            })
-            .collect())
+        get_liquidity_tokens(&mut db, tokens, address)
     }
 
-    async fn orders(_context: &Context) -> Vec<Order> {
+    async fn orders(_context: &Context) -> Result<Vec<Order>, FieldError> {
         let mut db = aquire_db_read_lock!();
-        let orders = OrderBook::get_orders(&mut db);
-        orders
-            .iter()
-            .cloned()
-            .map(|order: order_book::Order| {
-                let price = order.get_underlying_price(&mut db);
-                let amount = order.get_underlying_amount(&mut db);
-
-                return Order {
-                    order_type: format!("{:?}", order.order_type),
-                    id: U64(order.id),
-                    token: order.token.into(),
-                    amount: U64(amount),
-                    price: U64(price),
-                };
-            })
-            .collect()
+        get_orders(&mut db)
     }
 
     async fn proposals(_context: &Context) -> Vec<Proposal> {
@@ -143,21 +82,93 @@ impl QueryRoot {
                     .map(|vote| Vote {
                         voter: vote.voter.into(),
                         choice: format!("{:?}", vote.choice),
-                        weight: U64(vote.weight),
+                        weight: Governance::get_vote_weight(&mut db, vote).unwrap().into(),
                     })
                     .collect(),
                 result: proposal.result.map(|result| format!("{:?}", result)),
+                execution: proposal
+                    .execution
+                    .map(|execution| format!("{:?}", execution)),
             })
             .collect()
     }
 
+    async fn multisig_accounts(_context: &Context, owner: Address) -> Vec<MultisigAccount> {
+        let mut db = aquire_db_read_lock!();
+        let accounts: Vec<multisig::Account> = Multisig::get_owned_accounts(&mut db, owner.into())
+            .into_iter()
+            .filter_map(|account| Multisig::get_account(&mut db, account))
+            .collect();
+        accounts
+            .into_iter()
+            .map(|account| MultisigAccount {
+                address: Address(account.address),
+                owners: account.owners.into_iter().map(Address::from).collect(),
+                threshold: U64(account.threshold),
+                submissions: Multisig::get_submissions(&mut db, account.address)
+                    .into_iter()
+                    .map(|submission| MultisigSubmission {
+                        id: U64(submission.id as u64),
+                        submitter: Address(submission.submitter),
+                        action: serde_cbor::to_vec(&submission.action).unwrap().into(),
+                        approvals: submission
+                            .approvals
+                            .into_iter()
+                            .map(Address::from)
+                            .collect(),
+                        executed: submission.execution.is_some(),
+                        error: submission.execution.and_then(Result::err),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    // Transactions are returned newest first. `after` is the `endCursor` of
+    // the previous page.
+    async fn transactions(
+        _context: &Context,
+        address: Address,
+        token: Option<Address>,
+        first: Option<i32>,
+        after: Option<U64>,
+    ) -> TransactionPage {
+        let first = first.unwrap_or(DEFAULT_PAGE_SIZE).max(0).min(MAX_PAGE_SIZE) as usize;
+        let db = aquire_db_read_lock!();
+        let mut transactions = indexer::get_transactions(
+            &db.backend.guard.db,
+            address.into(),
+            token.map(Into::into),
+            first + 1,
+            after.map(|after| after.0),
+        );
+        let has_next_page = transactions.len() > first;
+        transactions.truncate(first);
+        TransactionPage {
+            end_cursor: transactions.last().map(|transaction| U64(transaction.id)),
+            has_next_page,
+            transactions: transactions.into_iter().map(indexed_transaction).collect(),
+        }
+    }
+
+    // Looks up a transaction by the hash returned when it was posted.
+    async fn transaction(_context: &Context, hash: Bytes) -> Option<IndexedTransaction> {
+        let db = aquire_db_read_lock!();
+        indexer::get_transaction_by_hash(&db.backend.guard.db, &hash.0).map(indexed_transaction)
+    }
+
+    async fn delegate(_context: &Context, address: Address) -> Option<Address> {
+        let mut db = aquire_db_read_lock!();
+        Governance::get_delegate(&mut db, address.into()).map(Address::from)
+    }
+
     async fn block_number(_context: &Context) -> Option<U64> {
         let mut db = aquire_db_read_lock!();
         let block_number = System::get_block_number(&mut db);
         Some(block_number.into())
     }
 
-    async fn issuance_rewards(_context: &Context, address: Bytes) -> Result<U64, FieldError> {
+    async fn issuance_rewards(_context: &Context, address: Bytes) -> Result<BigUint, FieldError> {
         let mut db = aquire_db_read_lock!();
         let issuance_rewards = Ellipticoin::get_issuance_rewards(
             &mut db,
@@ -168,7 +179,7 @@ impl QueryRoot {
                     .map_err(|_| anyhow!("Invalid address"))?,
             ),
         );
-        Ok(U64(issuance_rewards))
+        Ok(issuance_rewards.into())
     }
 
     async fn next_transaction_number(
@@ -182,3 +193,99 @@ impl QueryRoot {
         Ok(U64(System::get_next_transaction_number(&mut db, address)))
     }
 }
+
+pub fn get_tokens<B: Backend>(
+    db: &mut Db<B>,
+    tokens: Vec<Address>,
+    address: Address,
+) -> Result<Vec<Token>, FieldError> {
+    tokens
+        .iter()
+        .cloned()
+        .map(|token| -> Result<Token, FieldError> {
+            let balance = ellipticoin_contracts::Token::get_underlying_balance(
+                db,
+                address.clone().into(),
+                token.clone().into(),
+            )?;
+            let price = ellipticoin_contracts::Token::get_price(db, token.clone().into())?;
+            let underlying_exchange_rate =
+                ellipticoin_contracts::Token::get_underlying_exchange_rate(
+                    db,
+                    token.clone().into(),
+                )?;
+            let total_supply = ellipticoin_contracts::Token::get_underlying_total_supply(
+                db,
+                token.clone().into(),
+            )?;
+
+            Ok(Token {
+                address: token,
+                balance: balance.into(),
+                price: price.into(),
+                underlying_exchange_rate: underlying_exchange_rate.into(),
+                total_supply: total_supply.into(),
+            })
+        })
+        .collect()
+}
+
+pub fn get_liquidity_tokens<B: Backend>(
+    db: &mut Db<B>,
+    tokens: Vec<Address>,
+    address: Address,
+) -> Result<Vec<LiquidityToken>, FieldError> {
+    tokens
+        .iter()
+        .cloned()
+        .map(|token| -> Result<LiquidityToken, FieldError> {
+            let balance = AMM::get_balance(db, address.clone().into(), token.clone().into());
+            let total_supply = AMM::get_total_supply(db, token.clone().into());
+            let pool_supply_of_token = AMM::get_pool_supply_of_token(db, token.clone().into());
+            let pool_supply_of_usd = AMM::get_pool_supply_of_usd(db, token.clone().into());
+            let underlying_pool_supply_of_usd =
+                AMM::get_underlying_pool_supply_of_usd(db, token.clone().into())?;
+
+            Ok(LiquidityToken {
+                token_address: token,
+                balance: balance.into(),
+                total_supply: total_supply.into(),
+                pool_supply_of_token: pool_supply_of_token.into(),
+                pool_supply_of_usd: pool_supply_of_usd.into(),
+                underlying_pool_supply_of_usd: underlying_pool_supply_of_usd.into(),
+            })
+        })
+        .collect()
+}
+
+pub fn get_orders<B: Backend>(db: &mut Db<B>) -> Result<Vec<Order>, FieldError> {
+    let orders = OrderBook::get_orders(db);
+    orders
+        .iter()
+        .cloned()
+        .map(|order: order_book::Order| -> Result<Order, FieldError> {
+            let price = order.get_underlying_price(db)?;
+            let amount = order.get_underlying_amount(db)?;
+
+            return Ok(Order {
+                order_type: format!("{:?}", order.order_type),
+                id: U64(order.id),
+                token: order.token.into(),
+                amount: amount.into(),
+                price: price.into(),
+            });
+        })
+        .collect()
+}
+
+fn indexed_transaction(transaction: indexer::IndexedTransaction) -> IndexedTransaction {
+    IndexedTransaction {
+        id: U64(transaction.id),
+        hash: Bytes(transaction.hash),
+        sender: Address(transaction.sender),
+        block_number: U64(transaction.block_number),
+        action: (&transaction.action).into(),
+        transaction_id: transaction.result.clone().ok().map(U64),
+        error: transaction.result.err(),
+    }
+}
@@ -1,6 +1,10 @@
-use super::{graphql::handle_graphql, json_rpc::handle_json_rpc};
+use super::{
+    graphql::{handle_graphql, handle_graphql_ws},
+    json_rpc::handle_json_rpc,
+};
 use crate::api::{blocks, API};
 use tide::sse;
+use tide_websockets::WebSocket;
 
 impl API {
     pub fn routes(&mut self) {
@@ -10,6 +14,9 @@ impl API {
             .unwrap();
         self.app.at("/").get(sse::endpoint(blocks::broadcaster));
         self.app.at("/").post(handle_json_rpc);
-        self.app.at("/graphql").post(handle_graphql);
+        self.app
+            .at("/graphql")
+            .post(handle_graphql)
+            .get(WebSocket::new(handle_graphql_ws).with_protocols(&["graphql-ws"]));
     }
 }
@@ -0,0 +1,126 @@
+use crate::{
+    api::{
+        graphql::Context,
+        query_root::{get_liquidity_tokens, get_orders, get_tokens},
+        types::*,
+    },
+    aquire_db_read_lock,
+    constants::DB,
+    db,
+};
+use ellipticoin_contracts::{
+    amm, contract::Contract, order_book, system, token, OrderBook, System, AMM,
+};
+use ellipticoin_types::traits::ToKey;
+use futures::{future, stream, Future, Stream, StreamExt};
+use juniper::FieldError;
+use std::pin::Pin;
+
+type FieldStream<T> = Pin<Box<dyn Stream<Item = Result<T, FieldError>> + Send>>;
+
+pub struct Subscriptions;
+
+#[juniper::graphql_subscription(
+    Context = Context,
+)]
+impl Subscriptions {
+    async fn tokens(
+        _context: &Context,
+        tokens: Vec<Address>,
+        address: Address,
+    ) -> FieldStream<Vec<Token>> {
+        let keys = tokens
+            .iter()
+            .map(|token| {
+                key::<ellipticoin_contracts::Token>(
+                    token::Namespace::Balance as u16,
+                    &[&address.0, &token.0],
+                )
+            })
+            .collect();
+        watch(keys, move || {
+            let tokens = tokens.clone();
+            let address = address.clone();
+            async move {
+                let mut db = aquire_db_read_lock!();
+                get_tokens(&mut db, tokens, address)
+            }
+        })
+    }
+
+    async fn liquidity_tokens(
+        _context: &Context,
+        tokens: Vec<Address>,
+        address: Address,
+    ) -> FieldStream<Vec<LiquidityToken>> {
+        let keys = tokens
+            .iter()
+            .flat_map(|token| {
+                vec![
+                    key::<AMM>(amm::Namespace::Balance as u16, &[&address.0, &token.0]),
+                    key::<AMM>(amm::Namespace::TotalSupply as u16, &[&token.0]),
+                    key::<AMM>(amm::Namespace::PoolSupplyOfUsd as u16, &[&token.0]),
+                    key::<AMM>(amm::Namespace::PoolSupplyOfToken as u16, &[&token.0]),
+                ]
+            })
+            .collect();
+        watch(keys, move || {
+            let tokens = tokens.clone();
+            let address = address.clone();
+            async move {
+                let mut db = aquire_db_read_lock!();
+                get_liquidity_tokens(&mut db, tokens, address)
+            }
+        })
+    }
+
+    async fn orders(_context: &Context) -> FieldStream<Vec<Order>> {
+        watch(
+            vec![key::<OrderBook>(order_book::Namespace::Orders as u16, &[])],
+            || async {
+                let mut db = aquire_db_read_lock!();
+                get_orders(&mut db)
+            },
+        )
+    }
+
+    async fn block_number(_context: &Context) -> FieldStream<U64> {
+        watch(
+            vec![key::<System>(system::Namespace::BlockNumber as u16, &[])],
+            || async {
+                let mut db = aquire_db_read_lock!();
+                Ok(System::get_block_number(&mut db).into())
+            },
+        )
+    }
+}
+
+// Emits the current value and then a new one every time a commit writes to
+// one of the keys.
+fn watch<T, F, Fut>(keys: Vec<Vec<u8>>, load: F) -> FieldStream<T>
+where
+    T: Send + 'static,
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<T, FieldError>> + Send + 'static,
+{
+    let commits = db::subscribe_to_commits().filter(move |committed_keys| {
+        future::ready(committed_keys.iter().any(|key| keys.contains(key)))
+    });
+    Box::pin(
+        stream::once(future::ready(()))
+            .chain(commits.map(|_| ()))
+            .then(move |_| load()),
+    )
+}
+
+fn key<C: Contract>(namespace: u16, key_parts: &[&dyn ToKey]) -> Vec<u8> {
+    [
+        (C::NAME as u16).to_le_bytes().to_vec(),
+        namespace.to_le_bytes().to_vec(),
+        key_parts
+            .iter()
+            .flat_map(|key_part| key_part.to_key())
+            .collect(),
+    ]
+    .concat()
+}
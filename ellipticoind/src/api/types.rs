@@ -26,10 +26,10 @@ impl BlockchainState {
 #[derive(Clone, Debug)]
 pub struct Token {
     pub address: Address,
-    pub price: U64,
-    pub underlying_exchange_rate: U64,
-    pub balance: U64,
-    pub total_supply: U64,
+    pub price: BigUint,
+    pub underlying_exchange_rate: BigUint,
+    pub balance: BigUint,
+    pub total_supply: BigUint,
 }
 
 #[juniper::graphql_object]
@@ -38,19 +38,19 @@ impl Token {
         self.address.clone()
     }
 
-    fn price(&self) -> U64 {
+    fn price(&self) -> BigUint {
         self.price.clone()
     }
 
-    fn underlying_exchange_rate(&self) -> U64 {
+    fn underlying_exchange_rate(&self) -> BigUint {
         self.underlying_exchange_rate.clone()
     }
 
-    fn balance(&self) -> U64 {
+    fn balance(&self) -> BigUint {
         self.balance.clone()
     }
 
-    fn total_supply(&self) -> U64 {
+    fn total_supply(&self) -> BigUint {
         self.total_supply.clone()
     }
 }
@@ -58,11 +58,11 @@ impl Token {
 #[derive(Clone, Debug)]
 pub struct LiquidityToken {
     pub token_address: Address,
-    pub balance: U64,
-    pub total_supply: U64,
-    pub pool_supply_of_token: U64,
-    pub pool_supply_of_usd: U64,
-    pub underlying_pool_supply_of_usd: U64,
+    pub balance: BigUint,
+    pub total_supply: BigUint,
+    pub pool_supply_of_token: BigUint,
+    pub pool_supply_of_usd: BigUint,
+    pub underlying_pool_supply_of_usd: BigUint,
 }
 
 #[juniper::graphql_object]
@@ -71,23 +71,23 @@ impl LiquidityToken {
         self.token_address.clone()
     }
 
-    fn balance(&self) -> U64 {
+    fn balance(&self) -> BigUint {
         self.balance.clone()
     }
 
-    fn total_supply(&self) -> U64 {
+    fn total_supply(&self) -> BigUint {
         self.total_supply.clone()
     }
 
-    fn pool_supply_of_token(&self) -> U64 {
+    fn pool_supply_of_token(&self) -> BigUint {
         self.pool_supply_of_token.clone()
     }
 
-    fn pool_supply_of_usd(&self) -> U64 {
+    fn pool_supply_of_usd(&self) -> BigUint {
         self.pool_supply_of_usd.clone()
     }
 
-    fn underlying_pool_supply_of_usd(&self) -> U64 {
+    fn underlying_pool_supply_of_usd(&self) -> BigUint {
         self.underlying_pool_supply_of_usd.clone()
     }
 }
@@ -96,9 +96,9 @@ impl LiquidityToken {
 pub struct Order {
     pub id: U64,
     pub order_type: String,
-    pub amount: U64,
+    pub amount: BigUint,
     pub token: Address,
-    pub price: U64,
+    pub price: BigUint,
 }
 
 #[juniper::graphql_object]
@@ -115,11 +115,11 @@ impl Order {
         self.token.clone()
     }
 
-    fn amount(&self) -> U64 {
+    fn amount(&self) -> BigUint {
         self.amount.clone()
     }
 
-    fn price(&self) -> U64 {
+    fn price(&self) -> BigUint {
         self.price.clone()
     }
 }
@@ -133,13 +133,14 @@ pub struct Proposal {
     pub actions: Vec<Bytes>,
     pub votes: Vec<Vote>,
     pub result: Option<String>,
+    pub execution: Option<String>,
 }
 
 #[derive(Clone, Debug)]
 pub struct Vote {
     pub choice: String,
     pub voter: Address,
-    pub weight: U64,
+    pub weight: BigUint,
 }
 
 #[juniper::graphql_object]
@@ -152,7 +153,7 @@ impl Vote {
         self.voter.clone()
     }
 
-    fn weight(&self) -> U64 {
+    fn weight(&self) -> BigUint {
         self.weight.clone()
     }
 }
@@ -189,7 +190,214 @@ impl Proposal {
     fn result(&self) -> Option<String> {
         self.result.clone()
     }
+
+    fn execution(&self) -> Option<String> {
+        self.execution.clone()
+    }
+}
+#[derive(Clone, Debug)]
+pub struct MultisigAccount {
+    pub address: Address,
+    pub owners: Vec<Address>,
+    pub threshold: U64,
+    pub submissions: Vec<MultisigSubmission>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MultisigSubmission {
+    pub id: U64,
+    pub submitter: Address,
+    pub action: Bytes,
+    pub approvals: Vec<Address>,
+    pub executed: bool,
+    pub error: Option<String>,
+}
+
+#[juniper::graphql_object]
+impl MultisigAccount {
+    fn address(&self) -> Address {
+        self.address.clone()
+    }
+
+    fn owners(&self) -> Vec<Address> {
+        self.owners.clone()
+    }
+
+    fn threshold(&self) -> U64 {
+        self.threshold.clone()
+    }
+
+    fn submissions(&self) -> Vec<MultisigSubmission> {
+        self.submissions.clone()
+    }
+}
+
+#[juniper::graphql_object]
+impl MultisigSubmission {
+    fn id(&self) -> U64 {
+        self.id.clone()
+    }
+
+    fn submitter(&self) -> Address {
+        self.submitter.clone()
+    }
+
+    fn action(&self) -> Bytes {
+        self.action.clone()
+    }
+
+    fn approvals(&self) -> Vec<Address> {
+        self.approvals.clone()
+    }
+
+    fn executed(&self) -> bool {
+        self.executed
+    }
+
+    fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PostedTransaction {
+    pub hash: Bytes,
+    pub receipt: Option<TransactionReceipt>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TransactionReceipt {
+    pub transaction_id: U64,
+    pub gas_used: U64,
+}
+
+#[juniper::graphql_object]
+impl PostedTransaction {
+    fn hash(&self) -> Bytes {
+        self.hash.clone()
+    }
+
+    fn receipt(&self) -> Option<TransactionReceipt> {
+        self.receipt.clone()
+    }
+}
+
+#[juniper::graphql_object]
+impl TransactionReceipt {
+    fn transaction_id(&self) -> U64 {
+        self.transaction_id.clone()
+    }
+
+    fn gas_used(&self) -> U64 {
+        self.gas_used.clone()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TransactionPage {
+    pub transactions: Vec<IndexedTransaction>,
+    pub end_cursor: Option<U64>,
+    pub has_next_page: bool,
 }
+
+#[derive(Clone, Debug)]
+pub struct IndexedTransaction {
+    pub id: U64,
+    pub hash: Bytes,
+    pub sender: Address,
+    pub block_number: U64,
+    pub action: Action,
+    pub transaction_id: Option<U64>,
+    pub error: Option<String>,
+}
+
+#[juniper::graphql_object]
+impl TransactionPage {
+    fn transactions(&self) -> Vec<IndexedTransaction> {
+        self.transactions.clone()
+    }
+
+    fn end_cursor(&self) -> Option<U64> {
+        self.end_cursor.clone()
+    }
+
+    fn has_next_page(&self) -> bool {
+        self.has_next_page
+    }
+}
+
+#[juniper::graphql_object]
+impl IndexedTransaction {
+    fn id(&self) -> U64 {
+        self.id.clone()
+    }
+
+    fn hash(&self) -> Bytes {
+        self.hash.clone()
+    }
+
+    fn sender(&self) -> Address {
+        self.sender.clone()
+    }
+
+    fn block_number(&self) -> U64 {
+        self.block_number.clone()
+    }
+
+    fn action(&self) -> Action {
+        self.action.clone()
+    }
+
+    fn transaction_id(&self) -> Option<U64> {
+        self.transaction_id.clone()
+    }
+
+    fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+// Arguments are formatted the same way as the scalars of their types. Actions
+// which contain other actions or bridge messages list them as `actions`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Action {
+    pub name: String,
+    pub arguments: Vec<ActionArgument>,
+    pub actions: Vec<Action>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActionArgument {
+    pub name: String,
+    pub value: String,
+}
+
+#[juniper::graphql_object]
+impl Action {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn arguments(&self) -> Vec<ActionArgument> {
+        self.arguments.clone()
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        self.actions.clone()
+    }
+}
+
+#[juniper::graphql_object]
+impl ActionArgument {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn value(&self) -> String {
+        self.value.clone()
+    }
+}
+
 pub struct RedeemRequest {
     pub id: U64,
     pub sender: Address,
@@ -338,6 +546,12 @@ impl From<u64> for U64 {
     }
 }
 
+impl From<ellipticoin_types::Amount> for BigUint {
+    fn from(amount: ellipticoin_types::Amount) -> Self {
+        BigUint(amount.0.into())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct U32(pub u32);
 impl From<U32> for String {
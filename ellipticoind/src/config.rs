@@ -48,6 +48,8 @@ pub struct Opts {
 pub enum SubCommand {
     #[clap(name = "generate-keypair")]
     GenerateKeypair,
+    #[clap(name = "rotate-hash-onion")]
+    RotateHashOnion,
 }
 
 lazy_static! {
@@ -102,6 +104,14 @@ pub fn socket() -> SocketAddr {
     (OPTS.bind_address.parse::<IpAddr>().unwrap(), OPTS.port).into()
 }
 
+pub fn websocket_socket() -> SocketAddr {
+    (
+        OPTS.bind_address.parse::<IpAddr>().unwrap(),
+        OPTS.websocket_port,
+    )
+        .into()
+}
+
 pub fn address() -> Address {
     eth_address(&SIGNER.verifying_key())
 }
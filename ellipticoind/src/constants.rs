@@ -1,4 +1,9 @@
-use crate::{db, db::sled_backend::SledBackend, transaction::SignedTransaction};
+use crate::{
+    db,
+    db::sled_backend::SledBackend,
+    events::{self, Event},
+    transaction::SignedTransaction,
+};
 use anyhow::Result;
 use async_std::{
     channel::{self, Receiver, Sender},
@@ -11,11 +16,15 @@ use once_cell::sync::OnceCell;
 use std::{
     fs::{File, OpenOptions},
     sync::Arc,
-    time::Duration,
 };
 
 pub const NETWORK_ID: u64 = 24;
 pub const DEFAULT_GAS_LIMIT: u64 = 21000;
+pub const LEGACY_TRANSACTION_TYPE: u8 = 0;
+pub const EIP_2930_TRANSACTION_TYPE: u8 = 1;
+pub const EIP_1559_TRANSACTION_TYPE: u8 = 2;
+pub const TRANSACTION_VERSION: u8 = 1;
+pub const MAX_TIMESTAMP_DRIFT: u64 = 15;
 pub static DB: OnceCell<RwLock<SledBackend>> = OnceCell::new();
 
 lazy_static! {
@@ -27,7 +36,6 @@ lazy_static! {
             .open("var/transactions.cbor")
             .unwrap()
     );
-    pub static ref BLOCK_TIME: Duration = Duration::from_secs(3600);
     pub static ref TRANSACTION_QUEUE_SIZE: usize = 1000;
     pub static ref TRANSACTION_QUEUE: (
         Sender<(SignedTransaction, oneshot::Sender<Result<u64>>)>,
@@ -36,12 +44,16 @@ lazy_static! {
     pub static ref SYNCING: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
     pub static ref WEB_SOCKET_BROADCASTER: BroadcastChannel<(u32, String)> =
         BroadcastChannel::new();
+    pub static ref EVENT_SUBSCRIBERS: std::sync::Mutex<Vec<Sender<Event>>> = Default::default();
+    pub static ref COMMIT_SUBSCRIBERS: std::sync::Mutex<Vec<Sender<Vec<Vec<u8>>>>> =
+        Default::default();
     pub static ref SLED_DB: sled::Db = sled::open("var/db").unwrap();
 }
 
 impl TRANSACTION_QUEUE {
     pub async fn push(&self, transaction: SignedTransaction) -> oneshot::Receiver<Result<u64>> {
         let (sender, receiver) = oneshot::channel();
+        events::broadcast_pending_transaction(&transaction);
         self.0.send((transaction, sender)).await.unwrap();
         receiver
     }
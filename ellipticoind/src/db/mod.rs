@@ -1,8 +1,16 @@
 pub mod memory_backend;
 pub mod sled_backend;
-use crate::{config::address, constants::DB};
-use async_std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
-use ellipticoin_contracts::{Bridge, Ellipticoin, Miner, System};
+use crate::{
+    config::address,
+    constants::{COMMIT_SUBSCRIBERS, DB},
+};
+use async_std::{
+    channel::{self, Receiver},
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+use ellipticoin_contracts::{
+    token::tokens::TokenMetadata, Bridge, Ellipticoin, Miner, Parameters, System, Token,
+};
 pub use memory_backend::MemoryBackend;
 pub use sled_backend::SledBackend;
 use std::path::Path;
@@ -45,6 +53,24 @@ impl ellipticoin_types::db::Backend for StoreLock<'_> {
     fn flush(&mut self) {
         self.guard.flush();
     }
+
+    fn on_commit(&mut self, keys: Vec<Vec<u8>>) {
+        if keys.is_empty() {
+            return;
+        }
+        COMMIT_SUBSCRIBERS
+            .lock()
+            .unwrap()
+            .retain(|subscriber| subscriber.try_send(keys.clone()).is_ok());
+    }
+}
+
+// Receives the keys written by every commit. Subscribers are dropped once their
+// receiver is.
+pub fn subscribe_to_commits() -> Receiver<Vec<Vec<u8>>> {
+    let (sender, receiver) = channel::unbounded();
+    COMMIT_SUBSCRIBERS.lock().unwrap().push(sender);
+    receiver
 }
 
 impl ellipticoin_types::db::Backend for ReadLock<'_> {
@@ -81,6 +107,7 @@ macro_rules! aquire_db_write_lock {
         ellipticoin_types::Db {
             backend: store_lock,
             transaction_state: Default::default(),
+            savepoints: Default::default(),
         }
     }};
 }
@@ -93,6 +120,7 @@ macro_rules! aquire_db_read_lock {
         ellipticoin_types::Db {
             backend: store_lock,
             transaction_state: Default::default(),
+            savepoints: Default::default(),
         }
     }};
 }
@@ -122,6 +150,16 @@ pub async fn get_ethereum_block_number() -> u64 {
     Bridge::get_ethereum_block_number(&mut db)
 }
 
+pub async fn get_block_time() -> u64 {
+    let mut db = aquire_db_read_lock!();
+    Parameters::get_block_time(&mut db)
+}
+
+pub async fn get_bridged_tokens() -> Vec<TokenMetadata> {
+    let mut db = aquire_db_read_lock!();
+    Token::get_bridged_tokens(&mut db)
+}
+
 pub async fn get_miners() -> Vec<Miner> {
     let mut db = aquire_db_read_lock!();
     Ellipticoin::get_miners(&mut db)
@@ -131,6 +169,16 @@ pub async fn get_current_miner() -> Option<Miner> {
     get_miners().await.first().cloned()
 }
 
+pub async fn get_last_sealed_at() -> Option<u64> {
+    let mut db = aquire_db_read_lock!();
+    Ellipticoin::get_last_sealed_at(&mut db)
+}
+
+pub async fn get_miner_timeout() -> u64 {
+    let mut db = aquire_db_read_lock!();
+    Parameters::get_miner_timeout(&mut db)
+}
+
 pub async fn flush() {
     let mut db = aquire_db_write_lock!();
     db.flush();
@@ -1,3 +1,4 @@
+use crate::indexer;
 use indicatif::ProgressBar;
 use serde_cbor::Deserializer;
 use sled::Batch;
@@ -13,6 +14,7 @@ impl SledBackend {
     pub fn new() -> Self {
         let db = sled::open("var/db").unwrap();
         db.clear().unwrap();
+        indexer::clear(&db);
         Self {
             state: Default::default(),
             db,
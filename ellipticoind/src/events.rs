@@ -0,0 +1,103 @@
+use crate::{constants::EVENT_SUBSCRIBERS, transaction::SignedTransaction};
+use async_std::channel::{self, Receiver};
+use ellipticoin_contracts::{Action, Token};
+use ellipticoin_peerchain_ethereum::{crypto::keccak256, helpers::left_pad};
+use ellipticoin_types::{
+    db::{Backend, Db},
+    Address, Amount, U256,
+};
+
+lazy_static! {
+    static ref TRANSFER_TOPIC: Vec<u8> = keccak256(b"Transfer(address,address,uint256)");
+}
+
+#[derive(Clone, Debug)]
+pub enum Event {
+    NewHead(u64, Vec<u8>),
+    PendingTransaction(Vec<u8>),
+    Log(Log),
+}
+
+#[derive(Clone, Debug)]
+pub struct Log {
+    pub transaction_id: u64,
+    pub transaction_hash: Vec<u8>,
+    pub log_index: u64,
+    pub address: Address,
+    pub topics: Vec<Vec<u8>>,
+    pub data: Vec<u8>,
+}
+
+// Receives every event broadcast after subscribing. Subscribers are dropped
+// once their receiver is so events aren't queued when nobody is listening.
+pub fn subscribe() -> Receiver<Event> {
+    let (sender, receiver) = channel::unbounded();
+    EVENT_SUBSCRIBERS.lock().unwrap().push(sender);
+    receiver
+}
+
+fn broadcast(event: Event) {
+    EVENT_SUBSCRIBERS
+        .lock()
+        .unwrap()
+        .retain(|subscriber| subscriber.try_send(event.clone()).is_ok());
+}
+
+pub fn broadcast_pending_transaction(transaction: &SignedTransaction) {
+    broadcast(Event::PendingTransaction(transaction.hash()));
+}
+
+// Each transaction is reported as its own block to match `eth_blockNumber`.
+pub fn broadcast_transaction(transaction_id: u64, transaction_hash: Vec<u8>, logs: Vec<Log>) {
+    for log in logs {
+        broadcast(Event::Log(log));
+    }
+    broadcast(Event::NewHead(transaction_id, transaction_hash));
+}
+
+// Token transfers are reported as ERC20 `Transfer` logs so wallets can track
+// balances.
+pub fn transfer_logs<B: Backend>(
+    db: &mut Db<B>,
+    transaction_id: u64,
+    transaction_hash: &[u8],
+    sender: Address,
+    action: &Action,
+) -> Vec<Log> {
+    transfers(sender, action)
+        .into_iter()
+        .filter_map(|(from, to, underlying_amount, token)| {
+            let amount = Token::amount_to_native(db, Amount(underlying_amount), token).ok()?;
+            Some((from, to, amount, token))
+        })
+        .enumerate()
+        .map(|(log_index, (from, to, amount, token))| Log {
+            transaction_id,
+            transaction_hash: transaction_hash.to_vec(),
+            log_index: log_index as u64,
+            address: token,
+            topics: vec![
+                TRANSFER_TOPIC.clone(),
+                left_pad(&from.0, 32),
+                left_pad(&to.0, 32),
+            ],
+            data: amount.to_be_bytes().to_vec(),
+        })
+        .collect()
+}
+
+fn transfers(sender: Address, action: &Action) -> Vec<(Address, Address, U256, Address)> {
+    match action {
+        Action::Batch(actions) => actions
+            .iter()
+            .flat_map(|action| transfers(sender, action))
+            .collect(),
+        Action::Pay(recipient, underlying_amount, token) => {
+            vec![(sender, *recipient, *underlying_amount, *token)]
+        }
+        Action::TransferFrom(owner, recipient, underlying_amount, token) => {
+            vec![(*owner, *recipient, *underlying_amount, *token)]
+        }
+        _ => vec![],
+    }
+}
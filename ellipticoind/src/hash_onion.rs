@@ -1,41 +1,249 @@
 use crate::{
-    config::{HASH_ONION_SIZE, PRIVATE_KEY},
-    db::get_hash_onion_layers_left,
+    config::{address, HASH_ONION_SIZE, PRIVATE_KEY},
+    db,
 };
 use async_std::sync::{Arc, Mutex};
+use ellipticoin_contracts::constants::HASH_ONION_ROTATION_LAYERS;
 use ellipticoin_peerchain_ethereum::crypto::sha256;
 use indicatif::ProgressBar;
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    path::Path,
+};
+
+const HASH_ONION_FILE: &str = "var/hash_onion.cbor";
+const NEXT_HASH_ONION_FILE: &str = "var/hash_onion.next.cbor";
+const LOW_LAYER_WARNING: u64 = HASH_ONION_ROTATION_LAYERS;
 
 lazy_static! {
-    pub static ref ONION: async_std::sync::Arc<Mutex<Vec<[u8; 32]>>> = Arc::new(Mutex::new(vec![]));
+    pub static ref ONION: Arc<Mutex<HashOnion>> = Arc::new(Mutex::new(Default::default()));
 }
 
-pub async fn generate() {
-    println!("Generating Hash Onion");
-    let hash_onion_layers_left = get_hash_onion_layers_left()
-        .await
-        .unwrap_or(*HASH_ONION_SIZE as u64);
-    let pb = ProgressBar::new(hash_onion_layers_left);
-    pb.set_style(
-        indicatif::ProgressStyle::default_bar()
-            .template("[{elapsed_precise}] [{bar}] {pos}/{len} ({percent}%)")
-            .progress_chars("=> "),
-    );
-    let mut onion = vec![*PRIVATE_KEY];
-    for layer in 0..(hash_onion_layers_left) {
-        if layer % 10000 == 0 {
-            pb.inc(10000);
-        }
-        onion.push(sha256(onion.last().unwrap().to_vec()));
+// Only every `checkpoint_interval`th layer is kept. The layers between two
+// checkpoints are recomputed one segment at a time as they're peeled.
+#[derive(Serialize, Deserialize, Default)]
+pub struct HashOnion {
+    size: u64,
+    checkpoint_interval: u64,
+    checkpoints: Vec<[u8; 32]>,
+    #[serde(skip)]
+    layers_left: u64,
+    #[serde(skip)]
+    segment: Vec<[u8; 32]>,
+}
+
+impl HashOnion {
+    pub fn new(center: [u8; 32], size: u64) -> Self {
+        let checkpoint_interval = ((size as f64).sqrt().ceil() as u64).max(1);
+        let pb = ProgressBar::new(size);
+        pb.set_style(
+            indicatif::ProgressStyle::default_bar()
+                .template("[{elapsed_precise}] [{bar}] {pos}/{len} ({percent}%)")
+                .progress_chars("=> "),
+        );
+        let mut checkpoints = vec![center];
+        let mut layer = center;
+        for index in 1..=size {
+            layer = sha256(layer.to_vec());
+            if index % checkpoint_interval == 0 {
+                checkpoints.push(layer);
+                pb.inc(checkpoint_interval);
+            }
+        }
+        pb.finish();
+        Self {
+            size,
+            checkpoint_interval,
+            checkpoints,
+            layers_left: size + 1,
+            segment: vec![],
+        }
+    }
+
+    fn load(path: &str) -> Option<Self> {
+        let file = File::open(path).ok()?;
+        let mut onion: Self = serde_cbor::from_reader(file).ok()?;
+        onion.layers_left = onion.size + 1;
+        Some(onion)
+    }
+
+    fn save(&self, path: &str) {
+        let file = File::create(path).unwrap();
+        serde_cbor::to_writer(file, self).unwrap();
+    }
+
+    pub fn layer(&self, index: u64) -> [u8; 32] {
+        let checkpoint = index / self.checkpoint_interval;
+        (0..index % self.checkpoint_interval)
+            .fold(self.checkpoints[checkpoint as usize], |layer, _| {
+                sha256(layer.to_vec())
+            })
+    }
+
+    pub fn peel(&mut self) -> Option<[u8; 32]> {
+        if self.layers_left == 0 {
+            return None;
+        }
+        self.layers_left -= 1;
+        if self.segment.is_empty() {
+            let index = self.layers_left;
+            let checkpoint = index / self.checkpoint_interval;
+            let mut layer = self.checkpoints[checkpoint as usize];
+            self.segment.push(layer);
+            for _ in 0..index % self.checkpoint_interval {
+                layer = sha256(layer.to_vec());
+                self.segment.push(layer);
+            }
+        }
+        self.segment.pop()
+    }
+
+    // Positions the onion so that the next layer peeled hashes to `skin`.
+    fn set_position(&mut self, skin: [u8; 32], layers_left: u64) -> bool {
+        if layers_left > self.size || self.layer(layers_left) != skin {
+            return false;
+        }
+        self.layers_left = layers_left;
+        self.segment = vec![];
+        true
     }
+}
+
+pub async fn load() {
+    let onion = HashOnion::load(HASH_ONION_FILE).unwrap_or_else(|| {
+        println!("Generating Hash Onion");
+        let onion = HashOnion::new(*PRIVATE_KEY, *HASH_ONION_SIZE as u64);
+        onion.save(HASH_ONION_FILE);
+        onion
+    });
     *ONION.lock().await = onion;
-    pb.finish();
 }
 
-pub async fn layers_left() -> usize {
-    ONION.lock().await.len()
+// The onion's position is derived from the miner's skin on chain. If the skin
+// belongs to a pending rotation that rotation has already been applied.
+pub async fn sync() {
+    let miner = match db::get_miners()
+        .await
+        .into_iter()
+        .find(|miner| miner.address == address())
+    {
+        Some(miner) => miner,
+        None => return,
+    };
+    let mut onion = ONION.lock().await;
+    if onion.set_position(miner.hash_onion_skin, miner.hash_onion_layers_left) {
+        return;
+    }
+    if let Some(mut next_onion) = HashOnion::load(NEXT_HASH_ONION_FILE) {
+        if next_onion.set_position(miner.hash_onion_skin, miner.hash_onion_layers_left) {
+            fs::rename(NEXT_HASH_ONION_FILE, HASH_ONION_FILE).unwrap();
+            *onion = next_onion;
+            return;
+        }
+    }
+    panic!("Hash onion doesn't match the miner's onion skin");
+}
+
+pub fn rotate() {
+    println!("Generating Hash Onion");
+    let mut center = [0u8; 32];
+    OsRng.fill_bytes(&mut center);
+    HashOnion::new(center, *HASH_ONION_SIZE as u64).save(NEXT_HASH_ONION_FILE);
+    println!("The new hash onion will be used the next time ellipticoind starts");
+}
+
+pub fn has_pending_rotation() -> bool {
+    Path::new(NEXT_HASH_ONION_FILE).exists()
+}
+
+pub async fn start_rotation() {
+    *ONION.lock().await = HashOnion::load(NEXT_HASH_ONION_FILE).unwrap();
+}
+
+pub fn finish_rotation() {
+    fs::rename(NEXT_HASH_ONION_FILE, HASH_ONION_FILE).unwrap();
+}
+
+pub async fn layers_left() -> u64 {
+    ONION.lock().await.layers_left
 }
 
 pub async fn peel() -> [u8; 32] {
-    ONION.lock().await.pop().expect("No onion layers left")
+    let mut onion = ONION.lock().await;
+    let layer = onion.peel().expect("No onion layers left");
+    if onion.layers_left == LOW_LAYER_WARNING {
+        println!("Hash onion is running low, run `ellipticoind rotate-hash-onion`");
+    }
+    layer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layers(center: [u8; 32], size: u64) -> Vec<[u8; 32]> {
+        let mut layers = vec![center];
+        for _ in 0..size {
+            layers.push(sha256(layers.last().unwrap().to_vec()));
+        }
+        layers
+    }
+
+    // A size of 10 has a checkpoint interval of 4 so the last segment is
+    // shorter than the others.
+    #[test]
+    fn test_layer() {
+        let onion = HashOnion::new([1; 32], 10);
+        assert_eq!(onion.checkpoint_interval, 4);
+        for (index, layer) in layers([1; 32], 10).iter().enumerate() {
+            assert_eq!(onion.layer(index as u64), *layer);
+        }
+    }
+
+    #[test]
+    fn test_peel() {
+        let mut onion = HashOnion::new([1; 32], 10);
+        for layer in layers([1; 32], 10).iter().rev() {
+            assert_eq!(onion.peel(), Some(*layer));
+        }
+        assert_eq!(onion.peel(), None);
+    }
+
+    #[test]
+    fn test_peel_with_size_a_multiple_of_the_checkpoint_interval() {
+        let mut onion = HashOnion::new([1; 32], 16);
+        assert_eq!(onion.checkpoint_interval, 4);
+        for layer in layers([1; 32], 16).iter().rev() {
+            assert_eq!(onion.peel(), Some(*layer));
+        }
+        assert_eq!(onion.peel(), None);
+    }
+
+    #[test]
+    fn test_set_position() {
+        let layers = layers([1; 32], 10);
+        let mut onion = HashOnion::new([1; 32], 10);
+        onion.peel();
+        assert!(onion.set_position(layers[8], 8));
+        for layer in layers[..8].iter().rev() {
+            assert_eq!(onion.peel(), Some(*layer));
+        }
+        assert_eq!(onion.peel(), None);
+
+        assert!(onion.set_position(layers[6], 6));
+        assert_eq!(onion.peel(), Some(layers[5]));
+        assert_eq!(onion.peel(), Some(layers[4]));
+        assert_eq!(onion.peel(), Some(layers[3]));
+    }
+
+    #[test]
+    fn test_set_position_with_invalid_skin() {
+        let layers = layers([1; 32], 10);
+        let mut onion = HashOnion::new([1; 32], 10);
+        assert!(!onion.set_position(layers[7], 8));
+        assert!(!onion.set_position(sha256(layers[10].to_vec()), 11));
+        assert_eq!(onion.peel(), Some(layers[10]));
+    }
 }
@@ -0,0 +1,412 @@
+use crate::{
+    db::StoreLock,
+    transaction::{LegacySignedTransaction, SignedTransaction},
+};
+use anyhow::Result;
+use ellipticoin_contracts::{
+    bridge::PolygonMessage, contract::Contract, token::tokens::USD, Action, Ellipticoin, System,
+};
+use ellipticoin_types::{Address, Db};
+use serde::{Deserialize, Serialize};
+use sled::IVec;
+use std::convert::TryInto;
+
+const TRANSACTIONS: &str = "indexed_transactions";
+const TRANSACTIONS_BY_ADDRESS: &str = "indexed_transactions_by_address";
+const TRANSACTIONS_BY_ADDRESS_AND_TOKEN: &str = "indexed_transactions_by_address_and_token";
+const TRANSACTIONS_BY_TRANSACTION_ID: &str = "indexed_transactions_by_transaction_id";
+const TRANSACTIONS_BY_HASH: &str = "indexed_transactions_by_hash";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IndexedTransaction {
+    pub id: u64,
+    pub hash: Vec<u8>,
+    pub sender: Address,
+    pub transaction_number: u64,
+    pub block_number: u64,
+    pub action: Action,
+    pub result: Result<u64, String>,
+}
+
+// The index lives in its own trees so that it isn't part of the state. It's
+// rebuilt when the transactions are replayed on start up.
+pub fn clear(db: &sled::Db) {
+    for tree in &[
+        TRANSACTIONS,
+        TRANSACTIONS_BY_ADDRESS,
+        TRANSACTIONS_BY_ADDRESS_AND_TOKEN,
+        TRANSACTIONS_BY_TRANSACTION_ID,
+        TRANSACTIONS_BY_HASH,
+    ] {
+        db.drop_tree(tree).unwrap();
+    }
+}
+
+pub fn index_transaction(
+    db: &mut Db<StoreLock>,
+    transaction: &SignedTransaction,
+    result: &Result<u64>,
+) {
+    let sender = match transaction.sender(db) {
+        Ok(sender) => sender,
+        Err(_) => return,
+    };
+    index(
+        db,
+        transaction.hash(),
+        sender,
+        transaction.relayer().ok(),
+        transaction.transaction().transaction_number,
+        transaction.transaction().action.clone(),
+        result,
+    );
+}
+
+// Legacy transactions are indexed with their upgraded actions.
+pub fn index_legacy_transaction(
+    db: &mut Db<StoreLock>,
+    transaction: &LegacySignedTransaction,
+    result: &Result<u64>,
+) {
+    let (sender, action) = match (transaction.sender(db), transaction.0.action.upgrade(db)) {
+        (Ok(sender), Ok(action)) => (sender, action),
+        _ => return,
+    };
+    index(
+        db,
+        transaction.hash(),
+        sender,
+        None,
+        transaction.0.transaction_number,
+        action,
+        result,
+    );
+}
+
+fn index(
+    db: &mut Db<StoreLock>,
+    hash: Vec<u8>,
+    sender: Address,
+    relayer: Option<Address>,
+    transaction_number: u64,
+    action: Action,
+    result: &Result<u64>,
+) {
+    let block_number = System::get_block_number(db);
+    insert(
+        &db.backend.guard.db,
+        hash,
+        sender,
+        relayer,
+        transaction_number,
+        block_number,
+        action,
+        result,
+    );
+}
+
+fn insert(
+    sled_db: &sled::Db,
+    hash: Vec<u8>,
+    sender: Address,
+    relayer: Option<Address>,
+    transaction_number: u64,
+    block_number: u64,
+    action: Action,
+    result: &Result<u64>,
+) {
+    let (mut addresses, tokens) = touched(sender, &action);
+    addresses.extend(relayer);
+    addresses.sort();
+    addresses.dedup();
+    let transactions = sled_db.open_tree(TRANSACTIONS).unwrap();
+    let id = transactions
+        .last()
+        .unwrap()
+        .map(|(key, _value)| u64::from_be_bytes(key.as_ref().try_into().unwrap()) + 1)
+        .unwrap_or(0);
+    transactions
+        .insert(
+            id.to_be_bytes(),
+            serde_cbor::to_vec(&IndexedTransaction {
+                id,
+                hash: hash.clone(),
+                sender,
+                transaction_number,
+                block_number,
+                action,
+                result: result
+                    .as_ref()
+                    .map(|transaction_id| *transaction_id)
+                    .map_err(|err| err.to_string()),
+            })
+            .unwrap(),
+        )
+        .unwrap();
+    if let Ok(transaction_id) = result {
+        sled_db
+            .open_tree(TRANSACTIONS_BY_TRANSACTION_ID)
+            .unwrap()
+            .insert(transaction_id.to_be_bytes(), &id.to_be_bytes()[..])
+            .unwrap();
+        sled_db
+            .open_tree(TRANSACTIONS_BY_HASH)
+            .unwrap()
+            .insert(hash, &id.to_be_bytes()[..])
+            .unwrap();
+    }
+    let transactions_by_address = sled_db.open_tree(TRANSACTIONS_BY_ADDRESS).unwrap();
+    let transactions_by_address_and_token = sled_db
+        .open_tree(TRANSACTIONS_BY_ADDRESS_AND_TOKEN)
+        .unwrap();
+    for address in &addresses {
+        transactions_by_address
+            .insert(
+                [&address.0[..], &id.to_be_bytes()].concat(),
+                IVec::default(),
+            )
+            .unwrap();
+        for token in &tokens {
+            transactions_by_address_and_token
+                .insert(
+                    [&address.0[..], &token.0[..], &id.to_be_bytes()].concat(),
+                    IVec::default(),
+                )
+                .unwrap();
+        }
+    }
+}
+
+// Returns the transactions which touched `address` newest first, starting
+// before the transaction with the id `after`.
+pub fn get_transactions(
+    db: &sled::Db,
+    address: Address,
+    token: Option<Address>,
+    first: usize,
+    after: Option<u64>,
+) -> Vec<IndexedTransaction> {
+    let (index, prefix) = match token {
+        Some(token) => (
+            db.open_tree(TRANSACTIONS_BY_ADDRESS_AND_TOKEN).unwrap(),
+            [address.0.to_vec(), token.0.to_vec()].concat(),
+        ),
+        None => (
+            db.open_tree(TRANSACTIONS_BY_ADDRESS).unwrap(),
+            address.0.to_vec(),
+        ),
+    };
+    let end = [
+        prefix.clone(),
+        after.unwrap_or(u64::MAX).to_be_bytes().to_vec(),
+    ]
+    .concat();
+    let transactions = db.open_tree(TRANSACTIONS).unwrap();
+    index
+        .range(prefix..end)
+        .rev()
+        .take(first)
+        .map(|entry| {
+            let (key, _value) = entry.unwrap();
+            let id = &key[key.len() - 8..];
+            serde_cbor::from_slice(&transactions.get(id).unwrap().unwrap()).unwrap()
+        })
+        .collect()
+}
+
+// Returns the transaction which was assigned `transaction_id` when it ran.
+pub fn get_transaction(db: &sled::Db, transaction_id: u64) -> Option<IndexedTransaction> {
+    get_indexed(
+        db,
+        TRANSACTIONS_BY_TRANSACTION_ID,
+        &transaction_id.to_be_bytes(),
+    )
+}
+
+// Only transactions which ran successfully can be looked up by their hash.
+pub fn get_transaction_by_hash(db: &sled::Db, hash: &[u8]) -> Option<IndexedTransaction> {
+    get_indexed(db, TRANSACTIONS_BY_HASH, hash)
+}
+
+fn get_indexed(db: &sled::Db, index: &str, key: &[u8]) -> Option<IndexedTransaction> {
+    let id = db.open_tree(index).unwrap().get(key).unwrap()?;
+    let transactions = db.open_tree(TRANSACTIONS).unwrap();
+    Some(serde_cbor::from_slice(&transactions.get(id).unwrap().unwrap()).unwrap())
+}
+
+// Returns the addresses and tokens an action touches other than the fee.
+fn touched(sender: Address, action: &Action) -> (Vec<Address>, Vec<Address>) {
+    match action {
+        Action::AddLiquidity(_amount, token)
+        | Action::Buy(_, token, _)
+        | Action::CreatePool(_, token, _)
+        | Action::RemoveLiquidity(_, token)
+        | Action::Sell(_, token, _) => (vec![sender], vec![*token, USD]),
+        Action::Approve(spender, _amount, token) => (vec![sender, *spender], vec![*token]),
+        Action::ApproveMultisigAction(account, _id) => (vec![sender, *account], vec![]),
+        Action::Batch(actions) => {
+            let (mut addresses, mut tokens) = (vec![sender], vec![]);
+            for action in actions {
+                let (step_addresses, step_tokens) = touched(sender, action);
+                addresses.extend(step_addresses);
+                tokens.extend(step_tokens);
+            }
+            tokens.sort();
+            tokens.dedup();
+            (addresses, tokens)
+        }
+        Action::Compound(token, _minimum_output_amount) => {
+            (vec![sender], vec![Ellipticoin::address(), *token, USD])
+        }
+        Action::Harvest => (vec![sender], vec![Ellipticoin::address()]),
+        Action::CreateMultisig(owners, _threshold) => {
+            ([vec![sender], owners.clone()].concat(), vec![])
+        }
+        Action::CreateWithdrawlRequest(_amount, token) => (vec![sender], vec![*token]),
+        Action::Pay(recipient, _amount, token) => (vec![sender, *recipient], vec![*token]),
+        Action::ProcessPolygonMessages(messages, _block_number) => {
+            let (mut addresses, mut tokens) = (vec![sender], vec![]);
+            for message in messages {
+                if let PolygonMessage::Deposit(_amount, token, address) = message {
+                    addresses.push(*address);
+                    tokens.push(*token);
+                }
+            }
+            tokens.sort();
+            tokens.dedup();
+            (addresses, tokens)
+        }
+        Action::SubmitMultisigAction(account, action) => {
+            let (addresses, tokens) = touched(*account, action);
+            ([vec![sender], addresses].concat(), tokens)
+        }
+        Action::TransferFrom(owner, recipient, _amount, token) => {
+            (vec![sender, *owner, *recipient], vec![*token])
+        }
+        _ => (vec![sender], vec![]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use ellipticoin_test_framework::constants::{
+        actors::{ALICE, BOB, CAROL},
+        tokens::APPLES,
+    };
+    use ellipticoin_types::U256;
+
+    fn temporary_db() -> sled::Db {
+        sled::Config::new().temporary(true).open().unwrap()
+    }
+
+    fn ids(transactions: Vec<IndexedTransaction>) -> Vec<u64> {
+        transactions
+            .into_iter()
+            .map(|transaction| transaction.id)
+            .collect()
+    }
+
+    #[test]
+    fn test_touched() {
+        assert_eq!(
+            touched(ALICE, &Action::Pay(BOB, U256::from(1), APPLES)),
+            (vec![ALICE, BOB], vec![APPLES])
+        );
+        assert_eq!(
+            touched(ALICE, &Action::Sell(U256::from(1), APPLES, U256::from(1))),
+            (vec![ALICE], vec![APPLES, USD])
+        );
+        assert_eq!(
+            touched(
+                CAROL,
+                &Action::SubmitMultisigAction(
+                    ALICE,
+                    Box::new(Action::Pay(BOB, U256::from(1), APPLES))
+                )
+            ),
+            (vec![CAROL, ALICE, BOB], vec![APPLES])
+        );
+        assert_eq!(
+            touched(ALICE, &Action::Harvest),
+            (vec![ALICE], vec![Ellipticoin::address()])
+        );
+        assert_eq!(
+            touched(
+                ALICE,
+                &Action::TransferFrom(BOB, CAROL, U256::from(1), APPLES)
+            ),
+            (vec![ALICE, BOB, CAROL], vec![APPLES])
+        );
+    }
+
+    #[test]
+    fn test_pagination() {
+        let db = temporary_db();
+        for transaction_number in 1..=3 {
+            insert(
+                &db,
+                vec![transaction_number as u8; 32],
+                ALICE,
+                None,
+                transaction_number,
+                0,
+                Action::Pay(BOB, U256::from(1), APPLES),
+                &Ok(transaction_number),
+            );
+        }
+        assert_eq!(ids(get_transactions(&db, ALICE, None, 2, None)), vec![2, 1]);
+        assert_eq!(ids(get_transactions(&db, ALICE, None, 2, Some(1))), vec![0]);
+        assert_eq!(
+            ids(get_transactions(&db, BOB, Some(APPLES), 10, None)),
+            vec![2, 1, 0]
+        );
+        assert_eq!(get_transaction(&db, 2).unwrap().transaction_number, 2);
+        assert_eq!(
+            get_transaction_by_hash(&db, &[3; 32])
+                .unwrap()
+                .transaction_number,
+            3
+        );
+    }
+
+    #[test]
+    fn test_cursor_bounds() {
+        let db = temporary_db();
+        insert(
+            &db,
+            vec![1; 32],
+            ALICE,
+            None,
+            1,
+            0,
+            Action::Pay(BOB, U256::from(1), APPLES),
+            &Ok(1),
+        );
+        insert(
+            &db,
+            vec![2; 32],
+            BOB,
+            None,
+            1,
+            0,
+            Action::Pay(CAROL, U256::from(1), APPLES),
+            &Err(anyhow!("Insufficient balance")),
+        );
+        assert_eq!(ids(get_transactions(&db, ALICE, None, 10, None)), vec![0]);
+        assert_eq!(ids(get_transactions(&db, ALICE, None, 10, Some(0))), vec![]);
+        assert_eq!(ids(get_transactions(&db, CAROL, None, 10, None)), vec![1]);
+        assert_eq!(
+            ids(get_transactions(&db, CAROL, Some(USD), 10, None)),
+            vec![]
+        );
+        assert_eq!(
+            ids(get_transactions(&db, BOB, None, 10, Some(u64::MAX))),
+            vec![1, 0]
+        );
+        assert!(get_transaction(&db, 2).is_none());
+        assert_eq!(get_transaction_by_hash(&db, &[1; 32]).unwrap().id, 0);
+        assert!(get_transaction_by_hash(&db, &[2; 32]).is_none());
+    }
+}
@@ -12,8 +12,10 @@ pub mod config;
 pub mod constants;
 pub mod db;
 mod error;
+mod events;
 mod hash_onion;
 mod helpers;
+mod indexer;
 mod miner;
 mod peerchains;
 mod start_up;
@@ -2,7 +2,7 @@ use ellipticoind::{
     config::{SubCommand, OPTS},
     constants::TRANSACTIONS_FILE,
     db,
-    sub_commands::{self, generate_keypair},
+    sub_commands::{self, generate_keypair, rotate_hash_onion},
 };
 use std::{io::Write, process};
 
@@ -20,6 +20,7 @@ async fn main() {
     .expect("Error setting Ctrl-C handler");
     match &OPTS.subcmd {
         Some(SubCommand::GenerateKeypair) => generate_keypair(),
+        Some(SubCommand::RotateHashOnion) => rotate_hash_onion(),
         None => sub_commands::main().await,
     }
 }
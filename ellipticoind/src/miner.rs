@@ -1,9 +1,12 @@
 use crate::{
-    constants::{BLOCK_TIME, TRANSACTION_QUEUE, WEB_SOCKET_BROADCASTER},
+    config::address,
+    constants::{TRANSACTION_QUEUE, WEB_SOCKET_BROADCASTER},
     db,
     helpers::run_for,
     transaction::{self, new_seal_transaction},
 };
+use async_std::task::sleep;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub async fn run() {
     loop {
@@ -12,17 +15,48 @@ pub async fn run() {
 }
 
 async fn mine_block() {
+    wait_for_turn().await;
     println!("Won block #{}", db::get_block_number().await);
-    run_for(*BLOCK_TIME, async {
+    run_for(Duration::from_secs(db::get_block_time().await), async {
         loop {
             TRANSACTION_QUEUE.process_next_transaction().await;
             WEB_SOCKET_BROADCASTER.broadcast().await;
         }
     })
     .await;
-    transaction::run(new_seal_transaction().await)
-        .await
-        .unwrap();
+    if let Err(err) = transaction::run(new_seal_transaction().await).await {
+        println!("Failed to seal block: {}", err);
+    }
     WEB_SOCKET_BROADCASTER.broadcast().await;
     db::flush().await;
 }
+
+// If another miner won the block wait until it has timed out and it's our turn
+// to seal.
+async fn wait_for_turn() {
+    loop {
+        let position = db::get_miners()
+            .await
+            .iter()
+            .position(|miner| miner.address == address());
+        let last_sealed_at = db::get_last_sealed_at().await;
+        match (position, last_sealed_at) {
+            (Some(0), _) => return,
+            (Some(position), Some(last_sealed_at)) => {
+                let timeout = db::get_block_time().await * db::get_miner_timeout().await;
+                if now() >= last_sealed_at + timeout * position as u64 {
+                    return;
+                }
+            }
+            _ => (),
+        }
+        sleep(Duration::from_secs(1)).await;
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
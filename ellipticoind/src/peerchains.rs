@@ -21,7 +21,7 @@ pub fn start_polling() {
     });
     spawn(async move {
         let polygon_block_number = db::get_polygon_block_number().await;
-        ellipticoin_peerchain_polygon::event_stream(polygon_block_number)
+        ellipticoin_peerchain_polygon::event_stream(polygon_block_number, db::get_bridged_tokens)
             .for_each(|(messages, block_number)| process_polygon_messages(messages, block_number))
             .await;
     });
@@ -1,27 +1,44 @@
 use crate::{
     aquire_db_write_lock,
-    config::{self, address, OPTS, SIGNER},
+    config::{self, OPTS, SIGNER},
     constants::DB,
     db, hash_onion,
     serde_cbor::Deserializer,
-    transaction::{new_start_mining_transaction, run, SignedTransaction},
+    transaction::{
+        new_rotate_hash_onion_transaction, new_start_mining_transaction, run, LoggedTransaction,
+    },
+};
+use ellipticoin_contracts::{
+    constants::HASH_ONION_ROTATION_LAYERS, Ellipticoin, Miner, Parameters, Token,
 };
-use ellipticoin_contracts::Miner;
 use ellipticoin_peerchain_ethereum::signature::eth_address;
 use std::{fs::File, path::Path};
 
 pub async fn start_miner() {
-    let start_mining_transaction = new_start_mining_transaction().await;
     let miners = db::get_miners().await;
-    if !miners
+    let miner = miners
         .iter()
-        .any(|Miner { address, .. }| address.clone() == config::address())
-    {
-        run(start_mining_transaction).await.unwrap();
+        .find(|Miner { address, .. }| address.clone() == config::address());
+    if miner.is_none() {
+        run(new_start_mining_transaction().await).await.unwrap();
         println!(
             "Started Miner: {}",
             hex::encode(eth_address(&SIGNER.verifying_key()))
         );
+    } else if hash_onion::has_pending_rotation()
+        && miner.unwrap().hash_onion_layers_left > HASH_ONION_ROTATION_LAYERS
+    {
+        println!(
+            "The new hash onion will be used once fewer than {} layers are left",
+            HASH_ONION_ROTATION_LAYERS
+        );
+    } else if hash_onion::has_pending_rotation() {
+        hash_onion::start_rotation().await;
+        run(new_rotate_hash_onion_transaction().await)
+            .await
+            .unwrap();
+        hash_onion::finish_rotation();
+        println!("Rotated Hash Onion");
     }
 }
 
@@ -33,16 +50,10 @@ pub async fn catch_up() {
         let mut last_printed_block_number = 0;
         let mut block_number = 0;
         for transaction in Deserializer::from_reader(&transacations_file)
-            .into_iter::<SignedTransaction>()
+            .into_iter::<LoggedTransaction>()
             .map(Result::unwrap)
         {
-            let result = crate::transaction::apply(&transaction).await;
-            if transaction.sender().unwrap_or(Default::default()) == address()
-                && transaction.is_seal()
-                && result.is_ok()
-            {
-                hash_onion::peel().await;
-            }
+            crate::transaction::apply(&transaction).await.ok();
             block_number = db::get_block_number().await;
             if block_number % 10000 == 0 && block_number != last_printed_block_number {
                 println!("Applied blocks #{}-#{}", block_number - 10000, block_number);
@@ -59,7 +70,7 @@ pub async fn catch_up() {
 
 pub async fn reset_state() {
     load_genesis_state().await;
-    hash_onion::generate().await;
+    hash_onion::load().await;
 }
 
 pub async fn load_genesis_state() {
@@ -68,6 +79,7 @@ pub async fn load_genesis_state() {
     let mut db = ellipticoin_types::Db {
         backend: store_lock,
         transaction_state: Default::default(),
+        savepoints: Default::default(),
     };
     let genesis_file = File::open(OPTS.genesis_state_path.clone()).expect(&format!(
         "Genesis file {} not found",
@@ -81,4 +93,9 @@ pub async fn load_genesis_state() {
         db.insert_raw(&key, &value);
         db.flush();
     }
+    Parameters::migrate(&mut db);
+    Token::migrate(&mut db);
+    Ellipticoin::migrate(&mut db);
+    db.commit();
+    db.flush();
 }
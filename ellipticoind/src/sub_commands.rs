@@ -1,4 +1,8 @@
-use crate::{api, config::socket, db, miner, peerchains, start_up};
+use crate::{
+    api,
+    config::{socket, websocket_socket},
+    db, hash_onion, miner, peerchains, start_up,
+};
 use ellipticoin_peerchain_ethereum::signature::eth_address;
 use k256::ecdsa::{SigningKey, VerifyingKey};
 use rand::rngs::OsRng;
@@ -13,12 +17,17 @@ pub fn generate_keypair() {
     println!("Signing Key: {}", hex::encode(signing_key.to_bytes()));
 }
 
+pub fn rotate_hash_onion() {
+    hash_onion::rotate();
+}
+
 pub async fn main() {
     db::initialize().await;
     start_up::reset_state().await;
     start_up::catch_up().await;
+    hash_onion::sync().await;
     start_up::start_miner().await;
     peerchains::start_polling();
-    api::start(socket()).await;
+    api::start(socket(), websocket_socket()).await;
     miner::run().await;
 }
@@ -1,14 +1,26 @@
 use crate::{
     aquire_db_read_lock, aquire_db_write_lock,
     config::{verification_key, HOST, OPTS},
-    constants::{DB, DEFAULT_GAS_LIMIT, TRANSACTIONS_FILE, TRANSACTION_QUEUE},
-    hash_onion,
+    constants::{
+        DB, DEFAULT_GAS_LIMIT, EIP_1559_TRANSACTION_TYPE, LEGACY_TRANSACTION_TYPE,
+        MAX_TIMESTAMP_DRIFT, TRANSACTIONS_FILE, TRANSACTION_QUEUE, TRANSACTION_VERSION,
+    },
+    events, hash_onion, indexer,
 };
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use ellipticoin_contracts::{
-    contract::Contract, token::tokens::CUSDC, Action, Bridge, Ellipticoin, System, Transaction, AMM,
+    contract::Contract,
+    crypto::ed25519_verify,
+    system::{LegacyAction, LegacyTransaction},
+    token::tokens::CUSDC,
+    Action, Bridge, Ellipticoin, Governance, Multisig, Parameters, System, Token, Transaction, AMM,
+};
+use ellipticoin_peerchain_ethereum::{
+    abi::{encode_action, encode_legacy_action},
+    crypto,
+    rlp::{self, Encodable, Item},
+    signature::Signature,
 };
-use ellipticoin_peerchain_ethereum::{abi::encode_action, crypto, rlp, signature::Signature};
 
 use ellipticoin_peerchain_polygon::process_withdrawl;
 use ellipticoin_types::{
@@ -17,142 +29,420 @@ use ellipticoin_types::{
     Address,
 };
 use num_bigint::BigUint;
-use num_traits::{pow, FromPrimitive};
+use num_traits::FromPrimitive;
 use serde::{Deserialize, Serialize};
-use std::convert::TryInto;
+use std::{
+    convert::TryInto,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// Ethereum transactions are signed over their RLP encoding and Ed25519
+// transactions over the CBOR encoding of the chain id and the `Transaction` so
+// that they can't be replayed on other chains. Ed25519 senders are
+// identified by the first 20 bytes of their verification key.
+//
+// Typed Ethereum transactions (EIP-2718) keep the fields which can't be derived
+// from the `Action` so that their signing data can be rebuilt. `gas_price` is
+// the max fee per gas for EIP-1559 transactions. Legacy transactions submitted
+// over JSON-RPC are stored with a transaction type of 0 so that the gas price
+// and limit they were signed with are kept too.
+//
+// A relayed transaction is signed by its sender and submitted by a relayer who
+// signs the CBOR encoding of the sender's signed transaction with Ed25519 and
+// pays its fee.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SignedTransaction {
+    Ethereum(Transaction, Signature),
+    EthereumTyped {
+        transaction_type: u8,
+        transaction: Transaction,
+        #[serde(with = "serde_bytes")]
+        max_priority_fee_per_gas: Vec<u8>,
+        #[serde(with = "serde_bytes")]
+        gas_price: Vec<u8>,
+        #[serde(with = "serde_bytes")]
+        gas_limit: Vec<u8>,
+        #[serde(with = "serde_bytes")]
+        access_list: Vec<u8>,
+        signature: Signature,
+    },
+    Ed25519 {
+        transaction: Transaction,
+        verification_key: [u8; 32],
+        #[serde(with = "serde_bytes")]
+        signature: Vec<u8>,
+    },
+    Relayed {
+        transaction: Box<SignedTransaction>,
+        relayer_verification_key: [u8; 32],
+        #[serde(with = "serde_bytes")]
+        relayer_signature: Vec<u8>,
+    },
+}
+
+// Transactions are logged with a version so that the format of the log can
+// change. Transactions logged before versioning are legacy transactions.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LoggedTransaction {
+    Versioned {
+        version: u8,
+        transaction: SignedTransaction,
+    },
+    Legacy(LegacySignedTransaction),
+}
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct SignedTransaction(pub Transaction, pub Signature);
+pub struct LegacySignedTransaction(pub LegacyTransaction, pub Signature);
 
 pub async fn sign(action: Action) -> SignedTransaction {
     let mut db = aquire_db_read_lock!();
-    let mut transaction: SignedTransaction = SignedTransaction(
+    let mut transaction: SignedTransaction = SignedTransaction::Ethereum(
         Transaction {
             transaction_number: System::get_next_transaction_number(&mut db, verification_key()),
             action,
         },
         Default::default(),
     );
-    transaction.sign();
+    transaction.sign(&mut db);
     transaction
 }
 
 impl SignedTransaction {
-    fn sign(&mut self) {
-        self.1 = crypto::sign(&self.signing_data())
+    // Transactions aren't assigned an id until they've run so they're
+    // identified by the hash of their encoding everywhere, including over
+    // JSON-RPC and GraphQL.
+    pub fn hash(&self) -> Vec<u8> {
+        crypto::keccak256(&serde_cbor::to_vec(self).unwrap())
     }
 
-    fn signing_data(&self) -> Vec<u8> {
-        rlp::encode(vec![
-            BigUint::from_u64(self.0.transaction_number)
-                .unwrap()
-                .to_bytes_be()
-                .to_vec(),
-            vec![],
-            BigUint::from_u64(self.gas_limit())
-                .unwrap()
-                .to_bytes_be()
-                .to_vec(),
-            self.to().unwrap().0.to_vec(),
-            self.value(),
-            self.data(),
-            BigUint::from_u64(OPTS.chain_id)
-                .unwrap()
-                .to_bytes_be()
-                .to_vec(),
-            vec![],
-            vec![],
-        ])
+    pub fn transaction(&self) -> &Transaction {
+        match self {
+            SignedTransaction::Ethereum(transaction, _signature) => transaction,
+            SignedTransaction::EthereumTyped { transaction, .. } => transaction,
+            SignedTransaction::Ed25519 { transaction, .. } => transaction,
+            SignedTransaction::Relayed { transaction, .. } => transaction.transaction(),
+        }
+    }
+
+    fn sign<B: Backend>(&mut self, db: &mut Db<B>) {
+        let signing_data = self.signing_data(db, OPTS.chain_id).unwrap();
+        if let SignedTransaction::Ethereum(_transaction, signature) = self {
+            *signature = crypto::sign(&signing_data)
+        }
+    }
+
+    fn signing_data<B: Backend>(&self, db: &mut Db<B>, chain_id: u64) -> Result<Vec<u8>> {
+        match self {
+            SignedTransaction::EthereumTyped {
+                transaction_type,
+                gas_price,
+                gas_limit,
+                ..
+            } if *transaction_type == LEGACY_TRANSACTION_TYPE => self.legacy_signing_data(
+                db,
+                chain_id,
+                gas_price.to_rlp_item(),
+                gas_limit.to_rlp_item(),
+            ),
+            SignedTransaction::EthereumTyped {
+                transaction_type,
+                max_priority_fee_per_gas,
+                gas_price,
+                gas_limit,
+                access_list,
+                ..
+            } => {
+                let mut fields = vec![
+                    chain_id.to_rlp_item(),
+                    self.transaction().transaction_number.to_rlp_item(),
+                ];
+                if *transaction_type == EIP_1559_TRANSACTION_TYPE {
+                    fields.push(max_priority_fee_per_gas.to_rlp_item());
+                }
+                fields.extend(vec![
+                    gas_price.to_rlp_item(),
+                    gas_limit.to_rlp_item(),
+                    to(&self.transaction().action)?.to_rlp_item(),
+                    value(&self.transaction().action).to_rlp_item(),
+                    data(db, &self.transaction().action)?.to_rlp_item(),
+                    rlp::decode_item(access_list)?,
+                ]);
+                Ok([vec![*transaction_type], rlp::encode(&fields)].concat())
+            }
+            _ => self.legacy_signing_data(
+                db,
+                chain_id,
+                Item::Bytes(vec![]),
+                self.gas_limit().to_rlp_item(),
+            ),
+        }
+    }
+
+    fn legacy_signing_data<B: Backend>(
+        &self,
+        db: &mut Db<B>,
+        chain_id: u64,
+        gas_price: Item,
+        gas_limit: Item,
+    ) -> Result<Vec<u8>> {
+        Ok(rlp::encode(&vec![
+            self.transaction().transaction_number.to_rlp_item(),
+            gas_price,
+            gas_limit,
+            to(&self.transaction().action)?.to_rlp_item(),
+            value(&self.transaction().action).to_rlp_item(),
+            data(db, &self.transaction().action)?.to_rlp_item(),
+            chain_id.to_rlp_item(),
+            Item::Bytes(vec![]),
+            Item::Bytes(vec![]),
+        ]))
     }
 
     fn gas_limit(&self) -> u64 {
-        if matches!(&self.0.action, Action::Pay(..)) {
+        if matches!(&self.transaction().action, Action::Pay(..)) {
             DEFAULT_GAS_LIMIT
         } else {
             DEFAULT_GAS_LIMIT
         }
     }
-    fn to(&self) -> Option<Address> {
-        Some(match &self.0.action {
-            Action::Pay(recipient, _amount, token) => {
-                if *token == CUSDC {
-                    *recipient
-                } else {
-                    *token
-                }
+
+    pub fn sender<B: Backend>(&self, db: &mut Db<B>) -> anyhow::Result<Address> {
+        self.recover_address(db)
+    }
+
+    pub fn run<B: Backend>(&self, db: &mut Db<B>) -> anyhow::Result<u64> {
+        let sender = self.sender(db)?;
+        self.check_fee_cap(db, sender)?;
+        if let SignedTransaction::Relayed { .. } = self {
+            let relayer = self.relayer()?;
+            self.transaction().run_relayed(db, sender, relayer)
+        } else {
+            self.transaction().run(db, sender)
+        }
+    }
+}
+
+impl SignedTransaction {
+    // Ethereum wallets sign the most they're willing to pay in fees as a gas
+    // limit and a gas price.
+    fn check_fee_cap<B: Backend>(&self, db: &mut Db<B>, sender: Address) -> Result<()> {
+        if let SignedTransaction::EthereumTyped {
+            gas_price,
+            gas_limit,
+            ..
+        } = self
+        {
+            let fee_token = Parameters::get_fee_token(db);
+            let fee = System::fee(db, sender, &self.transaction().action)?;
+            let underlying_fee = BigUint::from(Token::amount_to_underlying(db, fee, fee_token)?.0);
+            let fee_cap = BigUint::from_bytes_be(gas_limit) * BigUint::from_bytes_be(gas_price);
+            if underlying_fee > fee_cap {
+                bail!(
+                    "Fee of {} is more than the signed maximum of {}",
+                    underlying_fee,
+                    fee_cap
+                )
             }
-            Action::CreatePool(..) => AMM::address(),
-            Action::AddLiquidity(..) => AMM::address(),
-            Action::Buy(..) => AMM::address(),
-            Action::RemoveLiquidity(..) => AMM::address(),
-            Action::CreateWithdrawlRequest(..) => Bridge::address(),
-            Action::Seal(..) => Ellipticoin::address(),
-            Action::Sell(..) => AMM::address(),
-            Action::StartMining(..) => Ellipticoin::address(),
-            Action::ProcessEthereumMessages(..) => Bridge::address(),
-            Action::ProcessPolygonMessages(..) => Bridge::address(),
-            Action::Null => return None, // action => return Err(anyhow!("{:?} invalid action", action.clone())),
-        })
+        }
+        Ok(())
     }
 
-    fn data(&self) -> Vec<u8> {
-        match &self.0.action {
-            Action::Pay(_recipient, _amount, token) => {
-                if *token == CUSDC {
-                    vec![]
-                } else {
-                    encode_action(&self.0.action)
-                    
+    fn is_withdrawl(&self) -> bool {
+        matches!(
+            self.transaction().action,
+            Action::CreateWithdrawlRequest(_, _)
+        )
+    }
+
+    pub fn is_seal(&self) -> bool {
+        matches!(
+            self.transaction().action,
+            Action::Seal(_) | Action::SealAt(..)
+        )
+    }
+
+    pub fn recover_address<B: Backend>(&self, db: &mut Db<B>) -> Result<Address> {
+        self.recover_address_on_chain(db, OPTS.chain_id)
+    }
+
+    pub(crate) fn recover_address_on_chain<B: Backend>(
+        &self,
+        db: &mut Db<B>,
+        chain_id: u64,
+    ) -> Result<Address> {
+        match self {
+            SignedTransaction::Ethereum(_transaction, signature)
+            | SignedTransaction::EthereumTyped { signature, .. } => {
+                signature.recover_address(&self.signing_data(db, chain_id)?)
+            }
+            SignedTransaction::Ed25519 {
+                transaction,
+                verification_key,
+                signature,
+            } => ed25519_sender(chain_id, transaction, verification_key, signature),
+            SignedTransaction::Relayed { transaction, .. } => {
+                if let SignedTransaction::Relayed { .. } = **transaction {
+                    bail!("Relayed transactions can't be relayed")
                 }
+                transaction.recover_address_on_chain(db, chain_id)
             }
-            _ => encode_action(&self.0.action),
         }
     }
 
-    fn value(&self) -> Vec<u8> {
-        match &self.0.action {
-            Action::Pay(_recipient, amount, token) => {
-                if *token == CUSDC {
-                    (BigUint::from(amount) * BigUint::from(pow(BigUint::from(10u32), 12)))
-                        .to_bytes_be()
-                } else {
-                    vec![]
+    pub fn relayer(&self) -> Result<Address> {
+        match self {
+            SignedTransaction::Relayed {
+                transaction,
+                relayer_verification_key,
+                relayer_signature,
+            } => {
+                if relayer_signature.len() != 64 {
+                    bail!("Invalid Signature")
                 }
+                ed25519_verify(
+                    &serde_cbor::to_vec(transaction)?,
+                    relayer_verification_key,
+                    relayer_signature,
+                )?;
+                Ok(Address(relayer_verification_key[..20].try_into()?))
             }
-            _ => vec![],
+            _ => bail!("Transaction isn't relayed"),
         }
     }
+}
 
-    pub fn sender(&self) -> anyhow::Result<Address> {
-        Ok(self.recover_address().unwrap())
+// Actions without an ABI encoding can't be signed as Ethereum transactions.
+pub fn to(action: &Action) -> Result<Address> {
+    Ok(match action {
+        Action::Pay(recipient, _amount, token) => {
+            if *token == CUSDC {
+                *recipient
+            } else {
+                *token
+            }
+        }
+        Action::CreatePool(..) => AMM::address(),
+        Action::AddLiquidity(..) => AMM::address(),
+        Action::AddToken(..) => Token::address(),
+        Action::Approve(_spender, _amount, token) => *token,
+        Action::ApproveMultisigAction(..) => Multisig::address(),
+        Action::Batch(..) => System::address(),
+        Action::CancelProposal(..) => Governance::address(),
+        Action::ExecuteProposal(..) => Governance::address(),
+        Action::Compound(..) => Ellipticoin::address(),
+        Action::CreateMultisig(..) => Multisig::address(),
+        Action::Harvest => Ellipticoin::address(),
+        Action::Delegate(..) => Governance::address(),
+        Action::Undelegate => Governance::address(),
+        Action::Vote(..) => Governance::address(),
+        Action::WithdrawBond => Ellipticoin::address(),
+        Action::WithdrawVote(..) => Governance::address(),
+        Action::Buy(..) => AMM::address(),
+        Action::RemoveLiquidity(..) => AMM::address(),
+        Action::CreateWithdrawlRequest(..) => Bridge::address(),
+        Action::RotateHashOnion(..) => Ellipticoin::address(),
+        Action::Seal(..) => Ellipticoin::address(),
+        Action::SealAt(..) => Ellipticoin::address(),
+        Action::SetParameter(..) => Parameters::address(),
+        Action::Sell(..) => AMM::address(),
+        Action::StartMining(..) => Ellipticoin::address(),
+        Action::StopMining => Ellipticoin::address(),
+        Action::SubmitMultisigAction(..) => Multisig::address(),
+        Action::TransferFrom(_owner, _recipient, _amount, token) => *token,
+        Action::ProcessEthereumMessages(..) => Bridge::address(),
+        Action::ProcessPolygonMessages(..) => Bridge::address(),
+        Action::Null => bail!("{:?} has no Ethereum encoding", Action::Null),
+    })
+}
+
+pub fn data<B: Backend>(db: &mut Db<B>, action: &Action) -> Result<Vec<u8>> {
+    match action {
+        Action::Pay(_recipient, _amount, token) if *token == CUSDC => Ok(vec![]),
+        action => {
+            encode_action(db, action).map_err(|_| anyhow!("{:?} has no Ethereum encoding", action))
+        }
     }
+}
 
-    pub fn run<B: Backend>(&self, db: &mut Db<B>) -> anyhow::Result<u64> {
-        self.0.run(db, self.sender()?)
+pub fn value(action: &Action) -> Vec<u8> {
+    match action {
+        Action::Pay(_recipient, amount, token) => {
+            if *token == CUSDC {
+                BigUint::from(*amount).to_bytes_be()
+            } else {
+                vec![]
+            }
+        }
+        _ => vec![],
     }
 }
 
-impl SignedTransaction {
-    fn is_withdrawl(&self) -> bool {
-        matches!(self.0.action, Action::CreateWithdrawlRequest(_, _))
+fn ed25519_sender(
+    chain_id: u64,
+    transaction: &Transaction,
+    verification_key: &[u8; 32],
+    signature: &[u8],
+) -> Result<Address> {
+    if signature.len() != 64 {
+        bail!("Invalid Signature")
     }
+    ed25519_verify(
+        &serde_cbor::to_vec(&(chain_id, transaction))?,
+        verification_key,
+        signature,
+    )?;
+    Ok(Address(verification_key[..20].try_into()?))
+}
 
-    pub fn is_seal(&self) -> bool {
-        matches!(self.0.action, Action::Seal(_))
+impl LegacySignedTransaction {
+    pub fn hash(&self) -> Vec<u8> {
+        crypto::keccak256(&serde_cbor::to_vec(self).unwrap())
     }
 
-    pub fn recover_address(&self) -> Result<Address> {
-        self.1.recover_address(&self.signing_data())
+    // Legacy transactions were signed with the RLP encoding of a legacy
+    // Ethereum transaction and their amounts in the legacy ABI encoding.
+    pub fn sender<B: Backend>(&self, db: &mut Db<B>) -> Result<Address> {
+        let LegacySignedTransaction(legacy_transaction, signature) = self;
+        let transaction = SignedTransaction::Ethereum(
+            Transaction {
+                transaction_number: legacy_transaction.transaction_number,
+                action: legacy_transaction.action.upgrade(db)?,
+            },
+            Default::default(),
+        );
+        let data = match &legacy_transaction.action {
+            LegacyAction::Pay(_recipient, _amount, token) if *token == CUSDC => vec![],
+            action => encode_legacy_action(db, action)
+                .map_err(|_| anyhow!("{:?} has no Ethereum encoding", action))?,
+        };
+        signature.recover_address(&rlp::encode(&vec![
+            legacy_transaction.transaction_number.to_rlp_item(),
+            Item::Bytes(vec![]),
+            DEFAULT_GAS_LIMIT.to_rlp_item(),
+            to(&transaction.transaction().action)?.to_rlp_item(),
+            value(&transaction.transaction().action).to_rlp_item(),
+            data.to_rlp_item(),
+            OPTS.chain_id.to_rlp_item(),
+            Item::Bytes(vec![]),
+            Item::Bytes(vec![]),
+        ]))
+    }
+
+    pub fn run<B: Backend>(&self, db: &mut Db<B>) -> Result<u64> {
+        let sender = self.sender(db)?;
+        self.0.run(db, sender)
     }
 }
 
 impl Run for SignedTransaction {
-    fn sender(&self) -> Result<Address> {
-        self.sender()
+    fn sender<B: Backend>(&self, db: &mut Db<B>) -> Result<Address> {
+        self.sender(db)
     }
 
     fn run<B: Backend>(&self, db: &mut Db<B>) -> Result<u64> {
-        self.0.action.run(db, self.sender()?)
+        SignedTransaction::run(self, db)
     }
 }
 
@@ -162,38 +452,105 @@ pub async fn dispatch(signed_transaction: SignedTransaction) -> Result<u64, anyh
 }
 
 pub async fn run(transaction: SignedTransaction) -> Result<u64> {
+    validate_timestamps(&transaction.transaction().action)?;
     let mut db = aquire_db_write_lock!();
     let result = transaction.run(&mut db);
     if transaction.is_withdrawl() && result.is_ok() {
         let pending_withdrawls = Bridge::get_pending_withdrawls(&mut db);
-        process_withdrawl(pending_withdrawls.last().unwrap()).await;
+        let pending_withdrawl = pending_withdrawls.last().unwrap();
+        let native_amount =
+            Token::amount_to_native(&mut db, pending_withdrawl.amount, pending_withdrawl.token)?;
+        process_withdrawl(pending_withdrawl, native_amount).await;
     }
     if result.is_ok() {
         db.commit();
     } else {
         db.revert();
     }
+    indexer::index_transaction(&mut db, &transaction, &result);
+    if let Ok(transaction_id) = result {
+        let sender = transaction.sender(&mut db)?;
+        let hash = transaction.hash();
+        let logs = events::transfer_logs(
+            &mut db,
+            transaction_id,
+            &hash,
+            sender,
+            &transaction.transaction().action,
+        );
+        events::broadcast_transaction(transaction_id, hash, logs);
+    }
     let transacations_file = TRANSACTIONS_FILE.write().await;
-    serde_cbor::to_writer(&*transacations_file, &transaction).unwrap();
+    serde_cbor::to_writer(
+        &*transacations_file,
+        &LoggedTransaction::Versioned {
+            version: TRANSACTION_VERSION,
+            transaction,
+        },
+    )
+    .unwrap();
 
     result
 }
 
-pub async fn apply(transaction: &SignedTransaction) -> Result<()> {
+// Block timestamps can't be checked against the clock when the log is replayed
+// so they're checked when the seal is received. Rejected seals aren't logged.
+fn validate_timestamps(action: &Action) -> Result<()> {
+    match action {
+        Action::SealAt(_onion_skin, timestamp) => {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let timestamp = u64::from(*timestamp);
+            if timestamp > now + MAX_TIMESTAMP_DRIFT || timestamp + MAX_TIMESTAMP_DRIFT < now {
+                bail!(
+                    "Block timestamp {} is more than {} seconds from the current time {}",
+                    timestamp,
+                    MAX_TIMESTAMP_DRIFT,
+                    now
+                )
+            }
+            Ok(())
+        }
+        Action::Batch(actions) => actions.iter().try_for_each(validate_timestamps),
+        Action::SubmitMultisigAction(_account, action) => validate_timestamps(action),
+        _ => Ok(()),
+    }
+}
+
+pub async fn apply(logged_transaction: &LoggedTransaction) -> Result<()> {
     let backend = DB.get().unwrap().write().await;
     let store_lock = crate::db::StoreLock { guard: backend };
     let mut db = ellipticoin_types::Db {
         backend: store_lock,
         transaction_state: Default::default(),
+        savepoints: Default::default(),
     };
-    let result = transaction.run(&mut db);
-    if result.is_ok() {
-        db.commit();
-    } else {
-        db.revert();
+    match logged_transaction {
+        LoggedTransaction::Versioned {
+            version,
+            transaction,
+        } => {
+            if *version != TRANSACTION_VERSION {
+                bail!("Unknown transaction version {}", version)
+            }
+            let result = transaction.run(&mut db);
+            if result.is_ok() {
+                db.commit();
+            } else {
+                db.revert();
+            }
+            indexer::index_transaction(&mut db, transaction, &result);
+        }
+        LoggedTransaction::Legacy(transaction) => {
+            let result = transaction.run(&mut db);
+            if result.is_ok() {
+                db.commit();
+            } else {
+                db.revert();
+            }
+            indexer::index_legacy_transaction(&mut db, transaction, &result);
+        }
     }
 
-    drop(result);
     Ok(())
 }
 
@@ -206,6 +563,147 @@ pub async fn new_start_mining_transaction() -> SignedTransaction {
     .await
 }
 
+pub async fn new_rotate_hash_onion_transaction() -> SignedTransaction {
+    sign(Action::RotateHashOnion(
+        hash_onion::peel().await,
+        hash_onion::layers_left().await.try_into().unwrap(),
+    ))
+    .await
+}
+
 pub async fn new_seal_transaction() -> SignedTransaction {
-    sign(Action::Seal(hash_onion::peel().await)).await
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    sign(Action::SealAt(
+        hash_onion::peel().await,
+        timestamp.try_into().unwrap(),
+    ))
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_zebra::{SigningKey, VerificationKey};
+    use ellipticoin_test_framework::{
+        constants::{actors::BOB, tokens::APPLES},
+        new_db, setup,
+    };
+    use ellipticoin_types::{Uint, U256};
+    use maplit::hashmap;
+    use std::convert::TryFrom;
+
+    fn ed25519_transaction(chain_id: u64, transaction: Transaction) -> SignedTransaction {
+        let signing_key = SigningKey::from([1; 32]);
+        let signature = signing_key.sign(&serde_cbor::to_vec(&(chain_id, &transaction)).unwrap());
+        SignedTransaction::Ed25519 {
+            transaction,
+            verification_key: VerificationKey::from(&signing_key).into(),
+            signature: <[u8; 64]>::from(signature).to_vec(),
+        }
+    }
+
+    fn pay_transaction() -> Transaction {
+        Transaction {
+            transaction_number: 1,
+            action: Action::Pay(BOB, U256::from(20), APPLES),
+        }
+    }
+
+    #[test]
+    fn test_ed25519_cbor_round_trip() {
+        let signed_transaction = ed25519_transaction(24, pay_transaction());
+        let bytes = serde_cbor::to_vec(&signed_transaction).unwrap();
+        let decoded: SignedTransaction = serde_cbor::from_slice(&bytes).unwrap();
+        assert!(matches!(decoded, SignedTransaction::Ed25519 { .. }));
+        assert_eq!(serde_cbor::to_vec(&decoded).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_ed25519_sender() {
+        let signing_key = SigningKey::from([1; 32]);
+        let verification_key: [u8; 32] = VerificationKey::from(&signing_key).into();
+        if let SignedTransaction::Ed25519 {
+            transaction,
+            verification_key: signed_verification_key,
+            signature,
+        } = ed25519_transaction(24, pay_transaction())
+        {
+            assert_eq!(
+                ed25519_sender(24, &transaction, &signed_verification_key, &signature).unwrap(),
+                Address(verification_key[..20].try_into().unwrap())
+            );
+            assert!(ed25519_sender(1, &transaction, &signed_verification_key, &signature).is_err());
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn ethereum_transaction(gas_limit: u64) -> SignedTransaction {
+        SignedTransaction::EthereumTyped {
+            transaction_type: EIP_1559_TRANSACTION_TYPE,
+            transaction: pay_transaction(),
+            max_priority_fee_per_gas: vec![],
+            gas_price: BigUint::from(1_000_000_000u64).to_bytes_be(),
+            gas_limit: BigUint::from(gas_limit).to_bytes_be(),
+            access_list: vec![0xc0],
+            signature: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_fee_cap() {
+        let mut db = new_db();
+        setup(&mut db, hashmap! {});
+        assert!(ethereum_transaction(21_000)
+            .check_fee_cap(&mut db, BOB)
+            .is_ok());
+        assert_eq!(
+            ethereum_transaction(20_999)
+                .check_fee_cap(&mut db, BOB)
+                .err()
+                .unwrap()
+                .to_string(),
+            "Fee of 21000000000000 is more than the signed maximum of 20999000000000"
+        );
+    }
+
+    #[test]
+    fn test_logged_transactions() {
+        let bytes = serde_cbor::to_vec(&LoggedTransaction::Versioned {
+            version: TRANSACTION_VERSION,
+            transaction: ed25519_transaction(24, pay_transaction()),
+        })
+        .unwrap();
+        assert!(matches!(
+            serde_cbor::from_slice::<LoggedTransaction>(&bytes).unwrap(),
+            LoggedTransaction::Versioned {
+                version: TRANSACTION_VERSION,
+                transaction: SignedTransaction::Ed25519 { .. },
+            }
+        ));
+    }
+
+    #[test]
+    fn test_legacy_logged_transactions() {
+        let bytes = serde_cbor::to_vec(&LegacySignedTransaction(
+            LegacyTransaction {
+                transaction_number: 1,
+                action: LegacyAction::Pay(BOB, Uint::try_from(20u64).unwrap(), APPLES),
+            },
+            Default::default(),
+        ))
+        .unwrap();
+        match serde_cbor::from_slice::<LoggedTransaction>(&bytes).unwrap() {
+            LoggedTransaction::Legacy(LegacySignedTransaction(transaction, _signature)) => {
+                assert_eq!(
+                    transaction.action,
+                    LegacyAction::Pay(BOB, Uint::try_from(20u64).unwrap(), APPLES)
+                )
+            }
+            _ => panic!("Expected a legacy transaction"),
+        }
+    }
 }